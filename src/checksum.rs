@@ -0,0 +1,426 @@
+use crate::error::{FileSplitError, Result};
+use crate::manifest::to_hex;
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+/// 読み込み用バッファのサイズ（バイト単位）
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// チェックサムファイルの形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChecksumFormat {
+    /// SFV形式（CRC32）
+    Sfv,
+    /// md5sum形式
+    Md5,
+    /// sha256sum形式
+    Sha256,
+}
+
+impl ChecksumFormat {
+    /// すべての形式
+    pub const ALL: [ChecksumFormat; 3] = [Self::Sfv, Self::Md5, Self::Sha256];
+
+    /// 文字列から形式を解析する
+    ///
+    /// # 引数
+    /// * `name` - 形式名（sfv, md5, sha256）
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "sfv" | "crc32" => Ok(Self::Sfv),
+            "md5" => Ok(Self::Md5),
+            "sha256" | "sha-256" => Ok(Self::Sha256),
+            _ => Err(FileSplitError::InvalidChecksumFormat(name.to_string())),
+        }
+    }
+
+    /// 元ファイルのパスに対応するチェックサムファイルのパスを生成する
+    ///
+    /// # 戻り値
+    /// 例: "file.txt" -> "file.txt.sfv", "file.txt.md5", "file.txt.SHA256SUMS"
+    pub fn path_for(&self, base_path: &Path) -> PathBuf {
+        let extension = match self {
+            Self::Sfv => "sfv",
+            Self::Md5 => "md5",
+            Self::Sha256 => "SHA256SUMS",
+        };
+        let mut path = base_path.to_path_buf();
+        let file_name = format!(
+            "{}.{extension}",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        );
+        path.set_file_name(file_name);
+        path
+    }
+
+    /// データのチェックサムを計算する
+    ///
+    /// # 戻り値
+    /// チェックサムの16進数文字列（SFVは大文字、それ以外は小文字）
+    pub fn digest(&self, data: &[u8]) -> String {
+        let mut hasher = MultiHasher::new();
+        hasher.update(data);
+        hasher.finish(*self)
+    }
+
+    /// ファイルのチェックサムを計算する
+    pub fn digest_file(&self, path: &Path) -> Result<String> {
+        Ok(hash_file(path)?.finish(*self))
+    }
+
+    /// チェックサムファイルの1行を生成する
+    fn format_line(&self, name: &str, digest: &str) -> String {
+        match self {
+            Self::Sfv => format!("{name} {digest}\n"),
+            Self::Md5 | Self::Sha256 => format!("{digest}  {name}\n"),
+        }
+    }
+
+    /// チェックサムファイルの1行を解析する
+    ///
+    /// # 戻り値
+    /// ファイル名とチェックサムの組。空行・コメント行の場合はNone
+    fn parse_line<'a>(&self, line: &'a str) -> Option<(&'a str, &'a str)> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.trim().is_empty() {
+            return None;
+        }
+        match self {
+            Self::Sfv => {
+                if line.starts_with(';') {
+                    return None;
+                }
+                let (name, digest) = line.rsplit_once(' ')?;
+                Some((name.trim_end(), digest))
+            }
+            Self::Md5 | Self::Sha256 => {
+                if line.starts_with('#') {
+                    return None;
+                }
+                // "<ハッシュ>  <名前>"（テキスト）または "<ハッシュ> *<名前>"（バイナリ）
+                let (digest, name) = line.split_once(' ')?;
+                let name = name.strip_prefix([' ', '*']).unwrap_or(name);
+                Some((name, digest))
+            }
+        }
+    }
+}
+
+/// ファイルを1回だけ読み込んですべての形式のチェックサムを計算する
+pub(crate) fn hash_file(path: &Path) -> Result<MultiHasher> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut hasher = MultiHasher::new();
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    loop {
+        let bytes_read = reader.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
+    Ok(hasher)
+}
+
+/// すべての形式のチェックサムを同時に計算するハッシャー
+#[derive(Clone)]
+pub(crate) struct MultiHasher {
+    crc32: crc32fast::Hasher,
+    md5: Md5,
+    sha256: Sha256,
+}
+
+impl fmt::Debug for MultiHasher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MultiHasher").finish_non_exhaustive()
+    }
+}
+
+impl MultiHasher {
+    pub(crate) fn new() -> Self {
+        Self {
+            crc32: crc32fast::Hasher::new(),
+            md5: Md5::new(),
+            sha256: Sha256::new(),
+        }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        self.crc32.update(data);
+        self.md5.update(data);
+        self.sha256.update(data);
+    }
+
+    pub(crate) fn finish(self, format: ChecksumFormat) -> String {
+        match format {
+            ChecksumFormat::Sfv => format!("{:08X}", self.crc32.finalize()),
+            ChecksumFormat::Md5 => to_hex(&self.md5.finalize()),
+            ChecksumFormat::Sha256 => to_hex(&self.sha256.finalize()),
+        }
+    }
+}
+
+/// チェックサムファイルを作成する
+///
+/// # 引数
+/// * `base_path` - 元ファイルのパス（チェックサムファイルは同じディレクトリに作成する）
+/// * `files` - チェックサムを記録するファイル（元ファイルと同じディレクトリにあること）
+/// * `formats` - 作成するチェックサムファイルの形式
+///
+/// # 戻り値
+/// 作成したチェックサムファイルのパス
+pub fn write_sidecars(
+    base_path: &Path,
+    files: &[PathBuf],
+    formats: &[ChecksumFormat],
+) -> Result<Vec<PathBuf>> {
+    // 各ファイルを1回だけ読み込んですべての形式のチェックサムを計算する
+    let entries = files
+        .iter()
+        .map(|file| {
+            let name = file.file_name().unwrap_or_default().to_string_lossy();
+            Ok((name.to_string(), hash_file(file)?))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    write_sidecars_with(base_path, &entries, formats)
+}
+
+/// 計算済みのチェックサムからチェックサムファイルを作成する
+///
+/// # 引数
+/// * `base_path` - 元ファイルのパス（チェックサムファイルは同じディレクトリに作成する）
+/// * `entries` - 記録する名前とチェックサムの組（記録する順）
+/// * `formats` - 作成するチェックサムファイルの形式
+///
+/// # 戻り値
+/// 作成したチェックサムファイルのパス
+pub(crate) fn write_sidecars_with(
+    base_path: &Path,
+    entries: &[(String, MultiHasher)],
+    formats: &[ChecksumFormat],
+) -> Result<Vec<PathBuf>> {
+    let mut contents: Vec<String> = formats
+        .iter()
+        .map(|format| match format {
+            ChecksumFormat::Sfv => {
+                format!(
+                    "; Generated by filesplit-rs {}\n",
+                    env!("CARGO_PKG_VERSION")
+                )
+            }
+            _ => String::new(),
+        })
+        .collect();
+
+    for (name, hasher) in entries {
+        for (format, content) in formats.iter().zip(&mut contents) {
+            content.push_str(&format.format_line(name, &hasher.clone().finish(*format)));
+        }
+    }
+
+    let mut written = Vec::new();
+    for (format, content) in formats.iter().zip(contents) {
+        let path = format.path_for(base_path);
+        fs::write(&path, content)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// 元ファイルと同じディレクトリにあるチェックサムファイルの記録
+///
+/// 結合時に各分割ファイルのチェックサムを確認するために使用する
+#[derive(Debug, Default)]
+pub struct Sidecars {
+    /// 形式・チェックサムファイルのパス・ファイル名ごとのチェックサムの組
+    entries: Vec<(ChecksumFormat, PathBuf, HashMap<String, String>)>,
+}
+
+impl Sidecars {
+    /// 元ファイルに対応するチェックサムファイルをすべて読み込む
+    ///
+    /// # 引数
+    /// * `base_path` - 元ファイルのパス
+    ///
+    /// # 戻り値
+    /// 読み込んだ記録（チェックサムファイルがない場合は空）
+    pub fn load(base_path: &Path) -> Result<Self> {
+        let mut entries = Vec::new();
+        for format in ChecksumFormat::ALL {
+            let path = format.path_for(base_path);
+            if !path.exists() {
+                continue;
+            }
+            let content = fs::read_to_string(&path)?;
+            let digests = content
+                .lines()
+                .filter_map(|line| format.parse_line(line))
+                .map(|(name, digest)| (name.to_string(), digest.to_ascii_lowercase()))
+                .collect();
+            entries.push((format, path, digests));
+        }
+        Ok(Self { entries })
+    }
+
+    /// チェックサムファイルが1つもないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// ファイル名がいずれかのチェックサムファイルに記録されているかどうか
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries
+            .iter()
+            .any(|(_, _, digests)| digests.contains_key(name))
+    }
+
+    /// データのチェックサムが記録と一致するか確認する
+    ///
+    /// # 引数
+    /// * `path` - 確認するファイルのパス（ファイル名で記録を探す）
+    /// * `data` - ファイルの内容
+    ///
+    /// # 戻り値
+    /// 記録と一致しないチェックサムがあればエラー（記録がない場合は確認しない）
+    pub fn check(&self, path: &Path, data: &[u8]) -> Result<()> {
+        let mut hasher = MultiHasher::new();
+        hasher.update(data);
+        self.check_hasher(path, &hasher)
+    }
+
+    /// 計算済みのチェックサムが記録と一致するか確認する
+    ///
+    /// 結合結果のように、書き込みながらチェックサムを計算する場合に使用する
+    pub(crate) fn check_hasher(&self, path: &Path, hasher: &MultiHasher) -> Result<()> {
+        self.check_with(path, |format| Ok(hasher.clone().finish(format)))
+    }
+
+    /// ファイルのチェックサムが記録と一致するか確認する
+    ///
+    /// # 引数
+    /// * `path` - 記録を探すファイルのパス（ファイル名で記録を探す）
+    /// * `actual` - チェックサムを計算するファイルのパス（書き込み中の一時ファイルなど）
+    pub fn check_file(&self, path: &Path, actual: &Path) -> Result<()> {
+        self.check_with(path, |format| format.digest_file(actual))
+    }
+
+    /// 各形式の記録とチェックサムを比較する
+    fn check_with<F>(&self, path: &Path, mut digest: F) -> Result<()>
+    where
+        F: FnMut(ChecksumFormat) -> Result<String>,
+    {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        for (format, sidecar, digests) in &self.entries {
+            let Some(expected) = digests.get(name.as_ref()) else {
+                continue;
+            };
+            if digest(*format)?.to_ascii_lowercase() != *expected {
+                return Err(FileSplitError::CorruptedPart(format!(
+                    "{} ({} の記録とチェックサムが一致しません)",
+                    path.display(),
+                    sidecar.display()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_format() {
+        // 形式名の解析をテスト
+        assert_eq!(ChecksumFormat::parse("sfv").unwrap(), ChecksumFormat::Sfv);
+        assert_eq!(ChecksumFormat::parse("MD5").unwrap(), ChecksumFormat::Md5);
+        assert_eq!(
+            ChecksumFormat::parse("sha256").unwrap(),
+            ChecksumFormat::Sha256
+        );
+        assert!(ChecksumFormat::parse("sha1").is_err());
+    }
+
+    #[test]
+    fn test_digest() {
+        // 各形式のチェックサム計算をテスト
+        assert_eq!(ChecksumFormat::Sfv.digest(b"abc"), "352441C2");
+        assert_eq!(
+            ChecksumFormat::Md5.digest(b"abc"),
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+        assert_eq!(
+            ChecksumFormat::Sha256.digest(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_parse_line() {
+        // チェックサムファイルの行の解析をテスト
+        assert_eq!(
+            ChecksumFormat::Sfv.parse_line("data.bin.001 352441C2"),
+            Some(("data.bin.001", "352441C2"))
+        );
+        assert_eq!(ChecksumFormat::Sfv.parse_line("; comment"), None);
+        assert_eq!(
+            ChecksumFormat::Md5.parse_line("900150983cd24fb0d6963f7d28e17f72  my file.001"),
+            Some(("my file.001", "900150983cd24fb0d6963f7d28e17f72"))
+        );
+        assert_eq!(
+            ChecksumFormat::Sha256.parse_line("abcd *data.bin.002"),
+            Some(("data.bin.002", "abcd"))
+        );
+        assert_eq!(ChecksumFormat::Sha256.parse_line(""), None);
+    }
+
+    #[test]
+    fn test_write_and_check_sidecars() {
+        // チェックサムファイルの作成と確認をテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("data.bin");
+        let part1 = temp_dir.path().join("data.bin.001");
+        let part2 = temp_dir.path().join("data.bin.002");
+        fs::write(&part1, "abc").unwrap();
+        fs::write(&part2, "def").unwrap();
+
+        let written = write_sidecars(
+            &base_path,
+            &[part1.clone(), part2.clone()],
+            &ChecksumFormat::ALL,
+        )
+        .unwrap();
+        assert_eq!(written.len(), 3);
+
+        let sha256sums = fs::read_to_string(temp_dir.path().join("data.bin.SHA256SUMS")).unwrap();
+        assert_eq!(
+            sha256sums.lines().next().unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  data.bin.001"
+        );
+        let sfv = fs::read_to_string(temp_dir.path().join("data.bin.sfv")).unwrap();
+        assert!(sfv.contains("data.bin.001 352441C2\n"));
+
+        let sidecars = Sidecars::load(&base_path).unwrap();
+        assert!(sidecars.contains("data.bin.002"));
+        sidecars.check(&part1, b"abc").unwrap();
+        sidecars.check_file(&part2, &part2).unwrap();
+        assert!(matches!(
+            sidecars.check(&part1, b"abx"),
+            Err(FileSplitError::CorruptedPart(_))
+        ));
+    }
+
+    #[test]
+    fn test_load_without_sidecars() {
+        // チェックサムファイルがない場合は空になることをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let sidecars = Sidecars::load(&temp_dir.path().join("data.bin")).unwrap();
+        assert!(sidecars.is_empty());
+    }
+}
@@ -1,62 +1,937 @@
-use crate::config::{Config, Mode};
-use crate::error::Result;
-use clap::Parser;
+use clap::{ArgAction, ArgGroup, Args, Parser, Subcommand};
+use filesplit_rs::time_window::{DEFAULT_TIMESTAMP_FORMAT, DEFAULT_TIMESTAMP_REGEX};
+use filesplit_rs::{
+    ChecksumFormat, Encoding, FileSplitError, MergeOptions, NamingScheme, PartTemplate, Result,
+    SplitOptions, TimeWindow, TimeWindowOptions, VerifyOptions,
+};
+use std::ffi::OsString;
 use std::path::PathBuf;
 
+/// コマンドラインで指定された処理
+pub enum Command {
+    /// ファイル分割
+    Split {
+        /// 対象ファイルのパス（"-" の場合は標準入力）
+        file_path: PathBuf,
+        /// 分割のオプション
+        options: SplitOptions,
+    },
+    /// 時間枠分割
+    SplitByTime {
+        /// 対象ファイルのパス
+        file_path: PathBuf,
+        /// 時間枠分割のオプション
+        options: TimeWindowOptions,
+    },
+    /// ファイル結合
+    Merge {
+        /// 元ファイルのパス（分割ファイルを探す基準）
+        file_path: PathBuf,
+        /// 結合結果の出力先（Noneの場合は元ファイルのパス）
+        output: Option<MergeOutput>,
+        /// 結合のオプション
+        options: MergeOptions,
+    },
+    /// コンテナ形式の分割ファイルをヘッダーの情報で結合
+    MergeParts {
+        /// 分割ファイルまたは分割ファイルを含むディレクトリ
+        inputs: Vec<PathBuf>,
+        /// 結合後のファイルパス
+        file_path: PathBuf,
+        /// 結合のオプション
+        options: MergeOptions,
+    },
+    /// 他のツールで分割されたファイルの命名規則を判定して結合
+    MergeDetected {
+        /// 分割ファイル、元ファイルのパス、分割ファイルを含むディレクトリ、またはコピー用のバッチファイル
+        path: PathBuf,
+        /// 結合結果の出力先（Noneの場合は判定した元ファイルのパス）
+        output: Option<MergeOutput>,
+        /// 結合のオプション
+        options: MergeOptions,
+    },
+    /// 分割ファイルの検証
+    Verify {
+        /// 元ファイルのパス
+        file_path: PathBuf,
+        /// 検証のオプション
+        options: VerifyOptions,
+    },
+    /// 分割ファイル一式の情報の表示
+    Info {
+        /// 元ファイルのパス
+        file_path: PathBuf,
+        /// 分割ファイルの探し方（結合のオプション）
+        options: MergeOptions,
+    },
+    /// 分割ファイルの一覧の表示
+    List {
+        /// 元ファイルのパス
+        file_path: PathBuf,
+        /// 分割ファイルの探し方（結合のオプション）
+        options: MergeOptions,
+    },
+}
+
+/// 結合結果の出力先
+pub enum MergeOutput {
+    /// ファイルまたはディレクトリ
+    Path(PathBuf),
+    /// 標準出力
+    Stdout,
+}
+
+/// サブコマンドの名前（従来の形式の引数と区別するために使用する）
+const SUBCOMMANDS: [&str; 6] = ["split", "merge", "verify", "info", "list", "help"];
+
 /// コマンドライン引数の定義
 #[derive(Parser, Debug)]
 #[command(name = "filesplit-rs")]
 #[command(version = env!("CARGO_PKG_VERSION"))]
 #[command(
     about = format!("ファイル分割・結合ツール v{}", env!("CARGO_PKG_VERSION")),
-    long_about = None
+    long_about = None,
+    subcommand_required = true,
+    arg_required_else_help = true,
+    after_help = "従来の形式（-s <SIZE> <FILE_PATH>、-t <WINDOW> <FILE_PATH>、-m <FILE_PATH>、--verify <FILE_PATH>）も\nそれぞれ split, merge, verify サブコマンドとして使用できます"
 )]
 pub struct Cli {
-    /// 分割サイズを指定（-mオプションと同時に使用不可）
+    /// 実行するサブコマンド
+    #[command(subcommand)]
+    command: SubCommand,
+}
+
+/// サブコマンドの定義
+#[derive(Subcommand, Debug)]
+enum SubCommand {
+    /// ファイル分割
+    #[command(about = "ファイルを分割する（-s でサイズごと、-t で時間枠ごと）")]
+    Split(SplitArgs),
+    /// ファイル結合
+    #[command(about = "分割ファイルを結合して元のファイルを復元する")]
+    Merge(MergeArgs),
+    /// 分割ファイルの検証
+    #[command(about = "結合せずに分割ファイルの欠落・切り詰め・破損・余分を検証する")]
+    Verify(VerifyArgs),
+    /// 分割ファイル一式の情報の表示
+    #[command(about = "マニフェストに記録された分割ファイル一式の情報を表示する")]
+    Info(PartSetArgs),
+    /// 分割ファイルの一覧の表示
+    #[command(about = "結合する分割ファイルを番号順に一覧表示する")]
+    List(PartSetArgs),
+}
+
+/// split サブコマンドの引数
+#[derive(Args, Debug)]
+#[command(group(ArgGroup::new("mode").required(true).args(["size", "time_window"])))]
+struct SplitArgs {
+    /// 分割サイズ
+    #[arg(short = 's', long = "size", help = "分割サイズ（バイト）")]
+    size: Option<String>,
+
+    /// 時間枠分割を指定
     #[arg(
-        short = 's',
-        long = "size",
-        help = "分割サイズ（バイト）",
-        conflicts_with = "merge"
+        short = 't',
+        long = "time-window",
+        help = "ログを行内のタイムスタンプで時間枠ごとに分割する（hour または day）",
+        conflicts_with_all = [
+            "header", "footer", "no_manifest", "checksum", "filter", "remove_stale", "container",
+            "output_dir", "prefix", "name_template", "separator", "alphabetic", "start_index",
+            "no_metadata", "xattrs",
+        ]
     )]
-    size: Option<String>,
+    time_window: Option<String>,
+
+    /// 文字境界を考慮するエンコーディング
+    #[arg(
+        short = 'e',
+        long = "encoding",
+        help = "文字の途中で分割しないためのエンコーディング（utf-8, shift_jis, euc-jp, utf-16, utf-16le, utf-16be）",
+        requires = "size"
+    )]
+    encoding: Option<String>,
+
+    /// 各分割ファイルに挿入するヘッダー・フッター
+    #[command(flatten)]
+    template: TemplateArgs,
+
+    /// マニフェストを保存しない
+    #[arg(
+        long = "no-manifest",
+        help = "分割結果のマニフェスト（<ファイル名>.manifest.json）を保存しない"
+    )]
+    no_manifest: bool,
+
+    /// 作成するチェックサムファイルの形式
+    #[arg(
+        long = "checksum",
+        value_name = "FORMAT",
+        action = ArgAction::Append,
+        value_delimiter = ',',
+        help = "分割ファイルのチェックサムファイルを作成する（sfv, md5, sha256。カンマ区切りまたは複数指定可）"
+    )]
+    checksum: Vec<String>,
+
+    /// チェックサムファイルに元ファイルも含める
+    #[arg(
+        long = "checksum-original",
+        help = "チェックサムファイルに元ファイルも含める",
+        requires = "checksum"
+    )]
+    checksum_original: bool,
+
+    /// 分割ファイルの場所
+    #[command(flatten)]
+    location: LocationArgs,
+
+    /// 分割ファイルの名前の付け方
+    #[command(flatten)]
+    naming: NamingArgs,
+
+    /// 各分割ファイルを渡すコマンド
+    #[arg(
+        long = "filter",
+        value_name = "COMMAND",
+        allow_hyphen_values = true,
+        help = "分割ファイルを作成する代わりに、各分割ファイルの内容をコマンドの標準入力に渡す（$FILE, $FILESPLIT_INDEX, $FILESPLIT_SIZE を参照可。$FILESPLIT_SIZE は標準入力・パイプでは未設定）。マニフェストは作成しない",
+        conflicts_with_all = ["container", "checksum"]
+    )]
+    filter: Option<String>,
+
+    /// 古い分割ファイルを削除する
+    #[arg(
+        long = "remove-stale",
+        help = "以前の分割で作成された古い分割ファイルを削除する（以前のマニフェストに記録されたものだけ。省略時は警告だけを表示する）"
+    )]
+    remove_stale: bool,
+
+    /// コンテナ形式を使用する
+    #[arg(
+        long = "container",
+        help = "各分割ファイルの先頭に番号や識別子を記録したヘッダーを付ける"
+    )]
+    container: bool,
+
+    /// 元ファイルの名前と属性の記録
+    #[command(flatten)]
+    metadata: MetadataArgs,
+
+    /// タイムスタンプを検出する正規表現
+    #[arg(
+        long = "timestamp-regex",
+        help = "タイムスタンプを検出する正規表現（キャプチャグループがあれば最初のグループを使用）",
+        default_value = DEFAULT_TIMESTAMP_REGEX,
+        requires = "time_window"
+    )]
+    timestamp_regex: String,
+
+    /// タイムスタンプの書式
+    #[arg(
+        long = "timestamp-format",
+        help = "タイムスタンプの書式（strftime形式）",
+        default_value = DEFAULT_TIMESTAMP_FORMAT,
+        requires = "time_window"
+    )]
+    timestamp_format: String,
+
+    /// 対象ファイルのパス
+    #[arg(help = "分割するファイルのパス（- で標準入力）")]
+    file_path: PathBuf,
+}
+
+/// merge サブコマンドの引数
+#[derive(Args, Debug)]
+struct MergeArgs {
+    /// 分割時に挿入されたヘッダー・フッター
+    #[command(flatten)]
+    template: StripTemplateArgs,
+
+    /// 分割ファイルの場所
+    #[command(flatten)]
+    location: SearchLocationArgs,
+
+    /// 分割ファイルの名前の付け方
+    #[command(flatten)]
+    naming: NamingArgs,
+
+    /// 元ファイルの名前と属性の復元
+    #[command(flatten)]
+    metadata: RestoreMetadataArgs,
 
-    /// ファイル結合モードを指定
-    #[arg(short = 'm', long = "merge", help = "ファイルを結合する")]
-    merge: bool,
+    /// setuid・setgid・スティッキービットも復元する
+    #[arg(
+        long = "special-bits",
+        help = "マニフェストに記録された setuid・setgid・スティッキービットも復元する（省略時は tar と同じく 0o777 の範囲だけを復元する）",
+        conflicts_with = "no_metadata"
+    )]
+    special_bits: bool,
+
+    /// コンテナ形式の分割ファイルを結合する
+    #[arg(
+        long = "container",
+        help = "各分割ファイルの先頭のヘッダーを確認して取り除く"
+    )]
+    container: bool,
+
+    /// ヘッダーの情報で結合する分割ファイル
+    #[arg(
+        long = "parts",
+        value_name = "PATH",
+        action = ArgAction::Append,
+        help = "コンテナ形式の分割ファイル、または分割ファイルを含むディレクトリ（複数指定可）。名前に関係なくヘッダーの情報で結合する",
+        conflicts_with_all = ["output_dir", "prefix"]
+    )]
+    parts: Vec<PathBuf>,
+
+    /// 分割ファイルの命名規則を自動判定する
+    #[arg(
+        long = "detect",
+        help = "7-Zip（.001）、GNU split（xaa）、.partN、copy /b のバッチファイルなど他のツールの命名規則を判定して結合する（対象ファイルパスには分割ファイル・元ファイル・ディレクトリ・バッチファイルを指定可）",
+        conflicts_with_all = [
+            "parts", "container", "output_dir", "prefix", "name_template", "separator", "alphabetic",
+            "no_metadata", "xattrs", "special_bits",
+        ]
+    )]
+    detect: bool,
+
+    /// 結合結果の出力先
+    #[arg(
+        short = 'o',
+        long = "output",
+        value_name = "PATH",
+        help = "結合結果の出力先（ファイルまたはディレクトリ。- で標準出力）。分割ファイルは対象ファイルパスを基準に探す",
+        conflicts_with = "parts"
+    )]
+    output: Option<PathBuf>,
+
+    /// 既存の出力ファイルを上書きする
+    #[arg(
+        long = "force",
+        help = "結合結果の出力先が既に存在する場合に上書きする"
+    )]
+    force: bool,
+
+    /// 欠けている分割ファイルを飛ばして結合する
+    #[arg(
+        long = "allow-partial",
+        help = "連番に欠けがあっても存在する分割ファイルだけを結合する（結果は元のファイルと一致しません）"
+    )]
+    allow_partial: bool,
 
     /// 対象ファイルのパス
-    #[arg(help = "対象ファイルパス")]
+    #[arg(
+        help = "元のファイルのパス（分割ファイルを探す基準。--parts 指定時は結合後のファイルパス）"
+    )]
     file_path: PathBuf,
 }
 
+/// verify サブコマンドの引数
+#[derive(Args, Debug)]
+struct VerifyArgs {
+    /// 比較する元ファイル
+    #[arg(
+        long = "original",
+        value_name = "PATH",
+        help = "マニフェストの代わりに元ファイルの内容と比較して検証する"
+    )]
+    original: Option<PathBuf>,
+
+    /// 分割時に挿入したヘッダー・フッター
+    #[command(flatten)]
+    template: StripTemplateArgs,
+
+    /// 分割ファイルの場所
+    #[command(flatten)]
+    location: SearchLocationArgs,
+
+    /// 分割ファイルの名前の付け方
+    #[command(flatten)]
+    naming: NamingArgs,
+
+    /// 対象ファイルのパス
+    #[arg(help = "元のファイルのパス（分割ファイルを探す基準）")]
+    file_path: PathBuf,
+}
+
+/// info・list サブコマンドの引数
+#[derive(Args, Debug)]
+struct PartSetArgs {
+    /// 分割ファイルの場所
+    #[command(flatten)]
+    location: SearchLocationArgs,
+
+    /// 分割ファイルの名前の付け方
+    #[command(flatten)]
+    naming: NamingArgs,
+
+    /// 対象ファイルのパス
+    #[arg(help = "元のファイルのパス（分割ファイルを探す基準）")]
+    file_path: PathBuf,
+}
+
+/// 各分割ファイルに挿入するヘッダー・フッター（split）
+#[derive(Args, Debug)]
+struct TemplateArgs {
+    /// 各分割ファイルの先頭に挿入するテンプレート
+    #[arg(
+        long = "header",
+        allow_hyphen_values = true,
+        help = "各分割ファイルの先頭に挿入するテンプレート（{index}, {total}, {offset}, {original_name} を展開）"
+    )]
+    header: Option<String>,
+
+    /// 各分割ファイルの末尾に挿入するテンプレート
+    #[arg(
+        long = "footer",
+        allow_hyphen_values = true,
+        help = "各分割ファイルの末尾に挿入するテンプレート（展開できる値は --header と同じ）"
+    )]
+    footer: Option<String>,
+}
+
+impl TemplateArgs {
+    /// ヘッダー・フッターのテンプレートを作成する
+    fn into_template(self) -> PartTemplate {
+        PartTemplate {
+            header: self.header,
+            footer: self.footer,
+        }
+    }
+}
+
+/// 分割時に挿入されたヘッダー・フッター（merge・verify で共通）
+#[derive(Args, Debug)]
+struct StripTemplateArgs {
+    /// 分割時に挿入されたヘッダーのテンプレート
+    #[arg(
+        long = "header",
+        allow_hyphen_values = true,
+        help = "分割時に --header で指定したテンプレート。各分割ファイルの先頭が展開結果と一致するか確認し、データから取り除く"
+    )]
+    header: Option<String>,
+
+    /// 分割時に挿入されたフッターのテンプレート
+    #[arg(
+        long = "footer",
+        allow_hyphen_values = true,
+        help = "分割時に --footer で指定したテンプレート。各分割ファイルの末尾が展開結果と一致するか確認し、データから取り除く"
+    )]
+    footer: Option<String>,
+}
+
+impl StripTemplateArgs {
+    /// ヘッダー・フッターのテンプレートを作成する
+    fn into_template(self) -> PartTemplate {
+        PartTemplate {
+            header: self.header,
+            footer: self.footer,
+        }
+    }
+}
+
+/// 分割ファイルを置くディレクトリと名前（split）
+#[derive(Args, Debug)]
+struct LocationArgs {
+    /// 分割ファイルの名前の基準にするパス
+    #[arg(
+        long = "prefix",
+        value_name = "PATH",
+        help = "分割ファイルを <PATH>.001, <PATH>.002, ... として保存する（標準入力・パイプ・デバイスの分割時は必須）"
+    )]
+    prefix: Option<PathBuf>,
+
+    /// 分割ファイルを置くディレクトリ
+    #[arg(
+        long = "output-dir",
+        value_name = "DIR",
+        help = "分割ファイルを <DIR> に保存する（存在しない場合は作成する）"
+    )]
+    output_dir: Option<PathBuf>,
+}
+
+/// 分割ファイルを探すディレクトリと名前（merge・verify・info・list で共通）
+#[derive(Args, Debug)]
+struct SearchLocationArgs {
+    /// 分割時に指定された分割ファイルの名前の基準
+    #[arg(
+        long = "prefix",
+        value_name = "PATH",
+        help = "分割時に --prefix で指定した名前。<PATH>.001, <PATH>.002, ... を分割ファイルとして探す"
+    )]
+    prefix: Option<PathBuf>,
+
+    /// 分割ファイルを探すディレクトリ
+    #[arg(
+        long = "output-dir",
+        value_name = "DIR",
+        help = "分割時に --output-dir で指定したディレクトリ。<DIR> から分割ファイルを探す"
+    )]
+    output_dir: Option<PathBuf>,
+}
+
+/// 分割ファイルの名前の付け方（split・merge・verify・info・list で共通）
+#[derive(Args, Debug)]
+struct NamingArgs {
+    /// 分割ファイル名のテンプレート
+    #[arg(
+        long = "name-template",
+        value_name = "TEMPLATE",
+        help = "分割ファイル名のテンプレート（{name}, {stem}, {ext}, {index}, {index:4}, {alpha}, {alpha:3} を使用可。既定: {name}.{index}）"
+    )]
+    name_template: Option<String>,
+
+    /// 元ファイル名と番号の区切り文字
+    #[arg(
+        long = "separator",
+        value_name = "SEP",
+        help = "元ファイル名と番号の間に入れる文字列（既定: .）",
+        conflicts_with = "name_template"
+    )]
+    separator: Option<String>,
+
+    /// アルファベットの接尾辞を使用する
+    #[arg(
+        long = "alphabetic",
+        help = "番号の代わりに GNU split と同じアルファベットの接尾辞（aa, ab, ...）を付ける",
+        conflicts_with = "name_template"
+    )]
+    alphabetic: bool,
+
+    /// アルファベットの接尾辞の文字数
+    #[arg(
+        long = "suffix-length",
+        value_name = "N",
+        value_parser = clap::value_parser!(u16).range(1..),
+        help = "アルファベットの接尾辞の文字数を固定する（省略時は2文字から始めて足りなくなると自動的に増やす）",
+        requires = "alphabetic"
+    )]
+    suffix_length: Option<u16>,
+
+    /// 最初の分割ファイルの番号
+    #[arg(
+        long = "start-index",
+        value_name = "N",
+        default_value_t = 1,
+        help = "最初の分割ファイルの番号（0 または 1 など）"
+    )]
+    start_index: usize,
+}
+
+impl NamingArgs {
+    /// 分割ファイルの名前の付け方を決定する
+    fn into_naming(self) -> Result<NamingScheme> {
+        let separator = self.separator.as_deref();
+        let naming = match (&self.name_template, separator) {
+            (Some(template), _) => NamingScheme::parse(template)?,
+            (None, separator) if self.alphabetic => NamingScheme::alphabetic(
+                separator.unwrap_or("."),
+                self.suffix_length.map(usize::from),
+            )?,
+            (None, Some(separator)) => NamingScheme::with_separator(separator)?,
+            (None, None) => NamingScheme::default(),
+        };
+        Ok(naming.start_index(self.start_index))
+    }
+}
+
+/// 元ファイルの名前と属性の記録（split）
+#[derive(Args, Debug)]
+struct MetadataArgs {
+    /// 元ファイルの名前と属性を記録しない
+    #[arg(
+        long = "no-metadata",
+        help = "元ファイルの名前・パーミッション・最終更新日時をマニフェストに記録しない"
+    )]
+    no_metadata: bool,
+
+    /// 拡張属性も記録する
+    #[arg(
+        long = "xattrs",
+        help = "元ファイルの拡張属性もマニフェストに記録する（Unix で xattr 機能を有効にしたビルドのみ）",
+        conflicts_with = "no_metadata"
+    )]
+    xattrs: bool,
+}
+
+/// 元ファイルの名前と属性の復元（merge）
+#[derive(Args, Debug)]
+struct RestoreMetadataArgs {
+    /// 記録された名前と属性を復元しない
+    #[arg(
+        long = "no-metadata",
+        help = "マニフェストに記録された元ファイルの名前・パーミッション・最終更新日時を復元しない"
+    )]
+    no_metadata: bool,
+
+    /// 記録された拡張属性も復元する
+    #[arg(
+        long = "xattrs",
+        help = "マニフェストに記録された拡張属性も復元する（Unix で xattr 機能を有効にしたビルドのみ）",
+        conflicts_with = "no_metadata"
+    )]
+    xattrs: bool,
+}
+
 impl Cli {
-    /// コマンドライン引数を解析してConfigを生成する
+    /// コマンドライン引数を解析して実行する処理を決定する
+    ///
+    /// # 動作
+    /// 従来の -s / -t / -m / --verify 形式の引数は対応するサブコマンドとして解析する
     ///
     /// # 戻り値
-    /// 解析結果のConfig、またはエラー
-    pub fn parse_args() -> Result<Config> {
+    /// 解析結果のCommand、またはエラー
+    pub fn parse_args() -> Result<Command> {
         // clapを使用して引数を解析
-        let cli = Cli::parse();
-
-        // 動作モードを決定
-        let mode = if cli.merge {
-            // 結合モード
-            Mode::Merge
-        } else if let Some(size_str) = cli.size {
-            // 分割モード（サイズをパース）
-            let size = Config::parse_size(&size_str)?;
-            Mode::Split { size }
-        } else {
-            // -sまたは-mのどちらかが必須
-            return Err(crate::error::FileSplitError::InvalidSize(
-                "分割モードでは -s オプションでサイズを指定してください".to_string(),
-            ));
+        let cli = Cli::parse_from(legacy_args(std::env::args_os().collect()));
+        cli.command.into_command()
+    }
+}
+
+impl SubCommand {
+    /// サブコマンドの引数から実行する処理を決定する
+    fn into_command(self) -> Result<Command> {
+        let command = match self {
+            SubCommand::Split(args) => args.into_command()?,
+            SubCommand::Merge(args) => args.into_command()?,
+            SubCommand::Verify(args) => {
+                let mut options = VerifyOptions::default();
+                options.original = args.original;
+                options.template = args.template.into_template();
+                options.output_dir = args.location.output_dir;
+                options.prefix = args.location.prefix;
+                options.naming = args.naming.into_naming()?;
+                Command::Verify {
+                    file_path: args.file_path,
+                    options,
+                }
+            }
+            SubCommand::Info(args) => {
+                let (file_path, options) = args.into_options()?;
+                Command::Info { file_path, options }
+            }
+            SubCommand::List(args) => {
+                let (file_path, options) = args.into_options()?;
+                Command::List { file_path, options }
+            }
         };
+        Ok(command)
+    }
+}
+
+impl SplitArgs {
+    /// 分割または時間枠分割の処理を決定する
+    fn into_command(self) -> Result<Command> {
+        if let Some(window_str) = self.time_window {
+            // 時間枠分割モード
+            let mut options = TimeWindowOptions::new(TimeWindow::parse(&window_str)?);
+            options.timestamp_regex = self.timestamp_regex;
+            options.timestamp_format = self.timestamp_format;
+            return Ok(Command::SplitByTime {
+                file_path: self.file_path,
+                options,
+            });
+        }
 
-        Ok(Config {
-            file_path: cli.file_path,
-            mode,
+        // 分割モード（サイズをパース）
+        let mut options = SplitOptions::default();
+        options.chunk_size = parse_size(self.size.as_deref().unwrap_or_default())?;
+        options.encoding = self.encoding.as_deref().map(Encoding::parse).transpose()?;
+        options.template = self.template.into_template();
+        options.manifest = !self.no_manifest;
+        options.remove_stale = self.remove_stale;
+        options.container = self.container;
+        for format in &self.checksum {
+            let format = ChecksumFormat::parse(format)?;
+            if !options.checksums.contains(&format) {
+                options.checksums.push(format);
+            }
+        }
+        options.checksum_original = self.checksum_original;
+        options.filter = self.filter;
+        options.output_dir = self.location.output_dir;
+        options.prefix = self.location.prefix;
+        options.naming = self.naming.into_naming()?;
+        options.metadata = !self.metadata.no_metadata;
+        options.xattrs = self.metadata.xattrs;
+        Ok(Command::Split {
+            file_path: self.file_path,
+            options,
         })
     }
 }
+
+impl MergeArgs {
+    /// 結合の処理を決定する
+    fn into_command(self) -> Result<Command> {
+        let mut options = MergeOptions::default();
+        options.template = self.template.into_template();
+        options.allow_partial = self.allow_partial;
+        options.container = self.container;
+        options.force = self.force;
+        options.output_dir = self.location.output_dir;
+        options.prefix = self.location.prefix;
+        options.naming = self.naming.into_naming()?;
+        options.metadata = !self.metadata.no_metadata;
+        options.xattrs = self.metadata.xattrs;
+        options.special_bits = self.special_bits;
+        let output = self.output.map(|path| {
+            if path.as_os_str() == "-" {
+                MergeOutput::Stdout
+            } else {
+                MergeOutput::Path(path)
+            }
+        });
+
+        let command = if self.detect {
+            Command::MergeDetected {
+                path: self.file_path,
+                output,
+                options,
+            }
+        } else if self.parts.is_empty() {
+            Command::Merge {
+                file_path: self.file_path,
+                output,
+                options,
+            }
+        } else {
+            Command::MergeParts {
+                inputs: self.parts,
+                file_path: self.file_path,
+                options,
+            }
+        };
+        Ok(command)
+    }
+}
+
+impl PartSetArgs {
+    /// 分割ファイル一式の探し方を決定する
+    fn into_options(self) -> Result<(PathBuf, MergeOptions)> {
+        let mut options = MergeOptions::default();
+        options.output_dir = self.location.output_dir;
+        options.prefix = self.location.prefix;
+        options.naming = self.naming.into_naming()?;
+        Ok((self.file_path, options))
+    }
+}
+
+/// 従来の形式の引数をサブコマンド形式に書き換える
+///
+/// # 引数
+/// * `args` - プログラム名を含むコマンドライン引数
+///
+/// # 動作
+/// 最初の引数がサブコマンドでなければ、-s / -t があれば split、
+/// -m があれば merge、--verify があれば verify を先頭に挿入する
+/// （-m と --verify は取り除く）。複数の形式が混ざっている場合は最初のものを使い、
+/// 残りはサブコマンドの引数として解析されるためエラーになる
+///
+/// # 戻り値
+/// サブコマンド形式の引数
+pub fn legacy_args(mut args: Vec<OsString>) -> Vec<OsString> {
+    let is_subcommand = args
+        .get(1)
+        .and_then(|arg| arg.to_str())
+        .is_some_and(|arg| SUBCOMMANDS.contains(&arg));
+    if is_subcommand {
+        return args;
+    }
+
+    // "--" 以降はファイルパスとして扱う
+    let end = args
+        .iter()
+        .position(|arg| arg == "--")
+        .unwrap_or(args.len());
+    let found = args[1..end].iter().enumerate().find_map(|(i, arg)| {
+        let arg = arg.to_str()?;
+        match arg {
+            "-m" | "--merge" => Some((i + 1, "merge", true)),
+            "--verify" => Some((i + 1, "verify", true)),
+            _ if arg.starts_with("-s")
+                || arg.starts_with("-t")
+                || arg.starts_with("--size")
+                || arg.starts_with("--time-window") =>
+            {
+                Some((i + 1, "split", false))
+            }
+            _ => None,
+        }
+    });
+
+    if let Some((position, subcommand, remove)) = found {
+        if remove {
+            args.remove(position);
+        }
+        args.insert(1, subcommand.into());
+    }
+    args
+}
+
+/// 文字列からサイズをパースする
+pub fn parse_size(size_str: &str) -> Result<u64> {
+    size_str.parse::<u64>().map_err(|_| {
+        FileSplitError::InvalidSize(format!("'{size_str}' は有効な数値ではありません"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    /// 文字列の配列をコマンドライン引数に変換する
+    fn os_args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn test_cli_definition() {
+        // 引数の定義に矛盾がないことをテスト
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_subcommand_help() {
+        // 共通のオプションでもサブコマンドごとの説明が表示されることをテスト
+        let command = Cli::command();
+        let help = |subcommand: &str, id: &str| {
+            command
+                .find_subcommand(subcommand)
+                .unwrap()
+                .get_arguments()
+                .find(|arg| arg.get_id() == id)
+                .and_then(|arg| arg.get_help())
+                .unwrap()
+                .to_string()
+        };
+
+        assert!(help("split", "prefix").contains("保存する"));
+        assert!(help("split", "output_dir").contains("作成する"));
+        for subcommand in ["merge", "verify", "info", "list"] {
+            assert!(help(subcommand, "prefix").contains("探す"));
+            assert!(!help(subcommand, "prefix").contains("保存する"));
+            assert!(help(subcommand, "output_dir").contains("探す"));
+            assert!(!help(subcommand, "output_dir").contains("作成する"));
+        }
+        for subcommand in ["merge", "verify"] {
+            assert!(help(subcommand, "header").contains("取り除く"));
+        }
+        assert!(!help("split", "header").contains("取り除く"));
+        assert!(help("split", "no_metadata").contains("記録しない"));
+        assert!(help("merge", "no_metadata").contains("復元しない"));
+        assert!(help("split", "xattrs").contains("記録する"));
+        assert!(help("merge", "xattrs").contains("復元する"));
+    }
+
+    #[test]
+    fn test_legacy_args() {
+        // 従来の形式の引数をサブコマンド形式に書き換えるテスト
+        let cases: [(&[&str], &[&str]); 7] = [
+            (&["fs", "-s", "10", "a"], &["fs", "split", "-s", "10", "a"]),
+            (
+                &["fs", "--size=10", "a"],
+                &["fs", "split", "--size=10", "a"],
+            ),
+            (
+                &["fs", "-t", "day", "a"],
+                &["fs", "split", "-t", "day", "a"],
+            ),
+            (
+                &["fs", "a", "-m", "-o", "-"],
+                &["fs", "merge", "a", "-o", "-"],
+            ),
+            (&["fs", "--verify", "a"], &["fs", "verify", "a"]),
+            // サブコマンド形式と "--" 以降はそのまま
+            (&["fs", "merge", "-m"], &["fs", "merge", "-m"]),
+            (&["fs", "a", "--", "-m"], &["fs", "a", "--", "-m"]),
+        ];
+        for (args, expected) in cases {
+            assert_eq!(legacy_args(os_args(args)), os_args(expected), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_subcommands() {
+        // サブコマンドと従来の形式が同じ処理になることをテスト
+        for args in [
+            &["fs", "split", "-s", "1024", "--no-manifest", "a.bin"][..],
+            &["fs", "-s", "1024", "--no-manifest", "a.bin"][..],
+        ] {
+            let cli = Cli::try_parse_from(legacy_args(os_args(args))).unwrap();
+            match cli.command.into_command().unwrap() {
+                Command::Split { file_path, options } => {
+                    assert_eq!(file_path, PathBuf::from("a.bin"));
+                    assert_eq!(options.chunk_size, 1024);
+                    assert!(!options.manifest);
+                }
+                _ => panic!("予期しない処理"),
+            }
+        }
+
+        let cli = Cli::try_parse_from(os_args(&["fs", "list", "--prefix", "x", "a.bin"])).unwrap();
+        match cli.command.into_command().unwrap() {
+            Command::List { options, .. } => assert_eq!(options.prefix, Some("x".into())),
+            _ => panic!("予期しない処理"),
+        }
+
+        let cli = Cli::try_parse_from(legacy_args(os_args(&[
+            "fs",
+            "--verify",
+            "--header",
+            "#{index}",
+            "--output-dir",
+            "parts",
+            "a.bin",
+        ])))
+        .unwrap();
+        match cli.command.into_command().unwrap() {
+            Command::Verify { options, .. } => {
+                assert_eq!(options.template.header.as_deref(), Some("#{index}"));
+                assert_eq!(options.output_dir, Some("parts".into()));
+            }
+            _ => panic!("予期しない処理"),
+        }
+
+        // サブコマンドごとに使用できない引数はエラー
+        for args in [
+            &["fs", "merge", "-s", "10", "a"][..],
+            &["fs", "-s", "10", "-m", "a"][..],
+            &["fs", "split", "a"][..],
+            &["fs", "a"][..],
+        ] {
+            assert!(
+                Cli::try_parse_from(legacy_args(os_args(args))).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_size_valid() {
+        // 有効なサイズのパースをテスト
+        assert_eq!(parse_size("1024").unwrap(), 1024);
+        assert_eq!(parse_size("0").unwrap(), 0);
+        assert_eq!(parse_size("9999999").unwrap(), 9999999);
+        assert_eq!(parse_size("8589934592").unwrap(), 8589934592);
+    }
+
+    #[test]
+    fn test_parse_size_invalid() {
+        // 無効なサイズのパースをテスト
+        assert!(parse_size("abc").is_err());
+        assert!(parse_size("-100").is_err());
+        assert!(parse_size("12.34").is_err());
+        assert!(parse_size("").is_err());
+
+        // エラーメッセージの確認
+        let err = parse_size("abc").unwrap_err();
+        match err {
+            FileSplitError::InvalidSize(msg) => {
+                assert!(msg.contains("abc"));
+                assert!(msg.contains("有効な数値ではありません"));
+            }
+            _ => panic!("予期しないエラー型"),
+        }
+    }
+}
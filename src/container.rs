@@ -0,0 +1,209 @@
+use crate::error::{FileSplitError, Result};
+use std::fs::File;
+use std::io::{ErrorKind, Read};
+use std::path::Path;
+
+/// 分割ファイルのヘッダーを識別するマジックナンバー
+pub const MAGIC: [u8; 8] = *b"\x89FSPLIT\n";
+
+/// ヘッダーの形式バージョン
+pub const VERSION: u32 = 1;
+
+/// 分割ファイル一式の識別子のバイト数
+pub const SET_ID_LEN: usize = 16;
+
+/// ヘッダーのバイト数
+pub const HEADER_LEN: usize = 8 + 4 + SET_ID_LEN + 8 * 4 + 32;
+
+/// コンテナ形式の分割ファイルの先頭に付けるヘッダー
+///
+/// 分割ファイルの名前が変更されても、ヘッダーから分割ファイル一式と
+/// 番号を判別して結合できるようにする。
+/// すべての数値はリトルエンディアンで格納する
+///
+/// | 位置 | バイト数 | 内容 |
+/// |---|---|---|
+/// | 0 | 8 | マジックナンバー |
+/// | 8 | 4 | 形式バージョン |
+/// | 12 | 16 | 分割ファイル一式の識別子（ASCII、不足分は0埋め） |
+/// | 28 | 8 | 分割ファイルの番号（1から始まる） |
+/// | 36 | 8 | 分割ファイルの総数 |
+/// | 44 | 8 | 元ファイル内での開始位置 |
+/// | 52 | 8 | ヘッダーに続くデータのバイト数 |
+/// | 60 | 32 | ヘッダーに続くデータのSHA-256ハッシュ |
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartHeader {
+    /// 分割ファイル一式の識別子
+    pub set_id: String,
+    /// 分割ファイルの番号（1から始まる）
+    pub index: u64,
+    /// 分割ファイルの総数
+    pub total: u64,
+    /// 元ファイル内での開始位置（バイト）
+    pub offset: u64,
+    /// ヘッダーに続くデータのバイト数
+    pub length: u64,
+    /// ヘッダーに続くデータのSHA-256ハッシュ
+    pub checksum: [u8; 32],
+}
+
+impl PartHeader {
+    /// ヘッダーをバイト列に変換する
+    pub fn to_bytes(&self) -> [u8; HEADER_LEN] {
+        let mut bytes = [0u8; HEADER_LEN];
+        bytes[0..8].copy_from_slice(&MAGIC);
+        bytes[8..12].copy_from_slice(&VERSION.to_le_bytes());
+        let set_id = self.set_id.as_bytes();
+        let len = set_id.len().min(SET_ID_LEN);
+        bytes[12..12 + len].copy_from_slice(&set_id[..len]);
+        bytes[28..36].copy_from_slice(&self.index.to_le_bytes());
+        bytes[36..44].copy_from_slice(&self.total.to_le_bytes());
+        bytes[44..52].copy_from_slice(&self.offset.to_le_bytes());
+        bytes[52..60].copy_from_slice(&self.length.to_le_bytes());
+        bytes[60..92].copy_from_slice(&self.checksum);
+        bytes
+    }
+
+    /// バイト列からヘッダーを読み取る
+    ///
+    /// # 引数
+    /// * `bytes` - 分割ファイルの先頭のデータ
+    ///
+    /// # 戻り値
+    /// 読み取ったヘッダー。マジックナンバーで始まらない場合はNone、
+    /// マジックナンバーで始まるが形式が不正な場合はエラー
+    pub fn parse(bytes: &[u8]) -> Result<Option<Self>> {
+        if !bytes.starts_with(&MAGIC) {
+            return Ok(None);
+        }
+        if bytes.len() < HEADER_LEN {
+            return Err(FileSplitError::InvalidContainer(
+                "ヘッダーが途中で終わっています".to_string(),
+            ));
+        }
+
+        let u64_at = |pos: usize| u64::from_le_bytes(bytes[pos..pos + 8].try_into().unwrap());
+        let version = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+        if version > VERSION {
+            return Err(FileSplitError::InvalidContainer(format!(
+                "未対応の形式バージョンです: {version}"
+            )));
+        }
+
+        let set_id = &bytes[12..28];
+        let set_id_len = set_id.iter().position(|&b| b == 0).unwrap_or(SET_ID_LEN);
+        let set_id = std::str::from_utf8(&set_id[..set_id_len]).map_err(|_| {
+            FileSplitError::InvalidContainer("分割ファイル一式の識別子が不正です".to_string())
+        })?;
+
+        Ok(Some(Self {
+            set_id: set_id.to_string(),
+            index: u64_at(28),
+            total: u64_at(36),
+            offset: u64_at(44),
+            length: u64_at(52),
+            checksum: bytes[60..92].try_into().unwrap(),
+        }))
+    }
+
+    /// ファイルの先頭からヘッダーを読み取る
+    ///
+    /// # 戻り値
+    /// 読み取ったヘッダー、またはヘッダーがない場合はNone
+    pub fn read_from(path: &Path) -> Result<Option<Self>> {
+        let mut file = File::open(path)?;
+        let mut bytes = [0u8; HEADER_LEN];
+        let mut filled = 0;
+        while filled < HEADER_LEN {
+            match file.read(&mut bytes[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Self::parse(&bytes[..filled]).map_err(|e| with_path(e, path))
+    }
+}
+
+/// ヘッダーのエラーメッセージに分割ファイルのパスを付ける
+pub(crate) fn with_path(err: FileSplitError, path: &Path) -> FileSplitError {
+    match err {
+        FileSplitError::InvalidContainer(msg) => {
+            FileSplitError::InvalidContainer(format!("{}: {msg}", path.display()))
+        }
+        err => err,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::sha256_hex;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn sample_header() -> PartHeader {
+        PartHeader {
+            set_id: "0123456789abcdef".to_string(),
+            index: 2,
+            total: 3,
+            offset: 1024,
+            length: 512,
+            checksum: [7u8; 32],
+        }
+    }
+
+    #[test]
+    fn test_header_round_trip() {
+        // ヘッダーの変換と読み取りをテスト
+        let header = sample_header();
+        let bytes = header.to_bytes();
+
+        assert_eq!(bytes.len(), HEADER_LEN);
+        assert!(bytes.starts_with(&MAGIC));
+        assert_eq!(PartHeader::parse(&bytes).unwrap(), Some(header));
+    }
+
+    #[test]
+    fn test_header_short_set_id() {
+        // 短い識別子は0埋めして格納されることをテスト
+        let mut header = sample_header();
+        header.set_id = "abc".to_string();
+
+        let parsed = PartHeader::parse(&header.to_bytes()).unwrap().unwrap();
+        assert_eq!(parsed.set_id, "abc");
+    }
+
+    #[test]
+    fn test_parse_without_magic() {
+        // マジックナンバーがない場合はヘッダーなしとして扱う
+        assert_eq!(PartHeader::parse(b"plain data").unwrap(), None);
+        assert_eq!(PartHeader::parse(b"").unwrap(), None);
+    }
+
+    #[test]
+    fn test_parse_truncated_header() {
+        // ヘッダーが途中で終わっている場合のエラーテスト
+        let bytes = sample_header().to_bytes();
+        assert!(matches!(
+            PartHeader::parse(&bytes[..40]),
+            Err(FileSplitError::InvalidContainer(_))
+        ));
+    }
+
+    #[test]
+    fn test_read_from_file() {
+        // ファイルからのヘッダー読み取りをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("part");
+        let mut content = sample_header().to_bytes().to_vec();
+        content.extend_from_slice(b"payload");
+        fs::write(&path, &content).unwrap();
+
+        assert_eq!(PartHeader::read_from(&path).unwrap(), Some(sample_header()));
+
+        fs::write(&path, sha256_hex(b"plain")).unwrap();
+        assert_eq!(PartHeader::read_from(&path).unwrap(), None);
+    }
+}
@@ -0,0 +1,649 @@
+use crate::error::{FileSplitError, Result};
+use crate::metadata::is_plain_file_name;
+use crate::naming::NamingScheme;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 分割ファイルの名前の付け方の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SplitConvention {
+    /// "archive.7z.001" のような "." と番号（7-Zip・HJSplit・このツールの標準）
+    Numbered,
+    /// "movie.mkv.part1" や "archive.part01.rar" のような ".part" と番号
+    PartNumber,
+    /// "xaa", "xab", ... のようなアルファベット（GNU split の標準）
+    Alphabetic,
+    /// "x00", "x01", ... のような区切りのない番号（GNU split -d）
+    NumericSuffix,
+    /// "copy /b" で分割ファイルを連結するバッチファイル
+    CopyScript,
+}
+
+/// 名前の付け方の説明を表示する
+impl fmt::Display for SplitConvention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SplitConvention::Numbered => "番号付き（7-Zip・HJSplit 形式 .001）",
+            SplitConvention::PartNumber => ".partN 形式",
+            SplitConvention::Alphabetic => "GNU split 形式（xaa）",
+            SplitConvention::NumericSuffix => "GNU split -d 形式（x00）",
+            SplitConvention::CopyScript => "copy /b のバッチファイル",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 検出した分割ファイル一式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedParts {
+    /// 名前の付け方の種類
+    pub convention: SplitConvention,
+    /// 分割前のファイルのパス（結合結果の既定の出力先）
+    pub base_path: PathBuf,
+    /// 見つかった分割ファイル（1から始まる番号順）
+    pub parts: BTreeMap<usize, PathBuf>,
+    /// 分割ファイルの総数（最後の番号）
+    pub total: usize,
+}
+
+impl DetectedParts {
+    /// 欠けている分割ファイルの番号を返す
+    pub fn missing(&self) -> Vec<usize> {
+        (1..=self.total)
+            .filter(|index| !self.parts.contains_key(index))
+            .collect()
+    }
+}
+
+/// 他のツールで分割されたファイル一式を検出する
+///
+/// # 引数
+/// * `path` - 分割ファイルのどれか、分割前のファイルのパス、
+///   分割ファイルを含むディレクトリ、または "copy /b" のバッチファイル
+///
+/// # 動作
+/// 7-Zip・HJSplit（.001）、GNU split（xaa・x00）、.partN 形式の名前と、
+/// "copy /b a.001 + a.002 a" のように連結するバッチファイル（.bat・.cmd）を認識する。
+/// ディレクトリを指定した場合は、その中の分割ファイル一式が1つだけであれば使用する
+///
+/// # 戻り値
+/// 検出した分割ファイル一式。見つからない場合や、ディレクトリに複数の一式がある場合はエラー
+pub fn detect_parts(path: &Path) -> Result<DetectedParts> {
+    if path.is_dir() {
+        return detect_in_dir(path);
+    }
+    if is_script(path) && path.is_file() {
+        return parse_copy_script(path);
+    }
+
+    // 分割ファイルの名前から分割前のファイル名を推測する
+    // （"xzaaa" のように複数の解釈ができる場合は、分割ファイルが多く見つかるものを選ぶ）
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    if path.is_file() {
+        let mut best: Option<DetectedParts> = None;
+        for (convention, base_name, naming) in part_candidates(&name)? {
+            let base_path = path.with_file_name(&base_name);
+            let Some(detected) = scan_set(convention, &base_path, &naming)? else {
+                continue;
+            };
+            // 親ディレクトリのない相対パスは "./" 付きで検出されるため、ファイル名で比べる
+            if detected
+                .parts
+                .values()
+                .any(|part| part.file_name() == path.file_name())
+                && best
+                    .as_ref()
+                    .is_none_or(|best| detected.parts.len() > best.parts.len())
+            {
+                best = Some(detected);
+            }
+        }
+        if let Some(detected) = best {
+            return Ok(detected);
+        }
+    }
+
+    // 分割前のファイル名が指定された場合
+    for (convention, naming) in base_candidates()? {
+        if let Some(detected) = scan_set(convention, path, &naming)? {
+            return Ok(detected);
+        }
+    }
+
+    Err(FileSplitError::NoSplitFiles(
+        path.to_string_lossy().to_string(),
+    ))
+}
+
+/// ディレクトリの中の分割ファイル一式を検出する
+fn detect_in_dir(dir: &Path) -> Result<DetectedParts> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<_>>()?;
+    entries.sort();
+
+    let mut found: Vec<DetectedParts> = Vec::new();
+    for path in entries.iter().filter(|path| path.is_file()) {
+        let candidates = if is_script(path) {
+            vec![parse_copy_script(path)?]
+        } else {
+            // 最初の分割ファイルから一式を探す（名前だけで判断するため2つ以上あるものに限る）
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let mut candidates = Vec::new();
+            for (convention, base_name, naming) in part_candidates(&name)? {
+                // 最初の分割ファイルでなければディレクトリを読み直さない
+                let first = [0, 1].iter().any(|&start| {
+                    naming
+                        .clone()
+                        .start_index(start)
+                        .parse_index(&base_name, &name)
+                        == Some(1)
+                });
+                if !first {
+                    continue;
+                }
+                let base_path = path.with_file_name(&base_name);
+                if let Some(detected) = scan_set(convention, &base_path, &naming)? {
+                    if detected.parts.get(&1) == Some(path) && detected.parts.len() >= 2 {
+                        candidates.push(detected);
+                    }
+                }
+            }
+            candidates
+        };
+
+        // バッチファイルと名前から同じ一式が見つかった場合は1つにまとめる
+        for detected in candidates {
+            if !found.iter().any(|other| other.parts == detected.parts) {
+                found.push(detected);
+            }
+        }
+    }
+
+    // 別の一式の一部（"xzaaa", "xzaab" を "xza" の一式とみなしたものなど）は除く
+    let sets: Vec<BTreeMap<usize, PathBuf>> = found.iter().map(|d| d.parts.clone()).collect();
+    found.retain(|detected| {
+        !sets.iter().any(|other| {
+            other.len() > detected.parts.len()
+                && detected
+                    .parts
+                    .values()
+                    .all(|part| other.values().any(|p| p == part))
+        })
+    });
+
+    match found.len() {
+        0 => Err(FileSplitError::NoSplitFiles(
+            dir.to_string_lossy().to_string(),
+        )),
+        1 => Ok(found.remove(0)),
+        _ => Err(FileSplitError::AmbiguousParts(
+            found
+                .iter()
+                .map(|detected| {
+                    format!(
+                        "{}（{}）",
+                        detected.base_path.display(),
+                        detected.convention
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+        )),
+    }
+}
+
+/// 分割ファイルの名前から、考えられる名前の付け方と分割前のファイル名を挙げる
+///
+/// # 戻り値
+/// 名前の付け方の種類・分割前のファイル名・名前の付け方の組（優先する順）
+fn part_candidates(name: &str) -> Result<Vec<(SplitConvention, String, NamingScheme)>> {
+    let mut candidates = Vec::new();
+    let digits = name.len() - name.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    let head = &name[..name.len() - digits];
+
+    if digits > 0 {
+        if let Some(base) = head.strip_suffix(".part").filter(|base| !base.is_empty()) {
+            // "movie.mkv.part1"
+            candidates.push((
+                SplitConvention::PartNumber,
+                base.to_string(),
+                NamingScheme::parse("{name}.part{index}")?.min_width(1),
+            ));
+        } else if let Some(base) = head.strip_suffix('.').filter(|base| !base.is_empty()) {
+            // "archive.7z.001"
+            candidates.push((
+                SplitConvention::Numbered,
+                base.to_string(),
+                NamingScheme::parse("{name}.{index}")?.min_width(1),
+            ));
+        } else if digits >= 2 && !head.is_empty() {
+            // "x00"
+            candidates.push((
+                SplitConvention::NumericSuffix,
+                head.to_string(),
+                NamingScheme::parse("{name}{index}")?.min_width(1),
+            ));
+        }
+    }
+
+    // "archive.part01.rar"
+    if let Some((stem, ext)) = name.rsplit_once('.') {
+        let digits = stem.len() - stem.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+        if digits > 0 && !ext.is_empty() {
+            if let Some(base) = stem[..stem.len() - digits]
+                .strip_suffix(".part")
+                .filter(|base| !base.is_empty())
+            {
+                candidates.push((
+                    SplitConvention::PartNumber,
+                    format!("{base}.{ext}"),
+                    NamingScheme::parse("{stem}.part{index}{ext}")?.min_width(1),
+                ));
+            }
+        }
+    }
+
+    // "xaa"、"xzaaa"（文字数が自動的に増えたもの）、"xaaa"（文字数を固定したもの）。
+    // 分割ファイルの数が同じ場合は、接尾辞の長い（分割前のファイル名が短い）ものを優先する
+    let letters = name.len()
+        - name
+            .trim_end_matches(|c: char| c.is_ascii_lowercase())
+            .len();
+    let widening = NamingScheme::alphabetic("", None)?;
+    for length in (2..=letters.min(name.len() - 1)).rev() {
+        let base = &name[..name.len() - length];
+        if widening.parse_index(base, name).is_some() {
+            candidates.push((
+                SplitConvention::Alphabetic,
+                base.to_string(),
+                widening.clone(),
+            ));
+        }
+        candidates.push((
+            SplitConvention::Alphabetic,
+            base.to_string(),
+            NamingScheme::alphabetic("", Some(length))?,
+        ));
+    }
+    Ok(candidates)
+}
+
+/// 分割前のファイル名に対して考えられる名前の付け方を挙げる
+fn base_candidates() -> Result<Vec<(SplitConvention, NamingScheme)>> {
+    Ok(vec![
+        (
+            SplitConvention::Numbered,
+            NamingScheme::parse("{name}.{index}")?.min_width(1),
+        ),
+        (
+            SplitConvention::PartNumber,
+            NamingScheme::parse("{name}.part{index}")?.min_width(1),
+        ),
+        (
+            SplitConvention::PartNumber,
+            NamingScheme::parse("{stem}.part{index}{ext}")?.min_width(1),
+        ),
+        (
+            SplitConvention::Alphabetic,
+            NamingScheme::alphabetic("", None)?,
+        ),
+        (
+            SplitConvention::NumericSuffix,
+            NamingScheme::parse("{name}{index}")?.min_width(1),
+        ),
+    ])
+}
+
+/// 名前の付け方で分割ファイルを探し、最初の分割ファイルがあれば一式として返す
+///
+/// 番号の分割ファイルは、0番があれば0から始まるものとして扱う
+fn scan_set(
+    convention: SplitConvention,
+    base_path: &Path,
+    naming: &NamingScheme,
+) -> Result<Option<DetectedParts>> {
+    let numeric = !matches!(convention, SplitConvention::Alphabetic);
+    let starts: &[usize] = if numeric { &[0, 1] } else { &[1] };
+    for &start in starts {
+        let naming = naming.clone().start_index(start);
+        let parts = naming.scan(base_path)?;
+        if parts.contains_key(&1) {
+            let total = parts.keys().next_back().copied().unwrap_or(0);
+            return Ok(Some(DetectedParts {
+                convention,
+                base_path: base_path.to_path_buf(),
+                parts,
+                total,
+            }));
+        }
+    }
+    Ok(None)
+}
+
+/// バッチファイルかどうか
+fn is_script(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.eq_ignore_ascii_case("bat") || ext.eq_ignore_ascii_case("cmd"))
+        .unwrap_or(false)
+}
+
+/// "copy /b" で分割ファイルを連結するバッチファイルを読み込む
+///
+/// # 動作
+/// "copy /b a.001 + a.002 + a.003 a" のように "+" でつないだ行を探し、
+/// 連結するファイルを順番に、最後の名前を結合結果の出力先とする。
+/// 名前はバッチファイルと同じディレクトリのファイル名として扱い、
+/// 絶対パスや ".."、ディレクトリを含む名前はエラーにする
+/// （バッチファイルの内容で意図しないファイルを読み書きしないため）
+fn parse_copy_script(path: &Path) -> Result<DetectedParts> {
+    let bytes = fs::read(path)?;
+    let text = String::from_utf8_lossy(&bytes);
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let invalid = || FileSplitError::NoSplitFiles(path.to_string_lossy().to_string());
+
+    for line in text.lines() {
+        let line = line.trim().trim_start_matches('@');
+        let Some(args) = line
+            .get(..5)
+            .filter(|command| command.eq_ignore_ascii_case("copy "))
+            .map(|_| &line[5..])
+        else {
+            continue;
+        };
+
+        let (sources, destination) = split_copy_args(args);
+        if sources.len() < 2 {
+            continue;
+        }
+        let destination = destination.ok_or_else(invalid)?;
+        let mut parts = BTreeMap::new();
+        for (i, source) in sources.iter().enumerate() {
+            let part = dir.join(script_name(path, source)?);
+            if part.exists() {
+                parts.insert(i + 1, part);
+            }
+        }
+        return Ok(DetectedParts {
+            convention: SplitConvention::CopyScript,
+            base_path: dir.join(script_name(path, &destination)?),
+            parts,
+            total: sources.len(),
+        });
+    }
+    Err(invalid())
+}
+
+/// "copy" の引数を連結するファイルと出力先に分ける
+///
+/// # 戻り値
+/// 例: `/b "a.001" + a.002 /b out` -> (["a.001", "a.002"], Some("out"))
+fn split_copy_args(args: &str) -> (Vec<String>, Option<String>) {
+    // 引用符の外の空白と "+" で区切る（"+" は前後のファイルをつなぐ印として残す）
+    let mut tokens: Vec<Option<String>> = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in args.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            '+' if !quoted => {
+                tokens.push(Some(std::mem::take(&mut current)));
+                tokens.push(None);
+            }
+            c if c.is_whitespace() && !quoted => tokens.push(Some(std::mem::take(&mut current))),
+            c => current.push(c),
+        }
+    }
+    tokens.push(Some(current));
+
+    // 空の区切りと "/b" などのスイッチを除く
+    let tokens: Vec<Option<String>> = tokens
+        .into_iter()
+        .filter(|token| match token {
+            Some(token) => !token.is_empty() && !token.starts_with('/'),
+            None => true,
+        })
+        .collect();
+
+    let mut sources = Vec::new();
+    let mut destination = None;
+    let mut joined = true;
+    for token in tokens {
+        match token {
+            None => joined = true,
+            Some(name) if joined => {
+                sources.push(name);
+                joined = false;
+            }
+            Some(name) => {
+                destination.get_or_insert(name);
+            }
+        }
+    }
+    (sources, destination)
+}
+
+/// バッチファイルに書かれた名前を確認して返す
+///
+/// # 戻り値
+/// ディレクトリを含まない1つのファイル名。それ以外の場合はエラー
+fn script_name<'a>(script: &Path, name: &'a str) -> Result<&'a str> {
+    if is_plain_file_name(name) {
+        Ok(name)
+    } else {
+        Err(FileSplitError::UnsupportedInput(format!(
+            "{}: バッチファイルと同じディレクトリのファイル名ではありません: {name}",
+            script.display()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// テスト用のファイルを作成する
+    fn touch(dir: &Path, names: &[&str]) {
+        for name in names {
+            fs::write(dir.join(name), name).unwrap();
+        }
+    }
+
+    /// 検出結果の分割ファイル名を番号順に返す
+    fn part_names(detected: &DetectedParts) -> Vec<String> {
+        detected
+            .parts
+            .values()
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_numbered() {
+        // 7-Zip・HJSplit 形式の分割ファイルを番号順に検出するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        touch(dir, &["archive.7z.001", "archive.7z.002", "archive.7z.010"]);
+
+        let detected = detect_parts(&dir.join("archive.7z.002")).unwrap();
+        assert_eq!(detected.convention, SplitConvention::Numbered);
+        assert_eq!(detected.base_path, dir.join("archive.7z"));
+        assert_eq!(
+            part_names(&detected),
+            ["archive.7z.001", "archive.7z.002", "archive.7z.010"]
+        );
+        assert_eq!(detected.missing(), (3..=9).collect::<Vec<_>>());
+
+        // 分割前のファイル名からも検出できる
+        assert_eq!(detect_parts(&dir.join("archive.7z")).unwrap(), detected);
+    }
+
+    #[test]
+    fn test_detect_relative_path() {
+        // 親ディレクトリのない相対パスで指定した分割ファイルからも検出できることをテスト
+        let temp_dir = TempDir::new().unwrap();
+        touch(temp_dir.path(), &["d.bin.001", "d.bin.002"]);
+
+        let current_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        let result = detect_parts(Path::new("d.bin.002"));
+        std::env::set_current_dir(current_dir).unwrap();
+
+        let detected = result.unwrap();
+        assert_eq!(detected.convention, SplitConvention::Numbered);
+        assert_eq!(part_names(&detected), ["d.bin.001", "d.bin.002"]);
+    }
+
+    #[test]
+    fn test_detect_unpadded_and_zero_based() {
+        // 0埋めしない番号と0から始まる番号を数値の順に並べるテスト
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        touch(
+            dir,
+            &["movie.mkv.part1", "movie.mkv.part2", "movie.mkv.part10"],
+        );
+        touch(dir, &["x00", "x01", "x02"]);
+
+        let detected = detect_parts(&dir.join("movie.mkv.part10")).unwrap();
+        assert_eq!(detected.convention, SplitConvention::PartNumber);
+        assert_eq!(detected.base_path, dir.join("movie.mkv"));
+        assert_eq!(detected.total, 10);
+        assert_eq!(detected.parts[&10], dir.join("movie.mkv.part10"));
+
+        let detected = detect_parts(&dir.join("x01")).unwrap();
+        assert_eq!(detected.convention, SplitConvention::NumericSuffix);
+        assert_eq!(detected.base_path, dir.join("x"));
+        assert_eq!(part_names(&detected), ["x00", "x01", "x02"]);
+        assert!(detected.missing().is_empty());
+    }
+
+    #[test]
+    fn test_detect_part_before_extension() {
+        // 拡張子の前に番号がある .partN 形式を検出するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        touch(dir, &["backup.part01.rar", "backup.part02.rar"]);
+
+        let detected = detect_parts(&dir.join("backup.part01.rar")).unwrap();
+        assert_eq!(detected.convention, SplitConvention::PartNumber);
+        assert_eq!(detected.base_path, dir.join("backup.rar"));
+        assert_eq!(
+            part_names(&detected),
+            ["backup.part01.rar", "backup.part02.rar"]
+        );
+    }
+
+    #[test]
+    fn test_detect_alphabetic() {
+        // GNU split 形式の分割ファイルを検出するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        touch(dir, &["xaa", "xab", "xyz", "xzaaa"]);
+
+        let detected = detect_parts(&dir.join("xzaaa")).unwrap();
+        assert_eq!(detected.convention, SplitConvention::Alphabetic);
+        assert_eq!(detected.base_path, dir.join("x"));
+        assert_eq!(detected.total, 651);
+        assert_eq!(part_names(&detected), ["xaa", "xab", "xyz", "xzaaa"]);
+
+        // 文字数を固定したもの（split -a 3）
+        touch(dir, &["data.aaa", "data.aab"]);
+        let detected = detect_parts(&dir.join("data.aab")).unwrap();
+        assert_eq!(detected.base_path, dir.join("data."));
+        assert_eq!(part_names(&detected), ["data.aaa", "data.aab"]);
+    }
+
+    #[test]
+    fn test_detect_copy_script() {
+        // copy /b のバッチファイルに書かれた順番で分割ファイルを並べるテスト
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        touch(dir, &["video.mp4.001", "video.mp4.002", "video.mp4.003"]);
+        fs::write(
+            dir.join("join.bat"),
+            "@echo off\r\nCOPY /B \"video.mp4.001\"+video.mp4.003 + video.mp4.002 \"video.mp4\"\r\n",
+        )
+        .unwrap();
+
+        let detected = detect_parts(&dir.join("join.bat")).unwrap();
+        assert_eq!(detected.convention, SplitConvention::CopyScript);
+        assert_eq!(detected.base_path, dir.join("video.mp4"));
+        assert_eq!(detected.total, 3);
+        assert_eq!(detected.parts[&2], dir.join("video.mp4.003"));
+    }
+
+    #[test]
+    fn test_detect_copy_script_rejects_other_dirs() {
+        // バッチファイルと別のディレクトリのファイルを指す名前をエラーにするテスト
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        touch(dir, &["a.001", "a.002"]);
+        for line in [
+            "copy /b a.001 + a.002 ..\\..\\escaped",
+            "copy /b a.001 + a.002 ../escaped",
+            "copy /b a.001 + a.002 C:/escaped",
+            "copy /b a.001 + C:\\secret a",
+            "copy /b a.001 + sub\\a.002 a",
+            "copy /b a.001 + .. a",
+        ] {
+            fs::write(dir.join("join.bat"), line).unwrap();
+            assert!(
+                matches!(
+                    detect_parts(&dir.join("join.bat")),
+                    Err(FileSplitError::UnsupportedInput(_))
+                ),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_split_copy_args() {
+        // copy の引数の解析をテスト
+        assert_eq!(
+            split_copy_args("/b a.001 + a.002 /b a"),
+            (
+                vec!["a.001".to_string(), "a.002".to_string()],
+                Some("a".to_string())
+            )
+        );
+        assert_eq!(
+            split_copy_args("/b \"my file.001\"+\"my file.002\""),
+            (
+                vec!["my file.001".to_string(), "my file.002".to_string()],
+                None
+            )
+        );
+    }
+
+    #[test]
+    fn test_detect_in_dir() {
+        // ディレクトリから分割ファイル一式を検出するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        touch(dir, &["photo.jpg.001", "photo.jpg.002", "notes.txt", "baa"]);
+
+        let detected = detect_parts(dir).unwrap();
+        assert_eq!(detected.base_path, dir.join("photo.jpg"));
+        assert_eq!(part_names(&detected), ["photo.jpg.001", "photo.jpg.002"]);
+
+        // 複数の一式がある場合はどれを結合するか決められない
+        touch(dir, &["xaa", "xab"]);
+        assert!(matches!(
+            detect_parts(dir),
+            Err(FileSplitError::AmbiguousParts(_))
+        ));
+
+        // 分割ファイルがない場合
+        let empty = TempDir::new().unwrap();
+        assert!(matches!(
+            detect_parts(empty.path()),
+            Err(FileSplitError::NoSplitFiles(_))
+        ));
+    }
+}
@@ -0,0 +1,201 @@
+use crate::error::{FileSplitError, Result};
+
+/// 分割時に文字境界を考慮する文字エンコーディング
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// UTF-8（BOMの有無を問わない）
+    Utf8,
+    /// Shift_JIS（CP932を含む）
+    ShiftJis,
+    /// EUC-JP
+    EucJp,
+    /// UTF-16（BOMからバイト順を判定、BOMがなければビッグエンディアン）
+    Utf16,
+    /// UTF-16 リトルエンディアン
+    Utf16Le,
+    /// UTF-16 ビッグエンディアン
+    Utf16Be,
+}
+
+impl Encoding {
+    /// 1文字の最大バイト数
+    pub const MAX_CHAR_LEN: usize = 4;
+
+    /// 文字列からエンコーディングをパースする（大文字・小文字は区別しない）
+    ///
+    /// # 引数
+    /// * `s` - エンコーディング名（例: "utf-8", "shift_jis", "euc-jp", "utf-16le"）
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().replace('_', "-").as_str() {
+            "utf-8" | "utf8" => Ok(Encoding::Utf8),
+            "shift-jis" | "sjis" | "cp932" | "windows-31j" => Ok(Encoding::ShiftJis),
+            "euc-jp" | "eucjp" => Ok(Encoding::EucJp),
+            "utf-16" | "utf16" => Ok(Encoding::Utf16),
+            "utf-16le" | "utf16le" => Ok(Encoding::Utf16Le),
+            "utf-16be" | "utf16be" => Ok(Encoding::Utf16Be),
+            _ => Err(FileSplitError::InvalidEncoding(s.to_string())),
+        }
+    }
+
+    /// ファイル先頭のBOMからバイト順を確定したエンコーディングを返す
+    ///
+    /// # 引数
+    /// * `head` - ファイルの先頭データ
+    ///
+    /// # 戻り値
+    /// `Utf16` の場合はBOMに応じて `Utf16Le` または `Utf16Be`、それ以外はそのまま
+    pub fn resolve(self, head: &[u8]) -> Self {
+        match self {
+            Encoding::Utf16 if head.starts_with(&[0xFF, 0xFE]) => Encoding::Utf16Le,
+            Encoding::Utf16 => Encoding::Utf16Be,
+            other => other,
+        }
+    }
+
+    /// 先頭バイトから1文字のバイト数を求める
+    ///
+    /// # 引数
+    /// * `data` - 文字境界から始まるデータ（空でないこと）
+    ///
+    /// # 戻り値
+    /// 文字のバイト数（データが途中で終わっている場合はdataの長さを超えることがある）
+    pub fn char_len(&self, data: &[u8]) -> usize {
+        let lead = data[0];
+        match self {
+            Encoding::Utf8 => match lead {
+                0xC0..=0xDF => 2,
+                0xE0..=0xEF => 3,
+                0xF0..=0xF7 => 4,
+                // ASCIIと不正なバイトは1バイトとして扱う
+                _ => 1,
+            },
+            Encoding::ShiftJis => match lead {
+                0x81..=0x9F | 0xE0..=0xFC => 2,
+                _ => 1,
+            },
+            Encoding::EucJp => match lead {
+                0x8F => 3,
+                0x8E | 0xA1..=0xFE => 2,
+                _ => 1,
+            },
+            Encoding::Utf16 | Encoding::Utf16Be | Encoding::Utf16Le => {
+                if data.len() < 2 {
+                    return 2;
+                }
+                let unit = if *self == Encoding::Utf16Le {
+                    u16::from_le_bytes([data[0], data[1]])
+                } else {
+                    u16::from_be_bytes([data[0], data[1]])
+                };
+                // 上位サロゲートは下位サロゲートと組で1文字
+                if (0xD800..=0xDBFF).contains(&unit) {
+                    4
+                } else {
+                    2
+                }
+            }
+        }
+    }
+
+    /// データ内で最後の文字境界を求める
+    ///
+    /// # 引数
+    /// * `data` - 文字境界から始まるデータ
+    ///
+    /// # 戻り値
+    /// 文字の途中で切れない最大のバイト数
+    pub fn last_boundary(&self, data: &[u8]) -> usize {
+        // マルチバイト文字は後ろから判定できないため、先頭から順に読み進める
+        let mut pos = 0;
+        while pos < data.len() {
+            let len = self.char_len(&data[pos..]);
+            if pos + len > data.len() {
+                break;
+            }
+            pos += len;
+        }
+        pos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_encoding() {
+        // エンコーディング名のパースをテスト
+        assert_eq!(Encoding::parse("UTF-8").unwrap(), Encoding::Utf8);
+        assert_eq!(Encoding::parse("Shift_JIS").unwrap(), Encoding::ShiftJis);
+        assert_eq!(Encoding::parse("cp932").unwrap(), Encoding::ShiftJis);
+        assert_eq!(Encoding::parse("euc-jp").unwrap(), Encoding::EucJp);
+        assert_eq!(Encoding::parse("utf-16").unwrap(), Encoding::Utf16);
+        assert_eq!(Encoding::parse("UTF-16LE").unwrap(), Encoding::Utf16Le);
+        assert_eq!(Encoding::parse("utf-16be").unwrap(), Encoding::Utf16Be);
+        assert!(Encoding::parse("latin1").is_err());
+    }
+
+    #[test]
+    fn test_resolve_utf16_bom() {
+        // BOMからUTF-16のバイト順を判定するテスト
+        assert_eq!(
+            Encoding::Utf16.resolve(&[0xFF, 0xFE, 0x42, 0x30]),
+            Encoding::Utf16Le
+        );
+        assert_eq!(
+            Encoding::Utf16.resolve(&[0xFE, 0xFF, 0x30, 0x42]),
+            Encoding::Utf16Be
+        );
+        assert_eq!(Encoding::Utf16.resolve(b""), Encoding::Utf16Be);
+        assert_eq!(Encoding::Utf8.resolve(&[0xFF, 0xFE]), Encoding::Utf8);
+    }
+
+    #[test]
+    fn test_last_boundary_utf8() {
+        // UTF-8の文字境界をテスト（"aあい" = 1 + 3 + 3 バイト）
+        let data = "aあい".as_bytes();
+        assert_eq!(Encoding::Utf8.last_boundary(&data[..7]), 7);
+        assert_eq!(Encoding::Utf8.last_boundary(&data[..6]), 4);
+        assert_eq!(Encoding::Utf8.last_boundary(&data[..3]), 1);
+
+        // BOMは1つの3バイト文字として扱われる
+        let with_bom = [0xEF, 0xBB, 0xBF, b'a'];
+        assert_eq!(Encoding::Utf8.last_boundary(&with_bom[..2]), 0);
+        assert_eq!(Encoding::Utf8.last_boundary(&with_bom[..3]), 3);
+    }
+
+    #[test]
+    fn test_last_boundary_shift_jis() {
+        // Shift_JISの文字境界をテスト（"aあｱい" = 61 82A0 B1 82A2）
+        let data = [0x61, 0x82, 0xA0, 0xB1, 0x82, 0xA2];
+        assert_eq!(Encoding::ShiftJis.last_boundary(&data[..2]), 1);
+        assert_eq!(Encoding::ShiftJis.last_boundary(&data[..4]), 4);
+        assert_eq!(Encoding::ShiftJis.last_boundary(&data[..5]), 4);
+        // 2バイト目がASCII範囲でも先頭から判定するので誤らない（"ソ" = 83 5C）
+        let so = [0x83, 0x5C, 0x83, 0x5C];
+        assert_eq!(Encoding::ShiftJis.last_boundary(&so[..3]), 2);
+    }
+
+    #[test]
+    fn test_last_boundary_euc_jp() {
+        // EUC-JPの文字境界をテスト（"aあ" + 半角カナ + 補助漢字）
+        let data = [0x61, 0xA4, 0xA2, 0x8E, 0xB1, 0x8F, 0xB0, 0xA1];
+        assert_eq!(Encoding::EucJp.last_boundary(&data[..2]), 1);
+        assert_eq!(Encoding::EucJp.last_boundary(&data[..4]), 3);
+        assert_eq!(Encoding::EucJp.last_boundary(&data[..7]), 5);
+        assert_eq!(Encoding::EucJp.last_boundary(&data), 8);
+    }
+
+    #[test]
+    fn test_last_boundary_utf16() {
+        // UTF-16のサロゲートペアを分割しないことをテスト（"あ" + U+1F600）
+        let le = [0x42, 0x30, 0x3D, 0xD8, 0x00, 0xDE];
+        assert_eq!(Encoding::Utf16Le.last_boundary(&le[..3]), 2);
+        assert_eq!(Encoding::Utf16Le.last_boundary(&le[..5]), 2);
+        assert_eq!(Encoding::Utf16Le.last_boundary(&le), 6);
+
+        let be = [0x30, 0x42, 0xD8, 0x3D, 0xDE, 0x00];
+        assert_eq!(Encoding::Utf16Be.last_boundary(&be[..4]), 2);
+        assert_eq!(Encoding::Utf16Be.last_boundary(&be), 6);
+    }
+}
@@ -2,6 +2,7 @@ use std::fmt;
 
 /// ファイル分割・結合処理で発生するエラーを定義
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum FileSplitError {
     /// 無効なサイズ指定
     InvalidSize(String),
@@ -11,6 +12,36 @@ pub enum FileSplitError {
     IoError(std::io::Error),
     /// 分割ファイルが見つからない
     NoSplitFiles(String),
+    /// タイムスタンプの指定または解析の誤り
+    InvalidTimestamp(String),
+    /// 未対応のエンコーディング指定
+    InvalidEncoding(String),
+    /// 分割ファイルのヘッダー・フッターがテンプレートと一致しない
+    TemplateMismatch(String),
+    /// マニフェストの形式が不正
+    InvalidManifest(String),
+    /// 分割ファイルの内容が記録と一致しない
+    CorruptedPart(String),
+    /// 分割ファイルの連番に欠けがある（欠けている番号）
+    MissingParts(Vec<usize>),
+    /// コンテナ形式のヘッダーが不正
+    InvalidContainer(String),
+    /// 未対応のチェックサム形式の指定
+    InvalidChecksumFormat(String),
+    /// 出力先のファイルが既に存在する
+    OutputExists(String),
+    /// 分割ファイルの検証で問題が見つかった
+    VerificationFailed(String),
+    /// 入力の種類に対して使用できない指定
+    UnsupportedInput(String),
+    /// 分割ファイルを渡すコマンドが失敗した
+    FilterFailed(String),
+    /// 分割ファイル名のテンプレートが不正
+    InvalidNaming(String),
+    /// 分割ファイル名の接尾辞が足りない
+    SuffixExhausted(String),
+    /// 同じ番号の分割ファイルが複数ある
+    AmbiguousParts(String),
 }
 
 /// エラーメッセージの表示形式を定義
@@ -24,8 +55,78 @@ impl fmt::Display for FileSplitError {
                 f,
                 "分割ファイルが見つかりません: {base}.001, {base}.002, ..."
             ),
+            FileSplitError::InvalidTimestamp(msg) => write!(f, "タイムスタンプエラー: {msg}"),
+            FileSplitError::InvalidEncoding(name) => {
+                write!(f, "未対応のエンコーディングです: {name}")
+            }
+            FileSplitError::TemplateMismatch(path) => {
+                write!(f, "ヘッダー・フッターがテンプレートと一致しません: {path}")
+            }
+            FileSplitError::InvalidManifest(msg) => write!(f, "マニフェストが不正です: {msg}"),
+            FileSplitError::CorruptedPart(msg) => {
+                write!(f, "分割ファイルが記録と一致しません: {msg}")
+            }
+            FileSplitError::MissingParts(indices) => {
+                write!(
+                    f,
+                    "分割ファイルが欠けています（番号: {}）",
+                    format_ranges(indices)
+                )
+            }
+            FileSplitError::InvalidContainer(msg) => {
+                write!(f, "分割ファイルのヘッダーが不正です: {msg}")
+            }
+            FileSplitError::InvalidChecksumFormat(name) => write!(
+                f,
+                "未対応のチェックサム形式です: {name}（sfv, md5, sha256 のいずれかを指定してください）"
+            ),
+            FileSplitError::OutputExists(path) => write!(
+                f,
+                "出力先のファイルが既に存在します（上書きするには --force を指定してください）: {path}"
+            ),
+            FileSplitError::VerificationFailed(msg) => write!(f, "検証に失敗しました: {msg}"),
+            FileSplitError::UnsupportedInput(msg) => {
+                write!(f, "この入力では使用できない指定です: {msg}")
+            }
+            FileSplitError::FilterFailed(msg) => {
+                write!(f, "フィルターコマンドが失敗しました: {msg}")
+            }
+            FileSplitError::InvalidNaming(msg) => {
+                write!(f, "分割ファイル名のテンプレートが不正です: {msg}")
+            }
+            FileSplitError::SuffixExhausted(msg) => {
+                write!(f, "分割ファイル名の接尾辞が足りません: {msg}")
+            }
+            FileSplitError::AmbiguousParts(msg) => {
+                write!(f, "同じ番号の分割ファイルが複数あります: {msg}")
+            }
+        }
+    }
+}
+
+/// 番号の一覧を連続する範囲にまとめた文字列に変換する
+///
+/// # 戻り値
+/// 例: [1, 2, 3, 5] -> "1-3, 5"
+fn format_ranges(indices: &[usize]) -> String {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &index in indices {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == index => *end = index,
+            _ => ranges.push((index, index)),
         }
     }
+    ranges
+        .iter()
+        .map(|&(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{start}-{end}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 impl std::error::Error for FileSplitError {}
@@ -58,6 +159,81 @@ mod tests {
             err.to_string(),
             "分割ファイルが見つかりません: test.txt.001, test.txt.002, ..."
         );
+
+        let err = FileSplitError::InvalidTimestamp("bad".to_string());
+        assert_eq!(err.to_string(), "タイムスタンプエラー: bad");
+
+        let err = FileSplitError::InvalidEncoding("latin1".to_string());
+        assert_eq!(err.to_string(), "未対応のエンコーディングです: latin1");
+
+        let err = FileSplitError::TemplateMismatch("test.txt.001".to_string());
+        assert_eq!(
+            err.to_string(),
+            "ヘッダー・フッターがテンプレートと一致しません: test.txt.001"
+        );
+
+        let err = FileSplitError::InvalidManifest("broken".to_string());
+        assert_eq!(err.to_string(), "マニフェストが不正です: broken");
+
+        let err = FileSplitError::CorruptedPart("test.txt.001".to_string());
+        assert_eq!(
+            err.to_string(),
+            "分割ファイルが記録と一致しません: test.txt.001"
+        );
+
+        let err = FileSplitError::MissingParts(vec![1, 2, 3, 5, 7, 8]);
+        assert_eq!(
+            err.to_string(),
+            "分割ファイルが欠けています（番号: 1-3, 5, 7-8）"
+        );
+
+        let err = FileSplitError::InvalidContainer("part".to_string());
+        assert_eq!(err.to_string(), "分割ファイルのヘッダーが不正です: part");
+
+        let err = FileSplitError::InvalidChecksumFormat("sha1".to_string());
+        assert_eq!(
+            err.to_string(),
+            "未対応のチェックサム形式です: sha1（sfv, md5, sha256 のいずれかを指定してください）"
+        );
+
+        let err = FileSplitError::OutputExists("out.bin".to_string());
+        assert_eq!(
+            err.to_string(),
+            "出力先のファイルが既に存在します（上書きするには --force を指定してください）: out.bin"
+        );
+
+        let err = FileSplitError::VerificationFailed("欠落 1".to_string());
+        assert_eq!(err.to_string(), "検証に失敗しました: 欠落 1");
+
+        let err = FileSplitError::FilterFailed("gzip: exit status: 1".to_string());
+        assert_eq!(
+            err.to_string(),
+            "フィルターコマンドが失敗しました: gzip: exit status: 1"
+        );
+
+        let err = FileSplitError::InvalidNaming("{index} が含まれていません: {name}".to_string());
+        assert_eq!(
+            err.to_string(),
+            "分割ファイル名のテンプレートが不正です: {index} が含まれていません: {name}"
+        );
+
+        let err = FileSplitError::SuffixExhausted("上限 676 個".to_string());
+        assert_eq!(
+            err.to_string(),
+            "分割ファイル名の接尾辞が足りません: 上限 676 個"
+        );
+
+        let err = FileSplitError::AmbiguousParts("a.001 と a.0001".to_string());
+        assert_eq!(
+            err.to_string(),
+            "同じ番号の分割ファイルが複数あります: a.001 と a.0001"
+        );
+
+        let err = FileSplitError::UnsupportedInput("--container".to_string());
+        assert_eq!(
+            err.to_string(),
+            "この入力では使用できない指定です: --container"
+        );
     }
 
     #[test]
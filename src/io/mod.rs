@@ -1,7 +1,9 @@
 // I/O関連のサブモジュールを宣言
+pub mod output;
 pub mod reader;
 pub mod writer;
 
 // 公開APIとして再エクスポート
+pub use output::OutputFile;
 pub use reader::BufferedReader;
-pub use writer::SplitFileWriter;
+pub use writer::{SplitFileWriter, WrittenPart};
@@ -0,0 +1,200 @@
+use crate::error::{FileSplitError, Result};
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
+
+/// 結合結果を安全に書き込む出力ファイル
+///
+/// 出力先と同じディレクトリの一時ファイルに書き込み、
+/// `commit` で出力先の名前に置き換える。
+/// `commit` する前に破棄された場合は一時ファイルを削除し、出力先には何も残さない
+pub struct OutputFile {
+    /// 出力先のパス
+    path: PathBuf,
+    /// 既存のファイルを上書きするかどうか
+    force: bool,
+    /// 書き込み中の一時ファイル
+    writer: BufWriter<NamedTempFile>,
+}
+
+impl OutputFile {
+    /// 出力先と同じディレクトリに一時ファイルを作成する
+    ///
+    /// # 引数
+    /// * `path` - 出力先のパス
+    /// * `force` - 既存のファイルを上書きするかどうか
+    ///
+    /// # 戻り値
+    /// 作成したOutputFile、または上書きが許可されていないのに出力先が存在する場合はエラー
+    pub fn create(path: &Path, force: bool) -> Result<Self> {
+        if !force && path.exists() {
+            return Err(FileSplitError::OutputExists(
+                path.to_string_lossy().to_string(),
+            ));
+        }
+
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let prefix = format!(
+            ".{}.",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        );
+        let mut builder = tempfile::Builder::new();
+        builder.prefix(&prefix).suffix(".tmp");
+        // 一時ファイルは既定で 0600 になるため、File::create と同じく umask を適用した 0666 で作成する
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            builder.permissions(fs::Permissions::from_mode(0o666));
+        }
+        let temp = builder.tempfile_in(dir)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            force,
+            writer: BufWriter::new(temp),
+        })
+    }
+
+    /// 書き込み中の一時ファイルのパスを返す
+    pub fn temp_path(&self) -> &Path {
+        self.writer.get_ref().path()
+    }
+
+    /// 一時ファイルを出力先の名前に置き換えて書き込みを完了する
+    pub fn commit(self) -> Result<()> {
+        let temp = self.writer.into_inner().map_err(|e| e.into_error())?;
+        temp.as_file().sync_all()?;
+
+        // 既存のファイルを置き換える場合は、そのファイルのパーミッションを引き継ぐ
+        if self.force {
+            if let Ok(metadata) = fs::metadata(&self.path) {
+                if metadata.is_file() {
+                    fs::set_permissions(temp.path(), metadata.permissions())?;
+                }
+            }
+        }
+
+        let result = if self.force {
+            temp.persist(&self.path).map(|_| ())
+        } else {
+            // 書き込み中に作成された出力先も上書きしない
+            temp.persist_noclobber(&self.path).map(|_| ())
+        };
+        result.map_err(|e| {
+            if e.error.kind() == std::io::ErrorKind::AlreadyExists {
+                FileSplitError::OutputExists(self.path.to_string_lossy().to_string())
+            } else {
+                e.error.into()
+            }
+        })
+    }
+}
+
+impl Write for OutputFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// ディレクトリ内のファイル名を列挙する
+    fn list_dir(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_commit_output() {
+        // 一時ファイルに書き込んで出力先に置き換えるテスト
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("out.bin");
+
+        let mut output = OutputFile::create(&path, false).unwrap();
+        output.write_all(b"data").unwrap();
+        assert!(!path.exists());
+        assert!(output.temp_path().starts_with(temp_dir.path()));
+
+        output.commit().unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"data");
+        assert_eq!(list_dir(temp_dir.path()), vec!["out.bin"]);
+    }
+
+    #[test]
+    fn test_drop_without_commit() {
+        // 完了前に破棄した場合は何も残らないテスト
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("out.bin");
+
+        let mut output = OutputFile::create(&path, false).unwrap();
+        output.write_all(b"partial").unwrap();
+        drop(output);
+
+        assert!(list_dir(temp_dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_refuse_existing_output() {
+        // 既存のファイルは上書きが許可されている場合だけ置き換えるテスト
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("out.bin");
+        fs::write(&path, "original").unwrap();
+
+        assert!(matches!(
+            OutputFile::create(&path, false),
+            Err(FileSplitError::OutputExists(_))
+        ));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "original");
+
+        let mut output = OutputFile::create(&path, true).unwrap();
+        output.write_all(b"merged").unwrap();
+        output.commit().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "merged");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_commit_mode() {
+        // 出力先は File::create と同じパーミッションになり、上書き時は元のパーミッションを引き継ぐテスト
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let reference = temp_dir.path().join("reference.bin");
+        fs::File::create(&reference).unwrap();
+        let expected = fs::metadata(&reference).unwrap().permissions().mode() & 0o7777;
+        fs::remove_file(&reference).unwrap();
+
+        let path = temp_dir.path().join("out.bin");
+        let mut output = OutputFile::create(&path, false).unwrap();
+        output.write_all(b"data").unwrap();
+        output.commit().unwrap();
+        assert_eq!(
+            fs::metadata(&path).unwrap().permissions().mode() & 0o7777,
+            expected
+        );
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+        let mut output = OutputFile::create(&path, true).unwrap();
+        output.write_all(b"merged").unwrap();
+        output.commit().unwrap();
+        assert_eq!(
+            fs::metadata(&path).unwrap().permissions().mode() & 0o7777,
+            0o640
+        );
+    }
+}
@@ -1,12 +1,12 @@
 use crate::error::Result;
 use std::fs::File;
-use std::io::{BufReader, Read};
+use std::io::{BufReader, ErrorKind, Read};
 use std::path::Path;
 
-/// バッファ付きファイルリーダー
-/// 大きなファイルを効率的に読み込むための構造体
-pub struct BufferedReader {
-    reader: BufReader<File>,
+/// バッファ付きリーダー
+/// 大きなファイルやパイプを効率的に読み込むための構造体
+pub struct BufferedReader<R = File> {
+    reader: BufReader<R>,
 }
 
 impl BufferedReader {
@@ -16,20 +16,43 @@ impl BufferedReader {
     /// * `path` - 読み込むファイルのパス
     pub fn new(path: &Path) -> Result<Self> {
         let file = File::open(path)?;
-        Ok(Self {
-            reader: BufReader::new(file),
-        })
+        Ok(Self::from_reader(file))
+    }
+}
+
+impl<R: Read> BufferedReader<R> {
+    /// 任意の入力から読み込むBufferedReaderを作成する
+    ///
+    /// # 引数
+    /// * `reader` - 読み込む入力（パイプや標準入力など）
+    pub fn from_reader(reader: R) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+        }
     }
 
-    /// ファイルから指定されたバッファサイズ分のデータを読み込む
+    /// 入力から指定されたバッファサイズ分のデータを読み込む
     ///
     /// # 引数
     /// * `buffer` - 読み込んだデータを格納するバッファ
     ///
+    /// # 動作
+    /// パイプなどで1回の読み込みが途中で返ってきても、
+    /// バッファがいっぱいになるか入力の終端に達するまで読み込みを繰り返す
+    ///
     /// # 戻り値
-    /// 実際に読み込んだバイト数
+    /// 実際に読み込んだバイト数（入力の終端に達した場合だけバッファより少ない）
     pub fn read_chunk(&mut self, buffer: &mut [u8]) -> Result<usize> {
-        Ok(self.reader.read(buffer)?)
+        let mut filled = 0;
+        while filled < buffer.len() {
+            match self.reader.read(&mut buffer[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(filled)
     }
 }
 
@@ -82,6 +105,43 @@ mod tests {
         assert_eq!(bytes_read, 0);
     }
 
+    /// 1回の読み込みで少しずつしか返さない入力
+    struct ShortReader<'a> {
+        data: &'a [u8],
+        calls: usize,
+    }
+
+    impl Read for ShortReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.calls += 1;
+            // 割り込みも混ぜる
+            if self.calls.is_multiple_of(3) {
+                return Err(ErrorKind::Interrupted.into());
+            }
+            let len = buf.len().min(self.data.len()).min(3);
+            buf[..len].copy_from_slice(&self.data[..len]);
+            self.data = &self.data[len..];
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn test_buffered_reader_fills_buffer() {
+        // 読み込みが途中で返ってきてもバッファを埋めることをテスト
+        let source = ShortReader {
+            data: b"0123456789abc",
+            calls: 0,
+        };
+        let mut reader = BufferedReader::from_reader(source);
+        let mut buffer = vec![0u8; 10];
+
+        assert_eq!(reader.read_chunk(&mut buffer).unwrap(), 10);
+        assert_eq!(&buffer, b"0123456789");
+        assert_eq!(reader.read_chunk(&mut buffer).unwrap(), 3);
+        assert_eq!(&buffer[..3], b"abc");
+        assert_eq!(reader.read_chunk(&mut buffer).unwrap(), 0);
+    }
+
     #[test]
     fn test_buffered_reader_file_not_found() {
         // 存在しないファイルを開こうとした場合のエラーテスト
@@ -1,7 +1,90 @@
-use crate::error::Result;
+use crate::checksum::{self, ChecksumFormat, MultiHasher};
+use crate::container::{PartHeader, HEADER_LEN};
+use crate::error::{FileSplitError, Result};
+use crate::manifest::to_hex;
+use crate::naming::NamingScheme;
+use sha2::{Digest, Sha256};
 use std::fs::File;
-use std::io::{BufWriter, Write};
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+/// 分割ファイルの名前の基準にするパスを決める
+///
+/// # 引数
+/// * `path` - 元ファイルのパス
+/// * `output_dir` - 分割ファイルを置くディレクトリ（Noneの場合は元ファイルと同じ場所）
+/// * `prefix` - 元ファイル名の代わりに使用する名前
+///
+/// # 戻り値
+/// 例: ("data/file.txt", Some("/mnt/parts"), Some("backup")) -> "/mnt/parts/backup"
+pub(crate) fn part_base_path(
+    path: &Path,
+    output_dir: Option<&Path>,
+    prefix: Option<&Path>,
+) -> PathBuf {
+    match (output_dir, prefix) {
+        (Some(dir), Some(prefix)) => dir.join(prefix),
+        (Some(dir), None) => dir.join(path.file_name().unwrap_or_default()),
+        (None, Some(prefix)) => prefix.to_path_buf(),
+        (None, None) => path.to_path_buf(),
+    }
+}
+
+/// 書き込みを終えた分割ファイルの情報
+#[derive(Debug, Clone)]
+pub struct WrittenPart {
+    /// 分割ファイルのパス（フィルターコマンドに渡した場合は $FILE の値）
+    pub path: PathBuf,
+    /// 元ファイルのデータのバイト数（ヘッダー・フッターを除く）
+    pub length: u64,
+    /// 分割ファイル全体のバイト数
+    pub size: u64,
+    /// 分割ファイル全体のSHA-256ハッシュ（16進数文字列）
+    pub sha256: String,
+    /// チェックサムファイル用のチェックサム（`with_checksums` を指定した場合）
+    pub(crate) checksums: Option<MultiHasher>,
+}
+
+/// 分割ファイルの書き込み先
+enum PartSink {
+    /// 分割ファイル
+    File(BufWriter<File>),
+    /// フィルターコマンドの標準入力
+    Command {
+        child: Child,
+        stdin: BufWriter<ChildStdin>,
+    },
+}
+
+/// 書き込み中の分割ファイル
+struct OpenPart {
+    path: PathBuf,
+    sink: PartSink,
+    /// コンテナ形式のヘッダーに続くデータのSHA-256
+    hasher: Sha256,
+    /// コンテナ形式のヘッダーに続くデータのチェックサム
+    checksums: Option<MultiHasher>,
+    /// コンテナ形式のヘッダーに続くデータのバイト数
+    payload_len: u64,
+    /// 元ファイルのデータのバイト数
+    data_len: u64,
+}
+
+impl OpenPart {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        match &mut self.sink {
+            PartSink::File(writer) => writer.write_all(bytes)?,
+            PartSink::Command { stdin, .. } => stdin.write_all(bytes)?,
+        }
+        self.hasher.update(bytes);
+        if let Some(checksums) = &mut self.checksums {
+            checksums.update(bytes);
+        }
+        self.payload_len += bytes.len() as u64;
+        Ok(())
+    }
+}
 
 /// 分割ファイルの書き込みを管理する構造体
 pub struct SplitFileWriter {
@@ -9,6 +92,22 @@ pub struct SplitFileWriter {
     base_path: PathBuf,
     /// 現在の分割ファイルインデックス
     current_index: usize,
+    /// コンテナ形式で書き込む場合の分割ファイル一式の識別子と総数
+    container: Option<(String, usize)>,
+    /// 次の分割ファイルのデータの元ファイル内での開始位置
+    offset: u64,
+    /// 直前に書き込んだ分割ファイルのヘッダー
+    last_header: Option<PartHeader>,
+    /// 書き込み中の分割ファイル
+    current: Option<OpenPart>,
+    /// ファイルの代わりに各分割ファイルを渡すコマンド
+    filter: Option<String>,
+    /// 事前に数えた各分割ファイルのデータのバイト数
+    part_sizes: Vec<u64>,
+    /// チェックサムファイル用のチェックサムを計算するかどうか
+    checksums: bool,
+    /// 分割ファイルの名前の付け方
+    naming: NamingScheme,
 }
 
 impl SplitFileWriter {
@@ -20,9 +119,68 @@ impl SplitFileWriter {
         Self {
             base_path: base_path.to_path_buf(),
             current_index: 1,
+            container: None,
+            offset: 0,
+            last_header: None,
+            current: None,
+            filter: None,
+            part_sizes: Vec::new(),
+            checksums: false,
+            naming: NamingScheme::default(),
         }
     }
 
+    /// 分割ファイルの名前の付け方を指定する
+    pub fn with_naming(mut self, naming: NamingScheme) -> Self {
+        self.naming = naming;
+        self
+    }
+
+    /// 各分割ファイルの先頭にコンテナ形式のヘッダーを付けて書き込むようにする
+    ///
+    /// # 引数
+    /// * `set_id` - 分割ファイル一式の識別子
+    /// * `total` - 分割ファイルの総数
+    pub fn with_container(mut self, set_id: &str, total: usize) -> Self {
+        self.container = Some((set_id.to_string(), total));
+        self
+    }
+
+    /// 各分割ファイルをファイルに書き込む代わりにコマンドの標準入力に渡すようにする
+    ///
+    /// # 引数
+    /// * `command` - シェル（Windowsでは cmd）で実行するコマンド
+    ///
+    /// # 動作
+    /// 分割ファイルごとにコマンドを起動し、環境変数 `FILE` に分割ファイルのパス、
+    /// `FILESPLIT_INDEX` に番号、`FILESPLIT_SIZE` にデータのバイト数
+    /// （`with_part_sizes` で指定した場合）を設定する。
+    /// コンテナ形式のヘッダーは後から書き込めないため同時に使用できない
+    pub fn with_filter(mut self, command: &str) -> Self {
+        self.filter = Some(command.to_string());
+        self
+    }
+
+    /// フィルターコマンドに渡す各分割ファイルのデータのバイト数を指定する
+    pub fn with_part_sizes(mut self, sizes: Vec<u64>) -> Self {
+        self.part_sizes = sizes;
+        self
+    }
+
+    /// チェックサムファイル用のチェックサムも書き込みながら計算するようにする
+    pub fn with_checksums(mut self) -> Self {
+        self.checksums = true;
+        self
+    }
+
+    /// 直前に書き込んだ分割ファイルのヘッダーを返す
+    ///
+    /// # 戻り値
+    /// コンテナ形式で書き込んだ場合のヘッダー、それ以外はNone
+    pub fn last_header(&self) -> Option<&PartHeader> {
+        self.last_header.as_ref()
+    }
+
     /// 次の分割ファイルにデータを書き込む
     ///
     /// # 引数
@@ -31,32 +189,253 @@ impl SplitFileWriter {
     /// # 動作
     /// ファイル名に連番（.001, .002, ...）を付けて保存し、
     /// インデックスをインクリメントする
-    pub fn write_next_file(&mut self, data: &[u8]) -> Result<()> {
-        let file_path = self.get_split_file_path(self.current_index);
-        let file = File::create(&file_path)?;
-        let mut writer = BufWriter::new(file);
-        writer.write_all(data)?;
-        writer.flush()?;
-        self.current_index += 1;
+    ///
+    /// # 戻り値
+    /// 書き込んだ分割ファイルのパス
+    pub fn write_next_file(&mut self, data: &[u8]) -> Result<PathBuf> {
+        self.write_next_file_with(b"", data, b"")
+    }
+
+    /// ヘッダー・フッター付きで次の分割ファイルにデータを書き込む
+    ///
+    /// # 引数
+    /// * `header` - データの前に書き込むヘッダー
+    /// * `data` - 書き込むデータ
+    /// * `footer` - データの後に書き込むフッター
+    ///
+    /// # 動作
+    /// コンテナ形式の場合は、ヘッダー・データ・フッターの前に
+    /// 分割ファイルの番号や元ファイル内の位置を記録したヘッダーを書き込む
+    ///
+    /// # 戻り値
+    /// 書き込んだ分割ファイルのパス
+    pub fn write_next_file_with(
+        &mut self,
+        header: &[u8],
+        data: &[u8],
+        footer: &[u8],
+    ) -> Result<PathBuf> {
+        self.begin_part(header)?;
+        self.write_data(data)?;
+        Ok(self.finish_part(footer)?.path)
+    }
+
+    /// 次の分割ファイルの書き込みを始める
+    ///
+    /// # 引数
+    /// * `header` - データの前に書き込むヘッダー
+    ///
+    /// # 動作
+    /// 分割ファイルを作成（またはフィルターコマンドを起動）してヘッダーを書き込む。
+    /// コンテナ形式の場合は、ヘッダーの前にコンテナ形式のヘッダーの領域を確保しておき、
+    /// `finish_part` で内容が確定してから書き込む。
+    /// 書き込み中の分割ファイルがあれば、フッターなしで書き込みを終える
+    pub fn begin_part(&mut self, header: &[u8]) -> Result<()> {
+        if self.current.is_some() {
+            self.finish_part(b"")?;
+        }
+        self.current = Some(self.open_part(header)?);
         Ok(())
     }
 
+    /// 分割ファイルを作成してヘッダーを書き込む
+    fn open_part(&self, header: &[u8]) -> Result<OpenPart> {
+        let path = self.get_split_file_path(self.current_index)?;
+        let sink = match &self.filter {
+            Some(_) if self.container.is_some() => {
+                return Err(FileSplitError::UnsupportedInput(
+                    "フィルターコマンドにはコンテナ形式のヘッダーを書き込めません".to_string(),
+                ))
+            }
+            Some(command) => self.spawn_filter(command, &path)?,
+            None => {
+                let mut writer = BufWriter::new(File::create(&path)?);
+                if self.container.is_some() {
+                    writer.write_all(&[0u8; HEADER_LEN])?;
+                }
+                PartSink::File(writer)
+            }
+        };
+
+        let mut part = OpenPart {
+            path,
+            sink,
+            hasher: Sha256::new(),
+            checksums: self.checksums.then(MultiHasher::new),
+            payload_len: 0,
+            data_len: 0,
+        };
+        self.write_to(&mut part, header)?;
+        Ok(part)
+    }
+
+    /// 分割ファイルを渡すフィルターコマンドを起動する
+    fn spawn_filter(&self, command: &str, path: &Path) -> Result<PartSink> {
+        #[cfg(windows)]
+        let mut process = {
+            let mut process = Command::new("cmd");
+            process.arg("/C").arg(command);
+            process
+        };
+        #[cfg(not(windows))]
+        let mut process = {
+            let mut process = Command::new("sh");
+            process.arg("-c").arg(command);
+            process
+        };
+
+        process
+            .env("FILE", path)
+            .env("FILESPLIT_INDEX", self.current_index.to_string())
+            .stdin(Stdio::piped());
+        match self.part_sizes.get(self.current_index - 1) {
+            Some(size) => process.env("FILESPLIT_SIZE", size.to_string()),
+            None => process.env_remove("FILESPLIT_SIZE"),
+        };
+
+        let mut child = process.spawn().map_err(|e| {
+            FileSplitError::FilterFailed(format!("{command} を起動できません: {e}"))
+        })?;
+        let stdin = child.stdin.take().map(BufWriter::new).ok_or_else(|| {
+            FileSplitError::FilterFailed(format!("{command} の標準入力を開けません"))
+        })?;
+        Ok(PartSink::Command { child, stdin })
+    }
+
+    /// 書き込み中の分割ファイルに書き込む
+    ///
+    /// フィルターコマンドへの書き込みに失敗した場合は、コマンドの終了を待って
+    /// 終了状態をエラーに含める
+    fn write_to(&self, part: &mut OpenPart, bytes: &[u8]) -> Result<()> {
+        match part.write(bytes) {
+            Ok(()) => Ok(()),
+            Err(e) => match &mut part.sink {
+                PartSink::Command { child, .. } => {
+                    let status = child.wait()?;
+                    Err(FileSplitError::FilterFailed(format!(
+                        "{} ({}): 書き込めません: {e}（{status}）",
+                        self.filter.as_deref().unwrap_or_default(),
+                        part.path.display()
+                    )))
+                }
+                PartSink::File(_) => Err(e.into()),
+            },
+        }
+    }
+
+    /// 書き込み中の分割ファイルに元ファイルのデータを書き込む
+    ///
+    /// # 引数
+    /// * `data` - 書き込むデータ
+    ///
+    /// # 動作
+    /// 書き込み中の分割ファイルがなければ、ヘッダーなしで書き込みを始める
+    pub fn write_data(&mut self, data: &[u8]) -> Result<()> {
+        let mut part = match self.current.take() {
+            Some(part) => part,
+            None => self.open_part(b"")?,
+        };
+        self.write_to(&mut part, data)?;
+        part.data_len += data.len() as u64;
+        self.current = Some(part);
+        Ok(())
+    }
+
+    /// 書き込み中の分割ファイルにフッターを書き込んで閉じる
+    ///
+    /// # 引数
+    /// * `footer` - データの後に書き込むフッター
+    ///
+    /// # 動作
+    /// コンテナ形式の場合は、ファイルの先頭に戻ってヘッダーを書き込む。
+    /// その場合のハッシュはヘッダーを含めるため、書き込んだファイルを読み直して計算する。
+    /// フィルターコマンドの場合は、標準入力を閉じてコマンドの終了を待ち、
+    /// 失敗していればエラーにする。
+    /// 書き込み中の分割ファイルがなければ、空の分割ファイルを作成する
+    ///
+    /// # 戻り値
+    /// 書き込んだ分割ファイルの情報
+    pub fn finish_part(&mut self, footer: &[u8]) -> Result<WrittenPart> {
+        let mut part = match self.current.take() {
+            Some(part) => part,
+            None => self.open_part(b"")?,
+        };
+        self.write_to(&mut part, footer)?;
+
+        self.last_header = self.container.as_ref().map(|(set_id, total)| PartHeader {
+            set_id: set_id.clone(),
+            index: self.current_index as u64,
+            total: *total as u64,
+            offset: self.offset,
+            length: part.payload_len,
+            checksum: part.hasher.clone().finalize().into(),
+        });
+
+        let (size, sha256, checksums) = match (part.sink, &self.last_header) {
+            (PartSink::File(mut writer), Some(part_header)) => {
+                writer.seek(SeekFrom::Start(0))?;
+                writer.write_all(&part_header.to_bytes())?;
+                writer.flush()?;
+                let hasher = checksum::hash_file(&part.path)?;
+                (
+                    HEADER_LEN as u64 + part.payload_len,
+                    hasher.clone().finish(ChecksumFormat::Sha256),
+                    self.checksums.then_some(hasher),
+                )
+            }
+            (PartSink::File(mut writer), None) => {
+                writer.flush()?;
+                (
+                    part.payload_len,
+                    to_hex(&part.hasher.finalize()),
+                    part.checksums,
+                )
+            }
+            (PartSink::Command { mut child, stdin }, _) => {
+                let command = self.filter.as_deref().unwrap_or_default();
+                // 標準入力を閉じてコマンドに終わりを知らせる
+                let closed = stdin.into_inner().map(drop).map_err(|e| e.into_error());
+                let status = child.wait()?;
+                if !status.success() {
+                    return Err(FileSplitError::FilterFailed(format!(
+                        "{command} ({}): {status}",
+                        part.path.display()
+                    )));
+                }
+                if let Err(e) = closed {
+                    return Err(FileSplitError::FilterFailed(format!(
+                        "{command} ({}): 書き込めません: {e}",
+                        part.path.display()
+                    )));
+                }
+                (
+                    part.payload_len,
+                    to_hex(&part.hasher.finalize()),
+                    part.checksums,
+                )
+            }
+        };
+
+        self.current_index += 1;
+        self.offset += part.data_len;
+        Ok(WrittenPart {
+            path: part.path,
+            length: part.data_len,
+            size,
+            sha256,
+            checksums,
+        })
+    }
+
     /// 指定されたインデックスの分割ファイルパスを生成する
     ///
     /// # 引数
     /// * `index` - ファイルのインデックス（1から始まる）
     ///
     /// # 戻り値
-    /// 例: "file.txt" -> "file.txt.001"
-    pub fn get_split_file_path(&self, index: usize) -> PathBuf {
-        let mut path = self.base_path.clone();
-        let file_name = format!(
-            "{}.{:03}",
-            path.file_name().unwrap_or_default().to_string_lossy(),
-            index
-        );
-        path.set_file_name(file_name);
-        path
+    /// 例: "file.txt" -> "file.txt.001"（名前の付け方が標準の場合）
+    pub fn get_split_file_path(&self, index: usize) -> Result<PathBuf> {
+        self.naming.path(&self.base_path, index)
     }
 }
 
@@ -74,21 +453,40 @@ mod tests {
         assert_eq!(writer.base_path, PathBuf::from("/tmp/test.txt"));
     }
 
+    #[test]
+    fn test_part_base_path() {
+        // 分割ファイルの名前の基準の決定をテスト
+        let path = Path::new("data/file.txt");
+        let dir = Path::new("/mnt/parts");
+        let prefix = Path::new("backup");
+
+        assert_eq!(part_base_path(path, None, None), path);
+        assert_eq!(
+            part_base_path(path, Some(dir), None),
+            Path::new("/mnt/parts/file.txt")
+        );
+        assert_eq!(part_base_path(path, None, Some(prefix)), prefix);
+        assert_eq!(
+            part_base_path(path, Some(dir), Some(prefix)),
+            Path::new("/mnt/parts/backup")
+        );
+    }
+
     #[test]
     fn test_get_split_file_path() {
         // 分割ファイルパスの生成をテスト
         let writer = SplitFileWriter::new(Path::new("/tmp/test.txt"));
 
         assert_eq!(
-            writer.get_split_file_path(1),
+            writer.get_split_file_path(1).unwrap(),
             PathBuf::from("/tmp/test.txt.001")
         );
         assert_eq!(
-            writer.get_split_file_path(10),
+            writer.get_split_file_path(10).unwrap(),
             PathBuf::from("/tmp/test.txt.010")
         );
         assert_eq!(
-            writer.get_split_file_path(999),
+            writer.get_split_file_path(999).unwrap(),
             PathBuf::from("/tmp/test.txt.999")
         );
     }
@@ -102,8 +500,9 @@ mod tests {
         let mut writer = SplitFileWriter::new(&base_path);
 
         // 最初のファイルに書き込み
-        writer.write_next_file(b"First chunk").unwrap();
+        let written = writer.write_next_file(b"First chunk").unwrap();
         let first_file = temp_dir.path().join("test.txt.001");
+        assert_eq!(written, first_file);
         assert!(first_file.exists());
         assert_eq!(fs::read(&first_file).unwrap(), b"First chunk");
 
@@ -131,6 +530,43 @@ mod tests {
         assert_eq!(fs::read(&file).unwrap(), b"");
     }
 
+    #[test]
+    fn test_write_next_file_with_header_footer() {
+        // ヘッダー・フッター付きの書き込みをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("test.txt");
+
+        let mut writer = SplitFileWriter::new(&base_path);
+        writer
+            .write_next_file_with(b"<head>", b"body", b"<foot>")
+            .unwrap();
+
+        let file = temp_dir.path().join("test.txt.001");
+        assert_eq!(fs::read(&file).unwrap(), b"<head>body<foot>");
+        assert_eq!(writer.current_index, 2);
+    }
+
+    #[test]
+    fn test_write_with_container_header() {
+        // コンテナ形式のヘッダー付きの書き込みをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("test.txt");
+
+        let mut writer = SplitFileWriter::new(&base_path).with_container("set", 2);
+        writer.write_next_file(b"first").unwrap();
+        writer.write_next_file_with(b"<", b"second", b">").unwrap();
+
+        let content = fs::read(temp_dir.path().join("test.txt.002")).unwrap();
+        let header = PartHeader::parse(&content).unwrap().unwrap();
+        assert_eq!(header.set_id, "set");
+        assert_eq!(header.index, 2);
+        assert_eq!(header.total, 2);
+        assert_eq!(header.offset, 5);
+        assert_eq!(header.length, 8);
+        assert_eq!(&content[crate::container::HEADER_LEN..], b"<second>");
+        assert_eq!(writer.last_header(), Some(&header));
+    }
+
     #[test]
     fn test_write_large_data() {
         // 大きなデータの書き込みをテスト
@@ -146,4 +582,35 @@ mod tests {
         assert!(file.exists());
         assert_eq!(fs::read(&file).unwrap().len(), 10000);
     }
+
+    #[test]
+    fn test_write_part_in_pieces() {
+        // 分割ファイルを少しずつ書き込むテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("test.txt");
+
+        let mut writer = SplitFileWriter::new(&base_path).with_container("set", 2);
+        writer.begin_part(b"[").unwrap();
+        writer.write_data(b"abc").unwrap();
+        writer.write_data(b"def").unwrap();
+        let part = writer.finish_part(b"]").unwrap();
+
+        let content = fs::read(&part.path).unwrap();
+        assert_eq!(&content[HEADER_LEN..], b"[abcdef]");
+        assert_eq!(part.length, 6);
+        assert_eq!(part.size, content.len() as u64);
+        assert_eq!(part.sha256, crate::manifest::sha256_hex(&content));
+
+        // ヘッダーは書き込み終了時の内容で書き込まれる
+        let header = PartHeader::parse(&content).unwrap().unwrap();
+        assert_eq!(header.index, 1);
+        assert_eq!(header.length, 8);
+        assert_eq!(header, *writer.last_header().unwrap());
+
+        // 次の分割ファイルの開始位置はデータのバイト数だけ進む
+        writer.write_data(b"gh").unwrap();
+        let part = writer.finish_part(b"").unwrap();
+        assert_eq!(part.path, temp_dir.path().join("test.txt.002"));
+        assert_eq!(writer.last_header().unwrap().offset, 6);
+    }
 }
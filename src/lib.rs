@@ -0,0 +1,54 @@
+//! ファイル分割・結合ライブラリ
+//!
+//! ファイルを固定サイズや独自の境界で分割し、分割したファイルを結合して
+//! 元のファイルを復元する。コマンドラインツール `filesplit-rs` はこのライブラリを
+//! 使用して実装されている。
+//!
+//! # 例
+//! ```no_run
+//! use filesplit_rs::{Merger, Splitter};
+//!
+//! // data.bin を 1MiB ごとに data.bin.001, data.bin.002, ... に分割する
+//! let mut splitter = Splitter::builder().chunk_size(1024 * 1024).build()?;
+//! splitter.split_file("data.bin".as_ref())?;
+//!
+//! // data.bin.001, data.bin.002, ... を data.bin に結合する
+//! let merger = Merger::builder().build()?;
+//! merger.merge_files("data.bin".as_ref())?;
+//! # Ok::<(), filesplit_rs::FileSplitError>(())
+//! ```
+
+// モジュールの宣言
+pub mod checksum;
+pub mod container;
+pub mod detect;
+pub mod encoding;
+pub mod error;
+pub mod io;
+pub mod manifest;
+pub mod merger;
+pub mod metadata;
+pub mod naming;
+pub mod options;
+pub mod splitter;
+pub mod strategy;
+pub mod template;
+pub mod time_window;
+pub mod verifier;
+
+// 公開APIとして再エクスポート
+pub use checksum::ChecksumFormat;
+pub use container::PartHeader;
+pub use detect::{detect_parts, DetectedParts, SplitConvention};
+pub use encoding::Encoding;
+pub use error::{FileSplitError, Result};
+pub use manifest::{Manifest, ManifestPart};
+pub use merger::{Merger, MergerBuilder, PartEntry};
+pub use metadata::FileMetadata;
+pub use naming::NamingScheme;
+pub use options::{MergeOptions, SplitOptions, TimeWindowOptions, VerifyOptions};
+pub use splitter::{Splitter, SplitterBuilder};
+pub use strategy::{Boundary, FixedSizeStrategy, SplitStrategy};
+pub use template::{PartInfo, PartTemplate};
+pub use time_window::{TimeWindow, TimeWindowSplitter};
+pub use verifier::{Verifier, VerifierBuilder, VerifyReport};
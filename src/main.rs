@@ -1,13 +1,12 @@
 // モジュールの宣言
 mod cli;
-mod config;
-mod error;
-mod io;
-mod merger;
-mod splitter;
-
-use config::Mode;
-use error::Result;
+
+use cli::{Command, MergeOutput};
+use filesplit_rs::{
+    detect_parts, FileSplitError, Manifest, Merger, Result, Splitter, TimeWindowSplitter, Verifier,
+};
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
 use std::process;
 
 /// メイン関数
@@ -22,43 +21,268 @@ fn main() {
 
 fn run() -> Result<()> {
     // コマンドライン引数を解析
-    let config = cli::Cli::parse_args()?;
+    let command = cli::Cli::parse_args()?;
 
     // 動作モードに応じて処理を分岐
-    match config.mode {
-        Mode::Split { size } => {
+    match command {
+        Command::Split { file_path, options } => {
             // 分割モードの処理
+            let chunk_size = options.chunk_size;
+            let has_prefix = options.prefix.is_some();
+            let mut splitter = Splitter::builder().options(options).build()?;
+
+            if file_path.as_os_str() == "-" {
+                // 標準入力は名前がないため分割ファイルの名前の指定が必要
+                require_prefix(has_prefix, "標準入力")?;
+                let base_path = splitter.part_base_path(&file_path);
+                println!("標準入力を分割しています: {}", base_path.display());
+                println!("分割サイズ: {chunk_size} バイト");
+                splitter.split_reader(io::stdin().lock(), &base_path)?;
+            } else {
+                // ファイルの存在確認
+                let metadata = fs::metadata(&file_path).map_err(|_| {
+                    FileSplitError::FileNotFound(file_path.to_string_lossy().to_string())
+                })?;
+
+                // 分割処理の開始を通知
+                println!("ファイルを分割しています: {}", file_path.display());
+                println!("分割サイズ: {chunk_size} バイト");
+
+                if metadata.is_file() {
+                    // ファイル分割を実行
+                    splitter.split_file(&file_path)?;
+                } else {
+                    // パイプやデバイスは1回だけ先頭から読み込む
+                    require_prefix(has_prefix, &file_path.to_string_lossy())?;
+                    let base_path = splitter.part_base_path(&file_path);
+                    splitter.split_reader(File::open(&file_path)?, &base_path)?;
+                }
+            }
+
+            // 以前の分割で作成された古い分割ファイルを通知
+            for path in splitter.removed_stale_parts() {
+                println!("古い分割ファイルを削除しました: {}", path.display());
+            }
+            for path in splitter.stale_parts() {
+                eprintln!(
+                    "警告: 以前の分割で作成された古い分割ファイルが残っています: {}",
+                    path.display()
+                );
+            }
+
+            println!("分割が完了しました。");
+        }
+        Command::SplitByTime { file_path, options } => {
+            // 時間枠分割モードの処理
 
             // ファイルの存在確認
-            if !config.file_path.exists() {
-                return Err(error::FileSplitError::FileNotFound(
-                    config.file_path.to_string_lossy().to_string(),
+            if !file_path.exists() {
+                return Err(FileSplitError::FileNotFound(
+                    file_path.to_string_lossy().to_string(),
                 ));
             }
 
-            // 分割処理の開始を通知
-            println!("ファイルを分割しています: {}", config.file_path.display());
-            println!("分割サイズ: {size} バイト");
+            println!(
+                "ファイルを時間枠ごとに分割しています: {}",
+                file_path.display()
+            );
 
-            // ファイル分割を実行
-            let splitter = splitter::Splitter::new(size);
-            splitter.split_file(&config.file_path)?;
+            // 時間枠分割を実行
+            let splitter = TimeWindowSplitter::from_options(&options)?;
+            let created = splitter.split_file(&file_path)?;
 
-            println!("分割が完了しました。");
+            println!("分割が完了しました。（{} ファイル）", created.len());
         }
-        Mode::Merge => {
+        Command::Merge {
+            file_path,
+            output,
+            options,
+        } => {
             // 結合モードの処理
+            let merger = Merger::builder().options(options).build()?;
 
-            // 結合処理の開始を通知
-            println!("ファイルを結合しています: {}", config.file_path.display());
+            match output {
+                None => {
+                    // 結合処理の開始を通知
+                    println!("ファイルを結合しています: {}", file_path.display());
 
-            // ファイル結合を実行
-            let merger = merger::Merger::new();
-            merger.merge_files(&config.file_path)?;
+                    // ファイル結合を実行（元のファイル名が記録されていればその名前で作成される）
+                    let output = merger.merge_files(&file_path)?;
+                    println!("結合が完了しました。（{}）", output.display());
+                }
+                Some(MergeOutput::Path(output)) => {
+                    println!(
+                        "ファイルを結合しています: {} -> {}",
+                        file_path.display(),
+                        output.display()
+                    );
+                    let output = merger.merge_files_to(&file_path, &output)?;
+                    println!("結合が完了しました。（{}）", output.display());
+                }
+                Some(MergeOutput::Stdout) => {
+                    // 標準出力には結合結果だけを書き込み、進捗は標準エラー出力に表示する
+                    eprintln!("ファイルを結合しています: {}", file_path.display());
+                    let mut writer = BufWriter::new(io::stdout().lock());
+                    merger.merge_to_writer(&file_path, &mut writer)?;
+                    eprintln!("結合が完了しました。");
+                }
+            }
+        }
+        Command::MergeDetected {
+            path,
+            output,
+            options,
+        } => {
+            // 他のツールの命名規則を判定して結合する
+            let merger = Merger::builder().options(options).build()?;
+            let detected = detect_parts(&path)?;
+
+            match output {
+                Some(MergeOutput::Stdout) => {
+                    // 標準出力には結合結果だけを書き込み、進捗は標準エラー出力に表示する
+                    eprintln!(
+                        "分割ファイルを検出しました: {}（{}、{} ファイル）",
+                        detected.base_path.display(),
+                        detected.convention,
+                        detected.parts.len()
+                    );
+                    let mut writer = BufWriter::new(io::stdout().lock());
+                    merger.merge_detected_to_writer(&detected, &mut writer)?;
+                    eprintln!("結合が完了しました。");
+                }
+                output => {
+                    let output = match output {
+                        Some(MergeOutput::Path(output)) => output,
+                        _ => detected.base_path.clone(),
+                    };
+                    println!(
+                        "分割ファイルを検出しました: {}（{}、{} ファイル）",
+                        detected.base_path.display(),
+                        detected.convention,
+                        detected.parts.len()
+                    );
+                    println!("ファイルを結合しています: {}", output.display());
+                    merger.merge_detected(&detected, &output)?;
+                    println!("結合が完了しました。");
+                }
+            }
+        }
+        Command::MergeParts {
+            inputs,
+            file_path,
+            options,
+        } => {
+            // コンテナ形式の分割ファイルをヘッダーの情報で結合する
+            println!(
+                "分割ファイルをヘッダーの情報で結合しています: {}",
+                file_path.display()
+            );
+
+            let merger = Merger::builder().options(options).build()?;
+            merger.merge_parts(&inputs, &file_path)?;
 
             println!("結合が完了しました。");
         }
+        Command::Verify { file_path, options } => {
+            // 検証モードの処理
+            println!("分割ファイルを検証しています: {}", file_path.display());
+
+            // 検証を実行して問題のあった分割ファイルを表示
+            let verifier = Verifier::builder().options(options).build()?;
+            let report = verifier.verify(&file_path)?;
+            for (label, paths) in [
+                ("欠落", &report.missing),
+                ("切り詰め", &report.truncated),
+                ("破損", &report.corrupted),
+                ("余分", &report.extra),
+            ] {
+                for path in paths {
+                    println!("{label}: {}", path.display());
+                }
+            }
+
+            if !report.is_ok() {
+                return Err(FileSplitError::VerificationFailed(report.summary()));
+            }
+            println!("検証が完了しました。（{}）", report.summary());
+        }
+        Command::Info { file_path, options } => {
+            // 分割ファイル一式の情報を表示する
+            let merger = Merger::builder().options(options).build()?;
+            match merger.load_manifest(&file_path)? {
+                Some(manifest) => print_manifest(&manifest),
+                None => {
+                    // マニフェストがなければ他のツールの命名規則として判定する
+                    let detected = detect_parts(&merger.part_base_path(&file_path))?;
+                    println!("元ファイル: {}", detected.base_path.display());
+                    println!("命名規則: {}", detected.convention);
+                    println!(
+                        "分割ファイル数: {}（欠落 {}）",
+                        detected.total,
+                        detected.missing().len()
+                    );
+                }
+            }
+        }
+        Command::List { file_path, options } => {
+            // 結合する分割ファイルを番号順に表示する
+            let merger = Merger::builder().options(options).build()?;
+            let entries = merger.list_parts(&file_path)?;
+            let mut missing = 0;
+            for entry in &entries {
+                match entry.size {
+                    Some(size) => {
+                        println!("{:>5} {size:>14} {}", entry.index, entry.path.display())
+                    }
+                    None => {
+                        missing += 1;
+                        println!("{:>5} {:>14} {}", entry.index, "欠落", entry.path.display());
+                    }
+                }
+            }
+            println!("{} ファイル（欠落 {missing}）", entries.len());
+        }
     }
 
     Ok(())
 }
+
+/// マニフェストに記録された分割ファイル一式の情報を表示する
+fn print_manifest(manifest: &Manifest) {
+    let original = manifest.original.as_ref();
+    let name = original.map_or(manifest.original_name.as_str(), |original| &original.name);
+    println!("元ファイル: {name}");
+    println!("サイズ: {} バイト", manifest.total_size);
+    println!("分割ファイル数: {}", manifest.part_count);
+    if manifest.container {
+        println!("形式: コンテナ形式");
+    }
+    if !manifest.set_id.is_empty() {
+        println!("識別子: {}", manifest.set_id);
+    }
+    if let Some(mode) = original.and_then(|original| original.mode) {
+        println!("パーミッション: {mode:04o}");
+    }
+    let modified = original
+        .and_then(|original| original.modified)
+        .and_then(|time| {
+            chrono::DateTime::from_timestamp(i64::try_from(time.secs).ok()?, time.nanos)
+        });
+    if let Some(modified) = modified {
+        println!("最終更新日時: {}", modified.format("%Y-%m-%d %H:%M:%S UTC"));
+    }
+    if let Some(original) = original.filter(|original| !original.xattrs.is_empty()) {
+        println!("拡張属性: {} 個", original.xattrs.len());
+    }
+}
+
+/// 名前から分割ファイルの名前を決められない入力で、--prefix の指定を確認する
+fn require_prefix(has_prefix: bool, input: &str) -> Result<()> {
+    if has_prefix {
+        Ok(())
+    } else {
+        Err(FileSplitError::UnsupportedInput(format!(
+            "{input} を分割するには --prefix で分割ファイルの名前を指定してください"
+        )))
+    }
+}
@@ -0,0 +1,302 @@
+use crate::error::{FileSplitError, Result};
+use crate::metadata::FileMetadata;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// マニフェストの形式バージョン
+pub const FORMAT_VERSION: u32 = 1;
+
+/// 分割ファイル一式の情報を記録するマニフェスト
+///
+/// 分割時に `元のファイル名.manifest.json` として保存され、
+/// 結合時に分割ファイルの数や内容の確認に使用される
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// マニフェストの形式バージョン
+    pub format_version: u32,
+    /// 分割ごとに生成する分割ファイル一式の識別子
+    ///
+    /// 以前の分割で作成された分割ファイルと区別するために使用する
+    #[serde(default)]
+    pub set_id: String,
+    /// 元ファイルの名前
+    pub original_name: String,
+    /// 元ファイルのサイズ（バイト）
+    pub total_size: u64,
+    /// 分割ファイルがコンテナ形式のヘッダー付きかどうか
+    #[serde(default)]
+    pub container: bool,
+    /// 分割ファイルの数
+    pub part_count: usize,
+    /// 各分割ファイルの情報（番号順）
+    pub parts: Vec<ManifestPart>,
+    /// 分割前のファイルの名前と属性（ファイル以外の入力を分割した場合はNone）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original: Option<FileMetadata>,
+    /// 分割時に作成したチェックサムファイルの名前
+    ///
+    /// 再分割時に、このツールが作成したチェックサムファイルだけを古いものとして扱うために使用する
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sidecars: Vec<String>,
+}
+
+/// マニフェストに記録する分割ファイルの情報
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestPart {
+    /// 分割ファイルの番号（1から始まる）
+    pub index: usize,
+    /// 分割ファイルの名前
+    pub name: String,
+    /// 元ファイル内での開始位置（バイト）
+    pub offset: u64,
+    /// 元ファイルから切り出したデータのバイト数
+    pub length: u64,
+    /// 分割ファイルのサイズ（ヘッダー・フッターを含む）
+    pub size: u64,
+    /// 分割ファイルのSHA-256ハッシュ（16進数）
+    pub sha256: String,
+}
+
+impl Manifest {
+    /// 新しい識別子を持つ空のマニフェストを作成する
+    ///
+    /// # 引数
+    /// * `original_name` - 元ファイルの名前
+    pub fn new(original_name: &str) -> Self {
+        Self {
+            format_version: FORMAT_VERSION,
+            set_id: generate_set_id(original_name),
+            original_name: original_name.to_string(),
+            total_size: 0,
+            container: false,
+            part_count: 0,
+            parts: Vec::new(),
+            original: None,
+            sidecars: Vec::new(),
+        }
+    }
+
+    /// 分割ファイルの情報を追加する
+    ///
+    /// # 引数
+    /// * `name` - 分割ファイルの名前
+    /// * `length` - 元ファイルから切り出したデータのバイト数
+    /// * `size` - 分割ファイルのサイズ
+    /// * `sha256` - 分割ファイルのSHA-256ハッシュ
+    pub fn push_part(&mut self, name: String, length: u64, size: u64, sha256: String) {
+        self.parts.push(ManifestPart {
+            index: self.parts.len() + 1,
+            name,
+            offset: self.total_size,
+            length,
+            size,
+            sha256,
+        });
+        self.total_size += length;
+        self.part_count = self.parts.len();
+    }
+
+    /// 元ファイルのパスに対応するマニフェストのパスを生成する
+    ///
+    /// # 戻り値
+    /// 例: "file.txt" -> "file.txt.manifest.json"
+    pub fn path_for(base_path: &Path) -> PathBuf {
+        let mut path = base_path.to_path_buf();
+        let file_name = format!(
+            "{}.manifest.json",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        );
+        path.set_file_name(file_name);
+        path
+    }
+
+    /// マニフェストをファイルに保存する
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| FileSplitError::InvalidManifest(e.to_string()))?;
+        fs::write(path, json + "\n")?;
+        Ok(())
+    }
+
+    /// ファイルからマニフェストを読み込む
+    ///
+    /// # 戻り値
+    /// 読み込んだマニフェスト、または形式が不正・未対応のバージョンの場合はエラー
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path)?;
+        let manifest: Manifest = serde_json::from_str(&json)
+            .map_err(|e| FileSplitError::InvalidManifest(format!("{}: {e}", path.display())))?;
+
+        if manifest.format_version > FORMAT_VERSION {
+            return Err(FileSplitError::InvalidManifest(format!(
+                "{}: 未対応の形式バージョンです: {}",
+                path.display(),
+                manifest.format_version
+            )));
+        }
+        if manifest.part_count != manifest.parts.len() {
+            return Err(FileSplitError::InvalidManifest(format!(
+                "{}: 分割ファイル数が一致しません",
+                path.display()
+            )));
+        }
+        Ok(manifest)
+    }
+}
+
+/// 分割ファイル一式の識別子を生成する
+///
+/// 現在時刻・プロセスID・元ファイルの名前から16桁の16進数文字列を作成する
+pub fn generate_set_id(original_name: &str) -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let mut hasher = Sha256::new();
+    hasher.update(nanos.to_le_bytes());
+    hasher.update(process::id().to_le_bytes());
+    hasher.update(original_name.as_bytes());
+    to_hex(&hasher.finalize()[..8])
+}
+
+/// データのSHA-256ハッシュを16進数文字列で返す
+pub fn sha256_hex(data: &[u8]) -> String {
+    to_hex(&Sha256::digest(data))
+}
+
+/// ファイルのSHA-256ハッシュを16進数文字列で返す
+///
+/// ファイル全体をメモリに読み込まずに計算する
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut hasher = Sha256::new();
+    io::copy(&mut reader, &mut hasher)?;
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// バイト列を16進数文字列に変換する
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_path_for() {
+        // マニフェストのパス生成をテスト
+        assert_eq!(
+            Manifest::path_for(Path::new("/tmp/test.txt")),
+            PathBuf::from("/tmp/test.txt.manifest.json")
+        );
+    }
+
+    #[test]
+    fn test_set_id() {
+        // 分割ファイル一式の識別子の生成をテスト
+        let first = Manifest::new("test.txt");
+        let second = Manifest::new("test.txt");
+        assert_eq!(first.set_id.len(), 16);
+        assert_ne!(first.set_id, second.set_id);
+    }
+
+    #[test]
+    fn test_load_without_set_id() {
+        // 識別子が記録されていないマニフェストも読み込めることをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("test.txt.manifest.json");
+        fs::write(
+            &path,
+            r#"{"format_version":1,"original_name":"test.txt","total_size":0,"part_count":0,"parts":[]}"#,
+        )
+        .unwrap();
+
+        let manifest = Manifest::load(&path).unwrap();
+        assert_eq!(manifest.set_id, "");
+        assert_eq!(manifest.original, None);
+        assert!(manifest.sidecars.is_empty());
+    }
+
+    #[test]
+    fn test_push_part() {
+        // 分割ファイル情報の追加とオフセット計算をテスト
+        let mut manifest = Manifest::new("test.txt");
+        manifest.push_part("test.txt.001".to_string(), 10, 10, sha256_hex(b"a"));
+        manifest.push_part("test.txt.002".to_string(), 5, 8, sha256_hex(b"b"));
+
+        assert_eq!(manifest.part_count, 2);
+        assert_eq!(manifest.total_size, 15);
+        assert_eq!(manifest.parts[1].index, 2);
+        assert_eq!(manifest.parts[1].offset, 10);
+        assert_eq!(manifest.parts[1].size, 8);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        // マニフェストの保存と読み込みをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("test.txt.manifest.json");
+
+        let mut manifest = Manifest::new("test.txt");
+        manifest.push_part("test.txt.001".to_string(), 3, 3, sha256_hex(b"abc"));
+        manifest.save(&path).unwrap();
+
+        let loaded = Manifest::load(&path).unwrap();
+        assert_eq!(loaded, manifest);
+    }
+
+    #[test]
+    fn test_load_unsupported_version() {
+        // 未対応のバージョンのエラーテスト
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("test.txt.manifest.json");
+
+        let mut manifest = Manifest::new("test.txt");
+        manifest.format_version = FORMAT_VERSION + 1;
+        manifest.save(&path).unwrap();
+
+        match Manifest::load(&path).unwrap_err() {
+            FileSplitError::InvalidManifest(msg) => assert!(msg.contains("未対応")),
+            _ => panic!("予期しないエラー型"),
+        }
+    }
+
+    #[test]
+    fn test_load_invalid_json() {
+        // 不正なJSONのエラーテスト
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("test.txt.manifest.json");
+        fs::write(&path, "not json").unwrap();
+
+        assert!(matches!(
+            Manifest::load(&path),
+            Err(FileSplitError::InvalidManifest(_))
+        ));
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        // SHA-256ハッシュの16進数表現をテスト
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_sha256_file() {
+        // ファイルのハッシュがデータのハッシュと一致することをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("test.txt");
+        fs::write(&path, "abc").unwrap();
+
+        assert_eq!(sha256_file(&path).unwrap(), sha256_hex(b"abc"));
+    }
+}
@@ -1,15 +1,184 @@
+use crate::checksum::{MultiHasher, Sidecars};
+use crate::container::{self, PartHeader, HEADER_LEN};
+use crate::detect::DetectedParts;
 use crate::error::{FileSplitError, Result};
-use std::fs::File;
-use std::io::{BufReader, BufWriter, Read, Write};
+use crate::io::{writer, OutputFile};
+use crate::manifest::{to_hex, Manifest, ManifestPart};
+use crate::metadata::FileMetadata;
+use crate::naming::NamingScheme;
+use crate::options::MergeOptions;
+use crate::template::{PartInfo, PartTemplate};
+use sha2::{Digest, Sha256};
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{ErrorKind, Read, Write};
 use std::path::{Path, PathBuf};
 
+/// 分割ファイルを読み込むバッファのサイズ（分割ファイルの大きさに関係なく一定）
+const BUFFER_SIZE: usize = 64 * 1024;
+
 /// 分割されたファイルを結合する構造体
-pub struct Merger;
+pub struct Merger {
+    /// 分割時に挿入されたヘッダー・フッター（結合時に取り除く）
+    template: PartTemplate,
+    /// 欠けている分割ファイルを飛ばして結合するかどうか
+    allow_partial: bool,
+    /// 分割ファイルにコンテナ形式のヘッダーが付いているかどうか
+    container: bool,
+    /// 既存の出力ファイルを上書きするかどうか
+    force: bool,
+    /// 分割ファイルを探すディレクトリ
+    output_dir: Option<PathBuf>,
+    /// 分割時に元ファイル名の代わりに使用した名前
+    prefix: Option<PathBuf>,
+    /// 分割ファイルの名前の付け方
+    naming: NamingScheme,
+    /// 記録された元ファイルの名前と属性を復元するかどうか
+    restore_metadata: bool,
+    /// 記録された拡張属性も復元するかどうか
+    xattrs: bool,
+    /// 記録された setuid・setgid・スティッキービットも復元するかどうか
+    special_bits: bool,
+}
+
+impl Default for Merger {
+    fn default() -> Self {
+        Self::from_options(MergeOptions::default())
+    }
+}
+
+/// 結合する分割ファイル一式
+struct PartSet {
+    /// 存在する分割ファイル（番号順）
+    parts: Vec<PartSource>,
+    /// 分割ファイルの総数
+    total: usize,
+    /// 分割ファイルにコンテナ形式のヘッダーが付いているかどうか
+    container: bool,
+    /// マニフェストに記録された分割ファイル一式の識別子
+    set_id: Option<String>,
+    /// マニフェストに記録された元ファイルの名前と属性
+    original: Option<FileMetadata>,
+    /// マニフェストに記録されたテンプレートの展開に使用する元ファイル名
+    original_name: Option<String>,
+}
+
+/// 分割ファイル一式の一覧の項目
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartEntry {
+    /// 分割ファイルの番号
+    pub index: usize,
+    /// 分割ファイルのパス
+    pub path: PathBuf,
+    /// 分割ファイルのサイズ（存在しない場合はNone）
+    pub size: Option<u64>,
+}
+
+/// 結合する分割ファイル
+struct PartSource {
+    /// 分割ファイルの番号（1から始まる）
+    index: usize,
+    /// 分割ファイルのパス
+    path: PathBuf,
+    /// マニフェストの記録（マニフェストがない場合はNone）
+    expected: Option<ManifestPart>,
+}
+
+/// 結合中の状態
+struct MergeState {
+    /// 分割ファイルの総数
+    total: usize,
+    /// 分割ファイルにコンテナ形式のヘッダーが付いているかどうか
+    container: bool,
+    /// 分割ファイル一式の識別子
+    set_id: Option<String>,
+    /// 次の分割ファイルの元ファイル内での開始位置
+    offset: u64,
+    /// テンプレートの展開に使用する元ファイル名
+    original_name: String,
+    /// 結合結果のチェックサム（チェックサムファイルに記録がある場合だけ計算する）
+    output_hasher: Option<MultiHasher>,
+}
+
+/// 読み込みながらハッシュを計算する分割ファイルのリーダー
+struct PartReader {
+    file: File,
+    /// 分割ファイル全体のSHA-256
+    sha256: Sha256,
+    /// コンテナ形式のヘッダーに続くデータのSHA-256
+    payload: Sha256,
+    /// チェックサムファイルとの照合用のチェックサム
+    checksums: Option<MultiHasher>,
+}
+
+impl PartReader {
+    fn new(file: File, checksums: bool) -> Self {
+        Self {
+            file,
+            sha256: Sha256::new(),
+            payload: Sha256::new(),
+            checksums: checksums.then(MultiHasher::new),
+        }
+    }
+
+    /// バッファがいっぱいになるかファイルの終わりまで読み込む
+    ///
+    /// # 戻り値
+    /// 読み込んだバイト数（ファイルの終わりに達した場合はバッファより少ない）
+    fn read_full(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        let mut filled = 0;
+        while filled < buffer.len() {
+            match self.file.read(&mut buffer[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let data = &buffer[..filled];
+        self.sha256.update(data);
+        self.payload.update(data);
+        if let Some(checksums) = &mut self.checksums {
+            checksums.update(data);
+        }
+        Ok(filled)
+    }
+
+    /// 続くデータが指定されたバイト列と一致するか確認する
+    fn starts_with(&mut self, expected: &[u8]) -> Result<bool> {
+        let mut buffer = vec![0u8; expected.len()];
+        let read = self.read_full(&mut buffer)?;
+        Ok(buffer[..read] == *expected)
+    }
+}
 
 impl Merger {
     /// 新しいMergerを作成する
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Mergerを組み立てるビルダーを作成する
+    pub fn builder() -> MergerBuilder {
+        MergerBuilder::default()
+    }
+
+    /// オプションからMergerを作成する
+    fn from_options(options: MergeOptions) -> Self {
+        Self {
+            template: options.template,
+            allow_partial: options.allow_partial,
+            container: options.container,
+            force: options.force,
+            output_dir: options.output_dir,
+            prefix: options.prefix,
+            naming: options.naming,
+            restore_metadata: options.metadata,
+            xattrs: options.xattrs,
+            special_bits: options.special_bits,
+        }
     }
 
     /// 分割されたファイルを結合する
@@ -19,67 +188,842 @@ impl Merger {
     ///
     /// # 動作
     /// base_path.001, base_path.002, ...の形式のファイルを
-    /// 順番に読み込んで、base_pathに結合する
-    pub fn merge_files(&self, base_path: &Path) -> Result<()> {
-        // 出力ファイルを作成
-        let output_file = File::create(base_path)?;
-        let mut writer = BufWriter::new(output_file);
+    /// 順番に読み込んで、base_pathに結合する。
+    /// base_path.manifest.json が存在する場合は、記録された分割ファイルを
+    /// 記録された順に結合し、各分割ファイルのサイズとハッシュを確認する。
+    /// テンプレートが指定されている場合は、各分割ファイルの前後から
+    /// 展開したヘッダー・フッターを取り除く。
+    /// 連番に欠けがある場合は、部分的な結合が許可されていなければエラーにする。
+    /// コンテナ形式の場合は、各分割ファイルのヘッダーの番号・識別子・ハッシュを確認する。
+    /// base_path.sfv, base_path.md5, base_path.SHA256SUMS が存在する場合は、
+    /// 記録された分割ファイルと結合結果のチェックサムを確認する。
+    /// 結合結果は同じディレクトリの一時ファイルに書き込み、成功した場合だけ
+    /// base_path に置き換える。base_path が既に存在する場合は、
+    /// 上書きが許可されていなければエラーにする。
+    /// 分割ファイルを探すディレクトリや名前が指定されている場合は、
+    /// base_path の代わりにそれらを反映したパスを基準に分割ファイルを探す。
+    /// マニフェストに元ファイルの名前が記録されている場合は、分割ファイルの名前を
+    /// 指定していなければ base_path と同じディレクトリに記録された名前で作成し、
+    /// 記録されたパーミッションと最終更新日時を復元する
+    ///
+    /// # 戻り値
+    /// 結合結果のパス
+    pub fn merge_files(&self, base_path: &Path) -> Result<PathBuf> {
+        self.merge_into(base_path, None)
+    }
+
+    /// 分割されたファイルを別のパスに結合する
+    ///
+    /// # 引数
+    /// * `base_path` - 元ファイルのパス（分割ファイルは base_path.001, ... ）
+    /// * `output` - 結合後のファイルパス。ディレクトリの場合はその中に元ファイルと同じ名前
+    ///   （マニフェストに記録されていればその名前）で作成する
+    ///
+    /// # 動作
+    /// 分割ファイルの探し方と確認の内容、属性の復元は `merge_files` と同じ
+    ///
+    /// # 戻り値
+    /// 結合結果のパス
+    pub fn merge_files_to(&self, base_path: &Path, output: &Path) -> Result<PathBuf> {
+        self.merge_into(base_path, Some(output))
+    }
+
+    /// 分割されたファイルを結合してファイルに書き込む
+    ///
+    /// # 引数
+    /// * `base_path` - 元ファイルのパス
+    /// * `output` - 結合後のファイルパス（Noneの場合は base_path または記録された名前）
+    fn merge_into(&self, base_path: &Path, output: Option<&Path>) -> Result<PathBuf> {
+        let part_base = self.part_base_path(base_path);
+        let set = self.collect_parts(&part_base)?;
+        let original = set.original.clone().filter(|_| self.restore_metadata);
+        // 記録された名前は結合結果が別のディレクトリに作成されないことを確認してから使う
+        let name = match &original {
+            Some(original) => Some(original.checked_name()?),
+            None => None,
+        };
 
-        // 分割ファイルのインデックスとフラグを初期化
-        let mut index = 1;
-        let mut found_any = false;
+        let output = match output {
+            Some(output) if output.is_dir() => output.join(
+                name.map_or(base_path.file_name().unwrap_or_default(), |name| {
+                    name.as_ref()
+                }),
+            ),
+            Some(output) => output.to_path_buf(),
+            // 分割ファイルの名前を指定した場合は base_path を結合後の名前として扱う
+            None => match name {
+                Some(name) if self.prefix.is_none() => base_path.with_file_name(name),
+                _ => base_path.to_path_buf(),
+            },
+        };
+
+        // 既存のファイルを上書きしないか先に確認する
+        if !self.force && output.exists() {
+            return Err(FileSplitError::OutputExists(
+                output.to_string_lossy().to_string(),
+            ));
+        }
+
+        // 一時ファイルに書き込み、すべて成功した場合だけ出力先に置き換える
+        let mut writer = OutputFile::create(&output, self.force)?;
+        self.write_parts(base_path, &part_base, set, &mut writer)?;
+        writer.commit()?;
+
+        if let Some(original) = original {
+            original.apply(&output, self.xattrs, self.special_bits)?;
+        }
+        Ok(output)
+    }
+
+    /// 分割されたファイルを結合して任意の書き込み先に書き込む
+    ///
+    /// # 引数
+    /// * `base_path` - 元ファイルのパス（分割ファイルは base_path.001, ... ）
+    /// * `writer` - 結合結果の書き込み先（標準出力など）
+    ///
+    /// # 動作
+    /// 分割ファイルの探し方と確認の内容は `merge_files` と同じ。
+    /// 途中でエラーになった場合、それまでのデータは書き込み先に書き込まれている
+    pub fn merge_to_writer<W: Write>(&self, base_path: &Path, writer: &mut W) -> Result<()> {
+        // 結合する分割ファイルを決定する
+        let part_base = self.part_base_path(base_path);
+        let set = self.collect_parts(&part_base)?;
+        self.write_parts(base_path, &part_base, set, writer)
+    }
+
+    /// 分割ファイル一式を順番に書き込み先に書き込む
+    ///
+    /// # 引数
+    /// * `base_path` - 元ファイルのパス
+    /// * `part_base` - 分割ファイルを探した基準のパス
+    /// * `set` - 結合する分割ファイル一式
+    /// * `writer` - 結合結果の書き込み先
+    ///
+    /// # 動作
+    /// テンプレートの展開にはマニフェストに記録された元ファイル名を使用し、
+    /// マニフェストがなければ base_path のファイル名を使用する
+    fn write_parts<W: Write>(
+        &self,
+        base_path: &Path,
+        part_base: &Path,
+        set: PartSet,
+        writer: &mut W,
+    ) -> Result<()> {
+        let PartSet {
+            parts,
+            total,
+            container,
+            set_id,
+            original_name,
+            ..
+        } = set;
+        let sidecars = Sidecars::load(part_base)?;
+
+        let original_name = original_name.unwrap_or_else(|| {
+            base_path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string()
+        });
+        let mut state = MergeState {
+            total,
+            container,
+            set_id,
+            offset: 0,
+            original_name,
+            output_hasher: None,
+        };
+        // チェックサムファイルに元ファイルが記録されていれば結合結果も確認する
+        if sidecars.contains(&state.original_name) {
+            state.output_hasher = Some(MultiHasher::new());
+        }
 
         // すべての分割ファイルを順番に処理
-        loop {
-            // 現在のインデックスの分割ファイルパスを生成
-            let split_path = Self::get_split_file_path(base_path, index);
-
-            // ファイルが存在しない場合の処理
-            if !split_path.exists() {
-                if !found_any {
-                    // 1つも分割ファイルが見つからなかった場合はエラー
-                    return Err(FileSplitError::NoSplitFiles(
-                        base_path.to_string_lossy().to_string(),
-                    ));
+        for part in &parts {
+            self.copy_part(
+                part.index,
+                &part.path,
+                part.expected.as_ref(),
+                &sidecars,
+                &mut state,
+                writer,
+            )?;
+        }
+
+        if let Some(hasher) = &state.output_hasher {
+            sidecars.check_hasher(base_path, hasher)?;
+        }
+
+        // バッファをフラッシュして書き込みを完了
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// 分割ファイルを探す基準のパスを返す
+    ///
+    /// # 引数
+    /// * `base_path` - 元ファイルのパス
+    ///
+    /// # 戻り値
+    /// 分割ファイルを探すディレクトリと名前の指定を反映したパス
+    pub fn part_base_path(&self, base_path: &Path) -> PathBuf {
+        writer::part_base_path(
+            base_path,
+            self.output_dir.as_deref(),
+            self.prefix.as_deref(),
+        )
+    }
+
+    /// 他のツールで分割されたファイル一式を結合する
+    ///
+    /// # 引数
+    /// * `detected` - `detect_parts` で検出した分割ファイル一式
+    /// * `output` - 結合後のファイルパス。ディレクトリの場合はその中に分割前と同じ名前で作成する
+    ///
+    /// # 動作
+    /// 検出した順番に分割ファイルを連結する。マニフェストやチェックサムファイルは使用しない。
+    /// 連番に欠けがある場合は、欠けを許可していなければエラーにする
+    pub fn merge_detected(&self, detected: &DetectedParts, output: &Path) -> Result<()> {
+        let output = if output.is_dir() {
+            output.join(detected.base_path.file_name().unwrap_or_default())
+        } else {
+            output.to_path_buf()
+        };
+        if !self.force && output.exists() {
+            return Err(FileSplitError::OutputExists(
+                output.to_string_lossy().to_string(),
+            ));
+        }
+
+        // 一時ファイルに書き込み、すべて成功した場合だけ出力先に置き換える
+        let mut writer = OutputFile::create(&output, self.force)?;
+        self.merge_detected_to_writer(detected, &mut writer)?;
+        writer.commit()
+    }
+
+    /// 他のツールで分割されたファイル一式を結合して任意の書き込み先に書き込む
+    ///
+    /// # 引数
+    /// * `detected` - `detect_parts` で検出した分割ファイル一式
+    /// * `writer` - 結合結果の書き込み先（標準出力など）
+    pub fn merge_detected_to_writer<W: Write>(
+        &self,
+        detected: &DetectedParts,
+        writer: &mut W,
+    ) -> Result<()> {
+        let missing = detected.missing();
+        if !missing.is_empty() && (!self.allow_partial || detected.parts.is_empty()) {
+            return Err(FileSplitError::MissingParts(missing));
+        }
+
+        let mut state = MergeState {
+            total: detected.total,
+            container: false,
+            set_id: None,
+            offset: 0,
+            original_name: detected
+                .base_path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            output_hasher: None,
+        };
+        let sidecars = Sidecars::default();
+        for (&index, path) in &detected.parts {
+            self.copy_part(index, path, None, &sidecars, &mut state, writer)?;
+        }
+        Ok(())
+    }
+
+    /// 分割ファイル一式のマニフェストを読み込む
+    ///
+    /// # 引数
+    /// * `base_path` - 元ファイルのパス
+    ///
+    /// # 戻り値
+    /// 分割ファイルを探す基準のパスのマニフェスト（存在しない場合はNone）
+    pub fn load_manifest(&self, base_path: &Path) -> Result<Option<Manifest>> {
+        let manifest_path = Manifest::path_for(&self.part_base_path(base_path));
+        if manifest_path.exists() {
+            Manifest::load(&manifest_path).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// 結合する分割ファイルの一覧を返す
+    ///
+    /// # 引数
+    /// * `base_path` - 元ファイルのパス
+    ///
+    /// # 動作
+    /// 分割ファイルの探し方は `merge_files` と同じ。
+    /// 欠けている分割ファイルもサイズなしで一覧に含める
+    ///
+    /// # 戻り値
+    /// 番号順の分割ファイルの一覧。1つも見つからない場合はエラー
+    pub fn list_parts(&self, base_path: &Path) -> Result<Vec<PartEntry>> {
+        let base_path = &self.part_base_path(base_path);
+        let manifest_path = Manifest::path_for(base_path);
+        let mut paths = BTreeMap::new();
+        if manifest_path.exists() {
+            for part in Manifest::load(&manifest_path)?.parts {
+                let path = Self::manifest_part_path(base_path, &part.name)?;
+                paths.insert(part.index, path);
+            }
+        } else {
+            // 連番の欠けも一覧に含める（名前は見つかった分割ファイルの桁数に合わせる）
+            paths = self.naming.scan(base_path)?;
+            let Some(&last) = paths.keys().next_back() else {
+                return Err(FileSplitError::NoSplitFiles(
+                    base_path.to_string_lossy().to_string(),
+                ));
+            };
+            let naming = self.naming.fit(last)?;
+            for index in 1..=last {
+                if let Entry::Vacant(entry) = paths.entry(index) {
+                    entry.insert(naming.path(base_path, index)?);
                 }
-                // 連番が途切れたら終了
-                break;
             }
+        }
+
+        Ok(paths
+            .into_iter()
+            .map(|(index, path)| PartEntry {
+                index,
+                size: fs::metadata(&path).ok().map(|metadata| metadata.len()),
+                path,
+            })
+            .collect())
+    }
+
+    /// コンテナ形式の分割ファイルを名前に関係なくヘッダーの情報で結合する
+    ///
+    /// # 引数
+    /// * `inputs` - 分割ファイルのパス、または分割ファイルを含むディレクトリ
+    /// * `output` - 結合後のファイルパス
+    ///
+    /// # 動作
+    /// ディレクトリが指定された場合は、その直下のファイルのうち
+    /// ヘッダーが付いているものだけを対象にする。
+    /// すべての分割ファイルが同じ分割ファイル一式のものであることを確認し、
+    /// ヘッダーに記録された番号順に結合する
+    pub fn merge_parts(&self, inputs: &[PathBuf], output: &Path) -> Result<()> {
+        if !self.force && output.exists() {
+            return Err(FileSplitError::OutputExists(
+                output.to_string_lossy().to_string(),
+            ));
+        }
+        let (headers, total) = self.collect_container_parts(inputs)?;
 
-            found_any = true;
+        // 一時ファイルに書き込み、すべて成功した場合だけ出力先に置き換える
+        let mut writer = OutputFile::create(output, self.force)?;
 
-            // 分割ファイルを読み込んで出力ファイルに書き込む
-            let mut reader = BufReader::new(File::open(&split_path)?);
-            let mut buffer = Vec::new();
-            reader.read_to_end(&mut buffer)?;
-            writer.write_all(&buffer)?;
+        let original_name = output
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let mut state = MergeState {
+            total,
+            container: true,
+            set_id: None,
+            offset: 0,
+            original_name,
+            output_hasher: None,
+        };
+        let sidecars = Sidecars::default();
 
-            // 次のファイルへ
-            index += 1;
+        for (index, path) in headers {
+            self.copy_part(
+                index as usize,
+                &path,
+                None,
+                &sidecars,
+                &mut state,
+                &mut writer,
+            )?;
         }
 
-        // バッファをフラッシュして書き込みを完了
-        writer.flush()?;
+        writer.commit()
+    }
+
+    /// コンテナ形式の分割ファイルを探して番号順に並べる
+    ///
+    /// # 戻り値
+    /// 番号と分割ファイルパスの組（番号順）と分割ファイルの総数の組
+    fn collect_container_parts(
+        &self,
+        inputs: &[PathBuf],
+    ) -> Result<(BTreeMap<u64, PathBuf>, usize)> {
+        // ヘッダーの付いた分割ファイルを集める
+        let mut found: Vec<(PartHeader, PathBuf)> = Vec::new();
+        for input in inputs {
+            if input.is_dir() {
+                let mut entries: Vec<PathBuf> = fs::read_dir(input)?
+                    .map(|entry| entry.map(|e| e.path()))
+                    .collect::<std::io::Result<_>>()?;
+                entries.sort();
+                for path in entries.into_iter().filter(|path| path.is_file()) {
+                    // ヘッダーのないファイル（マニフェストなど）は対象外
+                    if let Some(header) = PartHeader::read_from(&path)? {
+                        found.push((header, path));
+                    }
+                }
+            } else if input.exists() {
+                match PartHeader::read_from(input)? {
+                    Some(header) => found.push((header, input.clone())),
+                    None => {
+                        return Err(FileSplitError::InvalidContainer(format!(
+                            "{}: ヘッダーがありません",
+                            input.display()
+                        )))
+                    }
+                }
+            } else {
+                return Err(FileSplitError::FileNotFound(
+                    input.to_string_lossy().to_string(),
+                ));
+            }
+        }
+
+        let Some((first, _)) = found.first() else {
+            return Err(FileSplitError::InvalidContainer(
+                "ヘッダー付きの分割ファイルが見つかりません".to_string(),
+            ));
+        };
+
+        // 別の分割ファイル一式のものが混ざっていれば結合しない
+        let set_id = first.set_id.clone();
+        let total = first.total;
+        if let Some((header, path)) = found
+            .iter()
+            .find(|(header, _)| header.set_id != set_id || header.total != total)
+        {
+            return Err(FileSplitError::InvalidContainer(format!(
+                "{}: 別の分割ファイル一式のものです（識別子 {}、期待する識別子 {}）",
+                path.display(),
+                header.set_id,
+                set_id
+            )));
+        }
+
+        // 番号順に並べ、同じ番号の分割ファイルは内容が同じものだけを認める
+        let mut parts: BTreeMap<u64, (PartHeader, PathBuf)> = BTreeMap::new();
+        for (header, path) in found {
+            if header.index == 0 || header.index > total {
+                return Err(FileSplitError::InvalidContainer(format!(
+                    "{}: 番号が範囲外です: {}",
+                    path.display(),
+                    header.index
+                )));
+            }
+            match parts.get(&header.index) {
+                Some((existing, existing_path)) if existing.checksum != header.checksum => {
+                    return Err(FileSplitError::InvalidContainer(format!(
+                        "番号 {} の分割ファイルが重複しています: {}, {}",
+                        header.index,
+                        existing_path.display(),
+                        path.display()
+                    )));
+                }
+                Some(_) => {}
+                None => {
+                    parts.insert(header.index, (header, path));
+                }
+            }
+        }
+
+        let missing: Vec<usize> = (1..=total)
+            .filter(|index| !parts.contains_key(index))
+            .map(|index| index as usize)
+            .collect();
+        if !missing.is_empty() && !self.allow_partial {
+            return Err(FileSplitError::MissingParts(missing));
+        }
+
+        let parts = parts
+            .into_iter()
+            .map(|(index, (_, path))| (index, path))
+            .collect();
+        Ok((parts, total as usize))
+    }
+
+    /// コンテナ形式のヘッダーを読み取り、分割ファイル一式の識別子を確認する
+    ///
+    /// # 引数
+    /// * `bytes` - 分割ファイルの先頭のデータ
+    /// * `split_path` - エラー表示用の分割ファイルパス
+    /// * `set_id` - 期待する分割ファイル一式の識別子（Noneの場合は最初のヘッダーの識別子を設定する）
+    ///
+    /// # 戻り値
+    /// 読み取ったヘッダー
+    fn check_container_header(
+        bytes: &[u8],
+        split_path: &Path,
+        set_id: &mut Option<String>,
+    ) -> Result<PartHeader> {
+        let header = PartHeader::parse(bytes)
+            .map_err(|e| container::with_path(e, split_path))?
+            .ok_or_else(|| {
+                FileSplitError::InvalidContainer(format!(
+                    "{}: ヘッダーがありません",
+                    split_path.display()
+                ))
+            })?;
+
+        let expected = set_id.get_or_insert_with(|| header.set_id.clone());
+        if !expected.is_empty() && *expected != header.set_id {
+            return Err(FileSplitError::InvalidContainer(format!(
+                "{}: 別の分割ファイル一式のものです（識別子 {}、期待する識別子 {}）",
+                split_path.display(),
+                header.set_id,
+                expected
+            )));
+        }
+        Ok(header)
+    }
+
+    /// 分割ファイルを1つ読み込み、確認しながら書き込み先にコピーする
+    ///
+    /// # 引数
+    /// * `index` - 分割ファイルの番号
+    /// * `split_path` - 分割ファイルのパス
+    /// * `expected` - マニフェストに記録された情報
+    /// * `sidecars` - チェックサムファイルの記録
+    /// * `state` - 結合中の状態
+    /// * `writer` - 結合結果の書き込み先
+    ///
+    /// # 動作
+    /// 分割ファイル全体をメモリに読み込まず、一定の大きさのバッファで
+    /// 読み込みながらヘッダー・フッターを取り除いて書き込む。
+    /// ハッシュとチェックサムは読み込みながら計算し、最後に記録と照合する。
+    /// 照合に失敗した場合も、それまでのデータは書き込み先に書き込まれている
+    fn copy_part<W: Write>(
+        &self,
+        index: usize,
+        split_path: &Path,
+        expected: Option<&ManifestPart>,
+        sidecars: &Sidecars,
+        state: &mut MergeState,
+        writer: &mut W,
+    ) -> Result<()> {
+        let file = File::open(split_path)?;
+        let size = file.metadata()?.len();
+        let name = split_path.file_name().unwrap_or_default().to_string_lossy();
+        let mut reader = PartReader::new(file, sidecars.contains(&name));
+
+        // マニフェストの記録とサイズが違えば読み込む前にエラーにする
+        if let Some(expected) = expected {
+            if size != expected.size {
+                return Err(FileSplitError::CorruptedPart(format!(
+                    "{} (サイズ {} バイト、記録では {} バイト)",
+                    split_path.display(),
+                    size,
+                    expected.size
+                )));
+            }
+            state.offset = expected.offset;
+        }
+
+        // コンテナ形式のヘッダーを確認して読み飛ばす
+        let mut payload_len = size;
+        let mut container_header = None;
+        if state.container {
+            let mut bytes = [0u8; HEADER_LEN];
+            let read = reader.read_full(&mut bytes)?;
+            let header =
+                Self::check_container_header(&bytes[..read], split_path, &mut state.set_id)?;
+            if header.index != index as u64 {
+                return Err(FileSplitError::InvalidContainer(format!(
+                    "{}: 番号が一致しません（ヘッダーでは {}）",
+                    split_path.display(),
+                    header.index
+                )));
+            }
+            payload_len = size - HEADER_LEN as u64;
+            if payload_len != header.length {
+                return Err(FileSplitError::CorruptedPart(format!(
+                    "{} (サイズ {} バイト、ヘッダーの記録では {} バイト)",
+                    split_path.display(),
+                    payload_len,
+                    header.length
+                )));
+            }
+            state.offset = header.offset;
+            reader.payload = Sha256::new();
+            container_header = Some(header);
+        }
+
+        // ヘッダー・フッターの展開結果と一致しなければ元のデータを復元できない
+        let info = PartInfo {
+            index,
+            total: state.total,
+            offset: state.offset,
+            original_name: &state.original_name,
+        };
+        let template_header = self.template.render_header(&info);
+        let template_footer = self.template.render_footer(&info);
+        let template_len = (template_header.len() + template_footer.len()) as u64;
+        if payload_len < template_len || !reader.starts_with(template_header.as_bytes())? {
+            return Err(FileSplitError::TemplateMismatch(
+                split_path.to_string_lossy().to_string(),
+            ));
+        }
+
+        // ヘッダー・フッターの間のデータを書き込む
+        let data_len = payload_len - template_len;
+        let mut remaining = data_len;
+        let mut buffer = vec![0u8; BUFFER_SIZE.min(data_len as usize)];
+        while remaining > 0 {
+            let len = buffer.len().min(remaining as usize);
+            let read = reader.read_full(&mut buffer[..len])?;
+            if read == 0 {
+                return Err(FileSplitError::CorruptedPart(format!(
+                    "{} (読み込み中にサイズが変わりました)",
+                    split_path.display()
+                )));
+            }
+            writer.write_all(&buffer[..read])?;
+            if let Some(hasher) = &mut state.output_hasher {
+                hasher.update(&buffer[..read]);
+            }
+            remaining -= read as u64;
+        }
+
+        if !reader.starts_with(template_footer.as_bytes())? {
+            return Err(FileSplitError::TemplateMismatch(
+                split_path.to_string_lossy().to_string(),
+            ));
+        }
+        if reader.read_full(&mut [0u8; 1])? != 0 {
+            return Err(FileSplitError::CorruptedPart(format!(
+                "{} (読み込み中にサイズが変わりました)",
+                split_path.display()
+            )));
+        }
+
+        // 読み込みながら計算したハッシュを記録と照合する
+        if let Some(expected) = expected {
+            if to_hex(&reader.sha256.finalize_reset()) != expected.sha256 {
+                return Err(FileSplitError::CorruptedPart(format!(
+                    "{} (SHA-256が一致しません)",
+                    split_path.display()
+                )));
+            }
+        }
+        if let Some(header) = container_header {
+            if reader.payload.finalize_reset()[..] != header.checksum[..] {
+                return Err(FileSplitError::CorruptedPart(format!(
+                    "{} (SHA-256が一致しません)",
+                    split_path.display()
+                )));
+            }
+        }
+        if let Some(checksums) = &reader.checksums {
+            sidecars.check_hasher(split_path, checksums)?;
+        }
+
+        state.offset += data_len;
         Ok(())
     }
 
-    /// 指定されたインデックスの分割ファイルパスを生成する
+    /// 結合する分割ファイルの一覧を取得する
     ///
     /// # 引数
     /// * `base_path` - 元ファイルのパス
-    /// * `index` - ファイルのインデックス（1から始まる）
     ///
     /// # 戻り値
-    /// 例: "file.txt", 1 -> "file.txt.001"
-    fn get_split_file_path(base_path: &Path, index: usize) -> PathBuf {
-        let mut path = base_path.to_path_buf();
-        let file_name = format!(
-            "{}.{:03}",
-            path.file_name().unwrap_or_default().to_string_lossy(),
-            index
-        );
-        path.set_file_name(file_name);
-        path
+    /// 存在する分割ファイル（番号順）と分割ファイル一式の情報
+    fn collect_parts(&self, base_path: &Path) -> Result<PartSet> {
+        let mut parts = Vec::new();
+        let mut missing = Vec::new();
+        let mut container = self.container;
+        let mut set_id = None;
+        let mut original = None;
+        let mut original_name = None;
+
+        let manifest_path = Manifest::path_for(base_path);
+        let total = if manifest_path.exists() {
+            // マニフェストがあれば記録された分割ファイルを使用する
+            let manifest = Manifest::load(&manifest_path)?;
+            container = manifest.container;
+            set_id = Some(manifest.set_id);
+            original = manifest.original;
+            original_name = Some(manifest.original_name);
+            for part in manifest.parts {
+                let path = Self::manifest_part_path(base_path, &part.name)?;
+                if path.exists() {
+                    parts.push(PartSource {
+                        index: part.index,
+                        path,
+                        expected: Some(part),
+                    });
+                } else {
+                    missing.push(part.index);
+                }
+            }
+            manifest.part_count
+        } else {
+            // マニフェストがなければ同じ名前の分割ファイルをすべて探す
+            let scanned = self.naming.scan(base_path)?;
+            let Some(&last) = scanned.keys().next_back() else {
+                // 1つも分割ファイルが見つからなかった場合はエラー
+                return Err(FileSplitError::NoSplitFiles(
+                    base_path.to_string_lossy().to_string(),
+                ));
+            };
+            missing.extend((1..=last).filter(|index| !scanned.contains_key(index)));
+            parts.extend(scanned.into_iter().map(|(index, path)| PartSource {
+                index,
+                path,
+                expected: None,
+            }));
+            last
+        };
+
+        // 連番に欠けがあれば元のファイルを復元できない
+        if !missing.is_empty() && (!self.allow_partial || parts.is_empty()) {
+            return Err(FileSplitError::MissingParts(missing));
+        }
+        Ok(PartSet {
+            parts,
+            total,
+            container,
+            set_id,
+            original,
+            original_name,
+        })
+    }
+
+    /// マニフェストに記録された名前から分割ファイルのパスを生成する
+    ///
+    /// # 戻り値
+    /// 元ファイルと同じディレクトリのパス。名前にディレクトリを含む場合はエラー
+    pub(crate) fn manifest_part_path(base_path: &Path, name: &str) -> Result<PathBuf> {
+        // 分割ファイルは元ファイルと同じディレクトリにあるものだけを認める
+        if Path::new(name).file_name() != Some(name.as_ref()) {
+            return Err(FileSplitError::InvalidManifest(format!(
+                "不正な分割ファイル名です: {name}"
+            )));
+        }
+        let mut split_path = base_path.to_path_buf();
+        split_path.set_file_name(name);
+        Ok(split_path)
+    }
+}
+
+/// Mergerのビルダー
+///
+/// # 例
+/// ```no_run
+/// use filesplit_rs::Merger;
+///
+/// let merger = Merger::builder()
+///     .header("== {original_name} {index}/{total} ==\n")
+///     .build()?;
+/// merger.merge_files("notes.txt".as_ref())?;
+/// # Ok::<(), filesplit_rs::FileSplitError>(())
+/// ```
+#[derive(Default)]
+pub struct MergerBuilder {
+    /// 結合のオプション
+    options: MergeOptions,
+}
+
+impl MergerBuilder {
+    /// オプションをまとめて指定する
+    pub fn options(mut self, options: MergeOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// 分割時に挿入したヘッダー・フッターを指定する
+    pub fn template(mut self, template: PartTemplate) -> Self {
+        self.options.template = template;
+        self
+    }
+
+    /// 分割時に挿入したヘッダーのテンプレートを指定する
+    pub fn header(mut self, header: impl Into<String>) -> Self {
+        self.options.template.header = Some(header.into());
+        self
+    }
+
+    /// 分割ファイルにコンテナ形式のヘッダーが付いていることを指定する
+    ///
+    /// マニフェストがある場合はマニフェストの記録が優先される
+    pub fn container(mut self, container: bool) -> Self {
+        self.options.container = container;
+        self
+    }
+
+    /// 出力先のファイルが既に存在する場合に上書きするかどうかを指定する
+    /// （デフォルトは上書きせずにエラーにする）
+    pub fn force(mut self, force: bool) -> Self {
+        self.options.force = force;
+        self
+    }
+
+    /// 欠けている分割ファイルを飛ばして、存在する分割ファイルだけを結合する
+    pub fn allow_partial(mut self, allow_partial: bool) -> Self {
+        self.options.allow_partial = allow_partial;
+        self
+    }
+
+    /// 分割時に挿入したフッターのテンプレートを指定する
+    pub fn footer(mut self, footer: impl Into<String>) -> Self {
+        self.options.template.footer = Some(footer.into());
+        self
+    }
+
+    /// 分割ファイルを探すディレクトリを指定する
+    pub fn output_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.options.output_dir = Some(dir.into());
+        self
+    }
+
+    /// 分割時に元ファイル名の代わりに使用した名前を指定する
+    pub fn prefix(mut self, prefix: impl Into<PathBuf>) -> Self {
+        self.options.prefix = Some(prefix.into());
+        self
+    }
+
+    /// 分割時に使用した分割ファイルの名前の付け方を指定する
+    pub fn naming(mut self, naming: NamingScheme) -> Self {
+        self.options.naming = naming;
+        self
+    }
+
+    /// 記録された元ファイルの名前と属性を復元するかどうかを指定する
+    /// （デフォルトは復元する）
+    pub fn metadata(mut self, metadata: bool) -> Self {
+        self.options.metadata = metadata;
+        self
+    }
+
+    /// 記録された拡張属性も復元するかどうかを指定する
+    ///
+    /// xattr 機能を有効にしてビルドした Unix 環境でのみ使用できる
+    pub fn xattrs(mut self, xattrs: bool) -> Self {
+        self.options.xattrs = xattrs;
+        self
+    }
+
+    /// 記録された setuid・setgid・スティッキービットも復元するかどうかを指定する
+    /// （デフォルトは tar と同じく復元しない）
+    ///
+    /// マニフェストは分割ファイルと一緒に受け取るものなので、信頼できる場合だけ指定する
+    pub fn special_bits(mut self, special_bits: bool) -> Self {
+        self.options.special_bits = special_bits;
+        self
+    }
+
+    /// Mergerを作成する
+    pub fn build(self) -> Result<Merger> {
+        Ok(Merger::from_options(self.options))
     }
 }
 
@@ -87,6 +1031,7 @@ impl Merger {
 mod tests {
     use super::*;
     use crate::error::FileSplitError;
+    use crate::manifest::sha256_hex;
     use std::fs;
     use std::io::Write;
     use tempfile::TempDir;
@@ -99,21 +1044,6 @@ mod tests {
         let _ = merger;
     }
 
-    #[test]
-    fn test_get_split_file_path() {
-        // 分割ファイルパスの生成をテスト
-        let base = Path::new("/tmp/test.txt");
-
-        assert_eq!(
-            Merger::get_split_file_path(base, 1),
-            PathBuf::from("/tmp/test.txt.001")
-        );
-        assert_eq!(
-            Merger::get_split_file_path(base, 100),
-            PathBuf::from("/tmp/test.txt.100")
-        );
-    }
-
     #[test]
     fn test_merge_files_success() {
         // 正常な結合処理のテスト
@@ -155,13 +1085,19 @@ mod tests {
         let mut file4 = fs::File::create(temp_dir.path().join("test.txt.004")).unwrap();
         file4.write_all(b" Part 4").unwrap();
 
-        // ファイルを結合（.003で停止するはず）
+        // 欠けている番号を示すエラーになるはず
         let merger = Merger::new();
+        match merger.merge_files(&base_path).unwrap_err() {
+            FileSplitError::MissingParts(indices) => assert_eq!(indices, vec![3]),
+            _ => panic!("予期しないエラー型"),
+        }
+
+        // 部分的な結合を許可すると存在する分割ファイルだけを結合する
+        let merger = Merger::builder().allow_partial(true).build().unwrap();
         merger.merge_files(&base_path).unwrap();
 
-        // 結合されたファイルの内容を確認（.001と.002のみ）
         let content = fs::read_to_string(&base_path).unwrap();
-        assert_eq!(content, "Part 1 Part 2");
+        assert_eq!(content, "Part 1 Part 2 Part 4");
     }
 
     #[test]
@@ -213,6 +1149,623 @@ mod tests {
         assert_eq!(content, "");
     }
 
+    #[test]
+    fn test_merge_with_template() {
+        // ヘッダー・フッターを取り除いて結合するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("test.txt");
+
+        fs::write(
+            temp_dir.path().join("test.txt.001"),
+            "# test.txt 1/2 @0\nHello, # end\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("test.txt.002"),
+            "# test.txt 2/2 @7\nWorld!# end\n",
+        )
+        .unwrap();
+
+        let template = PartTemplate {
+            header: Some("# {original_name} {index}/{total} @{offset}\n".to_string()),
+            footer: Some("# end\n".to_string()),
+        };
+        let merger = Merger::builder().template(template).build().unwrap();
+        merger.merge_files(&base_path).unwrap();
+
+        let content = fs::read_to_string(&base_path).unwrap();
+        assert_eq!(content, "Hello, World!");
+    }
+
+    #[test]
+    fn test_merge_with_template_mismatch() {
+        // ヘッダーが一致しない場合のエラーテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("test.txt");
+
+        fs::write(temp_dir.path().join("test.txt.001"), "no header").unwrap();
+
+        let template = PartTemplate {
+            header: Some("[{index}]".to_string()),
+            footer: None,
+        };
+        let merger = Merger::builder().template(template).build().unwrap();
+        let result = merger.merge_files(&base_path);
+
+        match result.unwrap_err() {
+            FileSplitError::TemplateMismatch(path) => assert!(path.ends_with("test.txt.001")),
+            _ => panic!("予期しないエラー型"),
+        }
+    }
+
+    #[test]
+    fn test_merge_with_manifest() {
+        // マニフェストに記録された分割ファイルだけを結合するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("test.txt");
+
+        fs::write(temp_dir.path().join("test.txt.001"), "Hello, ").unwrap();
+        fs::write(temp_dir.path().join("test.txt.002"), "World!").unwrap();
+        // マニフェストに記録されていない分割ファイルは使用しない
+        fs::write(temp_dir.path().join("test.txt.003"), "stale").unwrap();
+
+        let mut manifest = Manifest::new("test.txt");
+        manifest.push_part("test.txt.001".to_string(), 7, 7, sha256_hex(b"Hello, "));
+        manifest.push_part("test.txt.002".to_string(), 6, 6, sha256_hex(b"World!"));
+        manifest.save(&Manifest::path_for(&base_path)).unwrap();
+
+        Merger::new().merge_files(&base_path).unwrap();
+
+        let content = fs::read_to_string(&base_path).unwrap();
+        assert_eq!(content, "Hello, World!");
+    }
+
+    #[test]
+    fn test_merge_with_manifest_corrupted_part() {
+        // 内容が記録と一致しない場合のエラーテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("test.txt");
+
+        fs::write(temp_dir.path().join("test.txt.001"), "Hellx").unwrap();
+
+        let mut manifest = Manifest::new("test.txt");
+        manifest.push_part("test.txt.001".to_string(), 5, 5, sha256_hex(b"Hello"));
+        manifest.save(&Manifest::path_for(&base_path)).unwrap();
+
+        let result = Merger::new().merge_files(&base_path);
+        assert!(matches!(result, Err(FileSplitError::CorruptedPart(_))));
+    }
+
+    #[test]
+    fn test_merge_refuses_part_from_other_split() {
+        // 以前の分割で作成された分割ファイルを結合しないテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("data.bin");
+        let old_part = temp_dir.path().join("old.002");
+
+        fs::write(&base_path, "0123456789".repeat(5)).unwrap();
+        crate::Splitter::new(10).split_file(&base_path).unwrap();
+        fs::copy(temp_dir.path().join("data.bin.002"), &old_part).unwrap();
+
+        // 再分割すると.004と.005は削除され、マニフェストに記録されない
+        fs::write(&base_path, "abcdefghij".repeat(3)).unwrap();
+        crate::Splitter::new(10).split_file(&base_path).unwrap();
+        fs::write(temp_dir.path().join("data.bin.004"), "stale").unwrap();
+        fs::remove_file(&base_path).unwrap();
+        Merger::new().merge_files(&base_path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&base_path).unwrap(),
+            "abcdefghij".repeat(3)
+        );
+
+        // 別の分割の分割ファイルに置き換えられていればエラー
+        fs::copy(&old_part, temp_dir.path().join("data.bin.002")).unwrap();
+        let result = Merger::builder()
+            .force(true)
+            .build()
+            .unwrap()
+            .merge_files(&base_path);
+        assert!(matches!(result, Err(FileSplitError::CorruptedPart(_))));
+    }
+
+    /// コンテナ形式で分割したファイル一式を作成する
+    fn split_container(dir: &Path, name: &str, content: &str, size: u64) -> PathBuf {
+        let file_path = dir.join(name);
+        fs::write(&file_path, content).unwrap();
+        crate::Splitter::builder()
+            .chunk_size(size)
+            .container(true)
+            .manifest(false)
+            .build()
+            .unwrap()
+            .split_file(&file_path)
+            .unwrap();
+        fs::remove_file(&file_path).unwrap();
+        file_path
+    }
+
+    #[test]
+    fn test_merge_container_by_base_path() {
+        // コンテナ形式の分割ファイルをヘッダーを取り除いて結合するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = split_container(temp_dir.path(), "data.bin", "0123456789abc", 5);
+
+        // ヘッダーを取り除かないと元のファイルに戻らない
+        Merger::new().merge_files(&base_path).unwrap();
+        assert_ne!(fs::read(&base_path).unwrap(), b"0123456789abc");
+
+        let merger = Merger::builder()
+            .container(true)
+            .force(true)
+            .build()
+            .unwrap();
+        merger.merge_files(&base_path).unwrap();
+        assert_eq!(fs::read_to_string(&base_path).unwrap(), "0123456789abc");
+
+        // 番号が入れ替わっていればエラー
+        let dir = temp_dir.path();
+        fs::rename(dir.join("data.bin.001"), dir.join("tmp")).unwrap();
+        fs::rename(dir.join("data.bin.002"), dir.join("data.bin.001")).unwrap();
+        fs::rename(dir.join("tmp"), dir.join("data.bin.002")).unwrap();
+        let result = merger.merge_files(&base_path);
+        assert!(matches!(result, Err(FileSplitError::InvalidContainer(_))));
+    }
+
+    #[test]
+    fn test_merge_parts_renamed() {
+        // 名前が変更された分割ファイルをヘッダーの情報で結合するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let parts_dir = temp_dir.path().join("parts");
+        fs::create_dir(&parts_dir).unwrap();
+        split_container(&parts_dir, "data.bin", "0123456789abc", 5);
+
+        fs::rename(parts_dir.join("data.bin.001"), parts_dir.join("zzz")).unwrap();
+        fs::rename(
+            parts_dir.join("data.bin.002"),
+            parts_dir.join("data.bin(1).003"),
+        )
+        .unwrap();
+        fs::rename(parts_dir.join("data.bin.003"), parts_dir.join("aaa")).unwrap();
+        fs::write(parts_dir.join("notes.txt"), "not a part").unwrap();
+
+        let output = temp_dir.path().join("restored.bin");
+        Merger::new()
+            .merge_parts(std::slice::from_ref(&parts_dir), &output)
+            .unwrap();
+        assert_eq!(fs::read_to_string(&output).unwrap(), "0123456789abc");
+
+        // ファイルを個別に指定しても結合できる
+        let inputs = vec![
+            parts_dir.join("aaa"),
+            parts_dir.join("zzz"),
+            parts_dir.join("data.bin(1).003"),
+        ];
+        fs::remove_file(&output).unwrap();
+        Merger::new().merge_parts(&inputs, &output).unwrap();
+        assert_eq!(fs::read_to_string(&output).unwrap(), "0123456789abc");
+
+        // 欠けている分割ファイルがあればエラー
+        fs::remove_file(&output).unwrap();
+        let result = Merger::new().merge_parts(&inputs[..2], &output);
+        match result.unwrap_err() {
+            FileSplitError::MissingParts(indices) => assert_eq!(indices, vec![2]),
+            _ => panic!("予期しないエラー型"),
+        }
+
+        // ヘッダーのないファイルを個別に指定するとエラー
+        let result = Merger::new().merge_parts(&[parts_dir.join("notes.txt")], &output);
+        assert!(matches!(result, Err(FileSplitError::InvalidContainer(_))));
+    }
+
+    #[test]
+    fn test_merge_parts_rejects_other_set() {
+        // 別の分割ファイル一式のものが混ざっている場合のエラーテスト
+        let temp_dir = TempDir::new().unwrap();
+        let first = temp_dir.path().join("first");
+        let second = temp_dir.path().join("second");
+        fs::create_dir(&first).unwrap();
+        fs::create_dir(&second).unwrap();
+        split_container(&first, "data.bin", "0123456789", 5);
+        split_container(&second, "data.bin", "abcdefghij", 5);
+
+        let output = temp_dir.path().join("restored.bin");
+        let inputs = vec![first.join("data.bin.001"), second.join("data.bin.002")];
+        match Merger::new().merge_parts(&inputs, &output).unwrap_err() {
+            FileSplitError::InvalidContainer(msg) => assert!(msg.contains("別の分割ファイル一式")),
+            _ => panic!("予期しないエラー型"),
+        }
+    }
+
+    #[test]
+    fn test_merge_parts_corrupted_payload() {
+        // ヘッダーのハッシュと内容が一致しない場合のエラーテスト
+        let temp_dir = TempDir::new().unwrap();
+        split_container(temp_dir.path(), "data.bin", "0123456789", 5);
+
+        let part = temp_dir.path().join("data.bin.002");
+        let mut content = fs::read(&part).unwrap();
+        *content.last_mut().unwrap() = b'X';
+        fs::write(&part, content).unwrap();
+
+        let output = temp_dir.path().join("restored.bin");
+        let result = Merger::new().merge_parts(&[temp_dir.path().to_path_buf()], &output);
+        assert!(matches!(result, Err(FileSplitError::CorruptedPart(_))));
+    }
+
+    #[test]
+    fn test_merge_checks_sidecars() {
+        // チェックサムファイルの記録と照合して結合するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("data.bin");
+        fs::write(&base_path, "0123456789abcde").unwrap();
+        crate::Splitter::builder()
+            .chunk_size(10)
+            .manifest(false)
+            .checksum(crate::ChecksumFormat::Md5)
+            .checksum_original(true)
+            .build()
+            .unwrap()
+            .split_file(&base_path)
+            .unwrap();
+
+        fs::remove_file(&base_path).unwrap();
+        Merger::new().merge_files(&base_path).unwrap();
+        assert_eq!(fs::read_to_string(&base_path).unwrap(), "0123456789abcde");
+
+        // 記録と一致しない分割ファイルがあればエラー
+        fs::write(temp_dir.path().join("data.bin.002"), "abcdX").unwrap();
+        fs::remove_file(&base_path).unwrap();
+        match Merger::new().merge_files(&base_path).unwrap_err() {
+            FileSplitError::CorruptedPart(msg) => assert!(msg.contains("data.bin.md5")),
+            _ => panic!("予期しないエラー型"),
+        }
+        // 失敗した場合は何も残らない
+        assert!(!base_path.exists());
+    }
+
+    #[test]
+    fn test_merge_does_not_clobber_existing_output() {
+        // 既存のファイルを上書きせず、失敗時に元のファイルを残すテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("test.txt");
+        fs::write(&base_path, "original").unwrap();
+
+        // 分割ファイルがない場合も元のファイルは切り詰められない
+        let result = Merger::builder()
+            .force(true)
+            .build()
+            .unwrap()
+            .merge_files(&base_path);
+        assert!(matches!(result, Err(FileSplitError::NoSplitFiles(_))));
+        assert_eq!(fs::read_to_string(&base_path).unwrap(), "original");
+
+        fs::write(temp_dir.path().join("test.txt.001"), "merged").unwrap();
+        let result = Merger::new().merge_files(&base_path);
+        assert!(matches!(result, Err(FileSplitError::OutputExists(_))));
+        assert_eq!(fs::read_to_string(&base_path).unwrap(), "original");
+
+        // 上書きを許可すると置き換える
+        let merger = Merger::builder().force(true).build().unwrap();
+        merger.merge_files(&base_path).unwrap();
+        assert_eq!(fs::read_to_string(&base_path).unwrap(), "merged");
+
+        // 一時ファイルが残っていないことを確認
+        let names: Vec<_> = fs::read_dir(temp_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names.len(), 2);
+    }
+
+    #[test]
+    fn test_merge_files_to_other_path() {
+        // 分割ファイルとは別の場所に結合するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let out_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("test.txt");
+        fs::write(temp_dir.path().join("test.txt.001"), "Hello ").unwrap();
+        fs::write(temp_dir.path().join("test.txt.002"), "World").unwrap();
+
+        // ファイルを指定した場合はそのパスに作成する
+        let output = out_dir.path().join("merged.txt");
+        Merger::new().merge_files_to(&base_path, &output).unwrap();
+        assert_eq!(fs::read_to_string(&output).unwrap(), "Hello World");
+
+        // ディレクトリを指定した場合は元ファイルと同じ名前で作成する
+        Merger::new()
+            .merge_files_to(&base_path, out_dir.path())
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(out_dir.path().join("test.txt")).unwrap(),
+            "Hello World"
+        );
+        assert!(!base_path.exists());
+
+        // 既存のファイルは上書きしない
+        let result = Merger::new().merge_files_to(&base_path, &output);
+        assert!(matches!(result, Err(FileSplitError::OutputExists(_))));
+    }
+
+    #[test]
+    fn test_merge_detected() {
+        // 他のツールの分割ファイルを番号の数値順に結合するテスト
+        let temp_dir = TempDir::new().unwrap();
+        for index in 1..=10 {
+            fs::write(
+                temp_dir.path().join(format!("movie.mkv.part{index}")),
+                format!("[{index}]"),
+            )
+            .unwrap();
+        }
+
+        let detected = crate::detect_parts(&temp_dir.path().join("movie.mkv.part1")).unwrap();
+        let merger = Merger::new();
+        merger.merge_detected(&detected, temp_dir.path()).unwrap();
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("movie.mkv")).unwrap(),
+            "[1][2][3][4][5][6][7][8][9][10]"
+        );
+
+        // 既存の出力先は上書きしない
+        let result = merger.merge_detected(&detected, temp_dir.path());
+        assert!(matches!(result, Err(FileSplitError::OutputExists(_))));
+
+        // 連番に欠けがある場合
+        fs::remove_file(temp_dir.path().join("movie.mkv.part5")).unwrap();
+        let detected = crate::detect_parts(&temp_dir.path().join("movie.mkv")).unwrap();
+        let mut output = Vec::new();
+        let result = merger.merge_detected_to_writer(&detected, &mut output);
+        assert!(matches!(result, Err(FileSplitError::MissingParts(m)) if m == vec![5]));
+    }
+
+    #[test]
+    fn test_merge_to_writer() {
+        // 任意の書き込み先に結合し、元ファイルのチェックサムも確認するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("data.bin");
+        fs::write(&base_path, "0123456789abcde").unwrap();
+        crate::Splitter::builder()
+            .chunk_size(10)
+            .checksum(crate::ChecksumFormat::Sha256)
+            .checksum_original(true)
+            .build()
+            .unwrap()
+            .split_file(&base_path)
+            .unwrap();
+
+        let mut output = Vec::new();
+        Merger::new()
+            .merge_to_writer(&base_path, &mut output)
+            .unwrap();
+        assert_eq!(output, b"0123456789abcde");
+
+        // 元ファイルの記録と一致しない場合はエラー
+        let sums_path = temp_dir.path().join("data.bin.SHA256SUMS");
+        let sums = fs::read_to_string(&sums_path).unwrap();
+        let sums = sums.replace(&sha256_hex(b"0123456789abcde"), &sha256_hex(b"other"));
+        fs::write(&sums_path, sums).unwrap();
+        let result = Merger::new().merge_to_writer(&base_path, &mut Vec::new());
+        assert!(matches!(result, Err(FileSplitError::CorruptedPart(_))));
+    }
+
+    /// 書き込みごとの最大サイズを記録する書き込み先
+    #[derive(Default)]
+    struct RecordingWriter {
+        data: Vec<u8>,
+        max_write: usize,
+    }
+
+    impl Write for RecordingWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.max_write = self.max_write.max(buf.len());
+            self.data.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_merge_streams_large_parts() {
+        // バッファより大きな分割ファイルを一定のバッファで結合するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("data.bin");
+        let data: Vec<u8> = (0..BUFFER_SIZE * 3 + 17).map(|i| (i % 251) as u8).collect();
+        fs::write(&base_path, &data).unwrap();
+        crate::Splitter::builder()
+            .chunk_size(BUFFER_SIZE as u64 * 2)
+            .header("--{index}/{total}--\n")
+            .footer("==end==")
+            .container(true)
+            .checksum(crate::ChecksumFormat::Sfv)
+            .checksum_original(true)
+            .build()
+            .unwrap()
+            .split_file(&base_path)
+            .unwrap();
+
+        let merger = Merger::builder()
+            .header("--{index}/{total}--\n")
+            .footer("==end==")
+            .build()
+            .unwrap();
+        let mut writer = RecordingWriter::default();
+        merger.merge_to_writer(&base_path, &mut writer).unwrap();
+        assert_eq!(writer.data, data);
+        assert!(writer.max_write <= BUFFER_SIZE);
+
+        // バッファの境界をまたぐ位置の破損も検出する
+        let part_path = temp_dir.path().join("data.bin.001");
+        let mut part = fs::read(&part_path).unwrap();
+        let pos = part.len() - BUFFER_SIZE / 2;
+        part[pos] ^= 0xff;
+        fs::write(&part_path, part).unwrap();
+        let result = merger.merge_to_writer(&base_path, &mut std::io::sink());
+        assert!(matches!(result, Err(FileSplitError::CorruptedPart(_))));
+    }
+
+    #[test]
+    fn test_merge_with_manifest_missing_part() {
+        // マニフェストに記録された分割ファイルがない場合のエラーテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("test.txt");
+
+        fs::write(temp_dir.path().join("test.txt.001"), "Hello").unwrap();
+
+        let mut manifest = Manifest::new("test.txt");
+        manifest.push_part("test.txt.001".to_string(), 5, 5, sha256_hex(b"Hello"));
+        manifest.push_part("test.txt.002".to_string(), 5, 5, sha256_hex(b"World"));
+        manifest.save(&Manifest::path_for(&base_path)).unwrap();
+
+        let result = Merger::new().merge_files(&base_path);
+        match result.unwrap_err() {
+            FileSplitError::MissingParts(indices) => assert_eq!(indices, vec![2]),
+            _ => panic!("予期しないエラー型"),
+        }
+    }
+
+    #[test]
+    fn test_merge_with_manifest_rejects_path_in_name() {
+        // 分割ファイル名にディレクトリを含むマニフェストを拒否するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("test.txt");
+
+        let mut manifest = Manifest::new("test.txt");
+        manifest.push_part("../secret".to_string(), 5, 5, sha256_hex(b"Hello"));
+        manifest.save(&Manifest::path_for(&base_path)).unwrap();
+
+        let result = Merger::new().merge_files(&base_path);
+        assert!(matches!(result, Err(FileSplitError::InvalidManifest(_))));
+    }
+
+    #[test]
+    fn test_merge_restores_original_metadata() {
+        // 記録された元ファイルの名前と最終更新日時を復元するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("report.pdf");
+        fs::write(&file_path, "Hello, World!").unwrap();
+        File::options()
+            .write(true)
+            .open(&file_path)
+            .unwrap()
+            .set_modified(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_500_000_000))
+            .unwrap();
+        let modified = fs::metadata(&file_path).unwrap().modified().unwrap();
+
+        // 別の名前で分割して元ファイルを削除する
+        let prefix = temp_dir.path().join("parts").join("x");
+        let mut splitter = crate::Splitter::builder()
+            .chunk_size(5)
+            .output_dir(temp_dir.path().join("parts"))
+            .prefix(&prefix)
+            .build()
+            .unwrap();
+        splitter.split_file(&file_path).unwrap();
+        fs::remove_file(&file_path).unwrap();
+
+        // 分割ファイルの名前だけを指定すると記録された名前で作成される
+        let output = Merger::new().merge_files(&prefix).unwrap();
+        assert_eq!(output, temp_dir.path().join("parts").join("report.pdf"));
+        assert_eq!(fs::read_to_string(&output).unwrap(), "Hello, World!");
+        assert_eq!(fs::metadata(&output).unwrap().modified().unwrap(), modified);
+
+        // ディレクトリを指定した場合もその中に記録された名前で作成される
+        let output = Merger::new()
+            .merge_files_to(&prefix, temp_dir.path())
+            .unwrap();
+        assert_eq!(output, file_path);
+
+        // 復元しない場合は指定したパスに結合する
+        let output = Merger::builder()
+            .metadata(false)
+            .build()
+            .unwrap()
+            .merge_files(&prefix)
+            .unwrap();
+        assert_eq!(output, prefix);
+        assert_ne!(fs::metadata(&output).unwrap().modified().unwrap(), modified);
+    }
+
+    #[test]
+    fn test_merge_rejects_unsafe_original_name() {
+        // 記録された元ファイル名にディレクトリを含むマニフェストを拒否するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("test.txt");
+        fs::write(temp_dir.path().join("test.txt.001"), "Hello").unwrap();
+
+        for name in ["../escaped", "/tmp/escaped"] {
+            let mut manifest = Manifest::new("test.txt");
+            manifest.push_part("test.txt.001".to_string(), 5, 5, sha256_hex(b"Hello"));
+            manifest.original = Some(FileMetadata {
+                name: name.to_string(),
+                size: 5,
+                ..FileMetadata::default()
+            });
+            manifest.save(&Manifest::path_for(&base_path)).unwrap();
+
+            let result = Merger::new().merge_files(&base_path);
+            assert!(matches!(result, Err(FileSplitError::InvalidManifest(_))));
+            assert!(!temp_dir.path().join(name).exists());
+            assert!(!base_path.exists());
+        }
+    }
+
+    #[test]
+    fn test_list_parts() {
+        // 欠けている分割ファイルを含めた一覧の取得をテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("test.txt");
+        fs::write(temp_dir.path().join("test.txt.001"), "Hello").unwrap();
+        fs::write(temp_dir.path().join("test.txt.003"), "!").unwrap();
+
+        let merger = Merger::new();
+        assert_eq!(merger.load_manifest(&base_path).unwrap(), None);
+        let entries = merger.list_parts(&base_path).unwrap();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.index, entry.size))
+                .collect::<Vec<_>>(),
+            vec![(1, Some(5)), (2, None), (3, Some(1))]
+        );
+        assert_eq!(entries[1].path, temp_dir.path().join("test.txt.002"));
+
+        // マニフェストがあれば記録された分割ファイルを一覧にする
+        let mut manifest = Manifest::new("test.txt");
+        manifest.push_part("test.txt.001".to_string(), 5, 5, sha256_hex(b"Hello"));
+        manifest.save(&Manifest::path_for(&base_path)).unwrap();
+        assert_eq!(merger.load_manifest(&base_path).unwrap(), Some(manifest));
+        assert_eq!(merger.list_parts(&base_path).unwrap().len(), 1);
+
+        // 1つも見つからない場合
+        let result = merger.list_parts(&temp_dir.path().join("missing.txt"));
+        assert!(matches!(result, Err(FileSplitError::NoSplitFiles(_))));
+    }
+
+    #[test]
+    fn test_scan_parts() {
+        // 分割ファイルの検索をテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("test.txt");
+
+        for name in [
+            "test.txt.001",
+            "test.txt.004",
+            "test.txt.1000",
+            "test.txt.manifest.json",
+            "test.txt.bak",
+            "other.txt.002",
+        ] {
+            fs::write(temp_dir.path().join(name), "").unwrap();
+        }
+
+        let parts = NamingScheme::default().scan(&base_path).unwrap();
+        assert_eq!(parts.keys().copied().collect::<Vec<_>>(), vec![1, 4, 1000]);
+        assert_eq!(parts[&4], temp_dir.path().join("test.txt.004"));
+    }
+
     #[test]
     fn test_merge_large_index() {
         // 大きなインデックス番号のテスト
@@ -223,9 +1776,11 @@ mod tests {
         let mut file = fs::File::create(temp_dir.path().join("test.txt.999")).unwrap();
         file.write_all(b"File 999").unwrap();
 
-        // .001がないのでエラーになるはず
+        // .001から.998がないのでエラーになるはず
         let merger = Merger::new();
-        let result = merger.merge_files(&base_path);
-        assert!(result.is_err());
+        match merger.merge_files(&base_path).unwrap_err() {
+            FileSplitError::MissingParts(indices) => assert_eq!(indices.len(), 998),
+            _ => panic!("予期しないエラー型"),
+        }
     }
 }
@@ -0,0 +1,375 @@
+use crate::error::{FileSplitError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::{Component, Path};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 分割前のファイルの名前と属性
+///
+/// 分割時にマニフェストに記録し、結合時に結合結果へ復元する
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileMetadata {
+    /// 元ファイルの名前（ディレクトリを含まない）
+    pub name: String,
+    /// 元ファイルのサイズ（バイト）
+    pub size: u64,
+    /// パーミッション（Unixのモードビット。記録できない環境ではNone）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<u32>,
+    /// 最終更新日時
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<Timestamp>,
+    /// 拡張属性（名前と16進数で表した値）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub xattrs: BTreeMap<String, String>,
+}
+
+/// UNIXエポックからの経過時間で表した日時
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timestamp {
+    /// 秒
+    pub secs: u64,
+    /// 秒未満のナノ秒
+    pub nanos: u32,
+}
+
+impl FileMetadata {
+    /// ファイルの名前と属性を読み込む
+    ///
+    /// # 引数
+    /// * `path` - 分割するファイルのパス
+    /// * `xattrs` - 拡張属性も読み込むかどうか
+    ///
+    /// # 戻り値
+    /// 読み込んだ属性。拡張属性に対応していないビルドで拡張属性を指定した場合はエラー
+    pub fn read(path: &Path, xattrs: bool) -> Result<Self> {
+        let metadata = fs::metadata(path)?;
+
+        #[cfg(unix)]
+        let mode = {
+            use std::os::unix::fs::PermissionsExt;
+            Some(metadata.permissions().mode() & 0o7777)
+        };
+        #[cfg(not(unix))]
+        let mode = None;
+
+        // UNIXエポックより前の日時は記録しない
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| Timestamp {
+                secs: duration.as_secs(),
+                nanos: duration.subsec_nanos(),
+            });
+
+        Ok(Self {
+            name: path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            size: metadata.len(),
+            mode,
+            modified,
+            xattrs: if xattrs {
+                read_xattrs(path)?
+            } else {
+                BTreeMap::new()
+            },
+        })
+    }
+
+    /// 記録された名前を確認して返す
+    ///
+    /// # 戻り値
+    /// 元ファイルの名前。空の名前、絶対パス、ディレクトリを含む名前、
+    /// "." や ".." の場合はエラー（結合結果を意図しない場所に作成しないため）
+    pub fn checked_name(&self) -> Result<&str> {
+        let name = self.name.as_str();
+        if !is_plain_file_name(name) {
+            return Err(FileSplitError::InvalidManifest(format!(
+                "不正な元ファイル名です: {name}"
+            )));
+        }
+        Ok(name)
+    }
+
+    /// 記録された属性をファイルに復元する
+    ///
+    /// # 引数
+    /// * `path` - 結合結果のパス
+    /// * `xattrs` - 拡張属性も復元するかどうか
+    /// * `special_bits` - setuid・setgid・スティッキービットも復元するかどうか
+    ///
+    /// # 動作
+    /// 拡張属性、最終更新日時、パーミッションの順に設定する
+    /// （読み取り専用のパーミッションで日時を設定できなくならないように）。
+    /// tar と同じく、指定がなければパーミッションは 0o777 の範囲だけを復元する。
+    /// Unix以外ではモードビットの書き込み権限だけを読み取り専用属性として反映する
+    pub fn apply(&self, path: &Path, xattrs: bool, special_bits: bool) -> Result<()> {
+        if xattrs {
+            write_xattrs(path, &self.xattrs)?;
+        }
+
+        if let Some(modified) = self.modified {
+            let time = UNIX_EPOCH + Duration::new(modified.secs, modified.nanos);
+            set_modified(path, time)?;
+        }
+
+        if let Some(mode) = self.mode {
+            let mode = mode & if special_bits { 0o7777 } else { 0o777 };
+            let mut permissions = fs::metadata(path)?.permissions();
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                permissions.set_mode(mode);
+            }
+            #[cfg(not(unix))]
+            permissions.set_readonly(mode & 0o222 == 0);
+            fs::set_permissions(path, permissions)?;
+        }
+        Ok(())
+    }
+}
+
+/// ディレクトリを含まない1つのファイル名かどうか
+///
+/// 空の名前、絶対パス、ディレクトリを含む名前、"." や ".." は認めない。
+/// Windowsで作成されたパスの区切り文字も認めない
+pub(crate) fn is_plain_file_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    let single = matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(part)), None) if part == name
+    );
+    single && !name.contains(['\\', '\0'])
+}
+
+/// ファイルの最終更新日時を設定する
+fn set_modified(path: &Path, time: SystemTime) -> Result<()> {
+    let file = File::options().write(true).open(path)?;
+    file.set_modified(time)?;
+    Ok(())
+}
+
+/// ファイルの拡張属性を読み込む
+///
+/// 名前がUTF-8でない拡張属性は記録しない
+#[cfg(all(unix, feature = "xattr"))]
+fn read_xattrs(path: &Path) -> Result<BTreeMap<String, String>> {
+    let mut attributes = BTreeMap::new();
+    for name in xattr::list(path)? {
+        let Some(key) = name.to_str() else {
+            continue;
+        };
+        if let Some(value) = xattr::get(path, &name)? {
+            attributes.insert(key.to_string(), crate::manifest::to_hex(&value));
+        }
+    }
+    Ok(attributes)
+}
+
+/// ファイルの拡張属性を設定する
+#[cfg(all(unix, feature = "xattr"))]
+fn write_xattrs(path: &Path, attributes: &BTreeMap<String, String>) -> Result<()> {
+    for (name, value) in attributes {
+        let value = from_hex(value).ok_or_else(|| {
+            FileSplitError::InvalidManifest(format!("拡張属性の値が不正です: {name}"))
+        })?;
+        xattr::set(path, name, &value)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(all(unix, feature = "xattr")))]
+fn read_xattrs(_path: &Path) -> Result<BTreeMap<String, String>> {
+    Err(xattr_unsupported())
+}
+
+#[cfg(not(all(unix, feature = "xattr")))]
+fn write_xattrs(_path: &Path, attributes: &BTreeMap<String, String>) -> Result<()> {
+    if attributes.is_empty() {
+        Ok(())
+    } else {
+        Err(xattr_unsupported())
+    }
+}
+
+/// 拡張属性に対応していないビルドのエラー
+#[cfg(not(all(unix, feature = "xattr")))]
+fn xattr_unsupported() -> FileSplitError {
+    FileSplitError::UnsupportedInput(
+        "拡張属性を扱うには Unix 環境で xattr 機能を有効にしてビルドしてください".to_string(),
+    )
+}
+
+/// 16進数文字列をバイト列に変換する
+#[cfg(all(unix, feature = "xattr"))]
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_and_apply() {
+        // 属性の読み込みと復元をテスト
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("report.pdf");
+        fs::write(&source, "content").unwrap();
+        set_modified(&source, UNIX_EPOCH + Duration::new(1_600_000_000, 123)).unwrap();
+
+        let metadata = FileMetadata::read(&source, false).unwrap();
+        assert_eq!(metadata.name, "report.pdf");
+        assert_eq!(metadata.size, 7);
+        assert_eq!(
+            metadata.modified,
+            Some(Timestamp {
+                secs: 1_600_000_000,
+                nanos: 123
+            })
+        );
+
+        let target = temp_dir.path().join("restored");
+        fs::write(&target, "content").unwrap();
+        metadata.apply(&target, false, false).unwrap();
+        assert_eq!(
+            fs::metadata(&target).unwrap().modified().unwrap(),
+            fs::metadata(&source).unwrap().modified().unwrap()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_apply_mode() {
+        // パーミッションの復元をテスト（読み取り専用でも日時を設定できる）
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let target = temp_dir.path().join("script.sh");
+        fs::write(&target, "#!/bin/sh\n").unwrap();
+
+        let metadata = FileMetadata {
+            name: "script.sh".to_string(),
+            size: 10,
+            mode: Some(0o555),
+            modified: Some(Timestamp {
+                secs: 1_000_000_000,
+                nanos: 0,
+            }),
+            xattrs: BTreeMap::new(),
+        };
+        metadata.apply(&target, false, false).unwrap();
+
+        let restored = fs::metadata(&target).unwrap();
+        assert_eq!(restored.permissions().mode() & 0o7777, 0o555);
+        assert_eq!(
+            restored.modified().unwrap(),
+            UNIX_EPOCH + Duration::from_secs(1_000_000_000)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_apply_special_bits() {
+        // setuid・setgid・スティッキービットは指定した場合だけ復元することをテスト
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let target = temp_dir.path().join("tool");
+        fs::write(&target, "binary").unwrap();
+
+        let metadata = FileMetadata {
+            name: "tool".to_string(),
+            size: 6,
+            mode: Some(0o6755),
+            ..FileMetadata::default()
+        };
+        metadata.apply(&target, false, false).unwrap();
+        let mode = fs::metadata(&target).unwrap().permissions().mode();
+        assert_eq!(mode & 0o7777, 0o755);
+
+        metadata.apply(&target, false, true).unwrap();
+        let mode = fs::metadata(&target).unwrap().permissions().mode();
+        // 一般ユーザーでは setgid を設定できない環境もあるため setuid だけ確認する
+        assert_eq!(mode & 0o4777, 0o4755);
+    }
+
+    #[test]
+    fn test_checked_name() {
+        // 記録された名前の確認をテスト
+        let named = |name: &str| FileMetadata {
+            name: name.to_string(),
+            ..FileMetadata::default()
+        };
+        assert_eq!(named("data.bin").checked_name().unwrap(), "data.bin");
+        assert_eq!(named("..data").checked_name().unwrap(), "..data");
+        for name in [
+            "",
+            ".",
+            "..",
+            "../secret",
+            "dir/data.bin",
+            "/etc/passwd",
+            "..\\secret",
+            "C:\\data.bin",
+        ] {
+            assert!(
+                matches!(
+                    named(name).checked_name(),
+                    Err(FileSplitError::InvalidManifest(_))
+                ),
+                "{name}"
+            );
+        }
+    }
+
+    #[cfg(not(all(unix, feature = "xattr")))]
+    #[test]
+    fn test_xattrs_unsupported() {
+        // 拡張属性に対応していないビルドのエラーをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("data.bin");
+        fs::write(&path, "data").unwrap();
+
+        assert!(matches!(
+            FileMetadata::read(&path, true),
+            Err(FileSplitError::UnsupportedInput(_))
+        ));
+        // 記録されていなければ復元時はエラーにしない
+        FileMetadata::default().apply(&path, true, false).unwrap();
+    }
+
+    #[cfg(all(unix, feature = "xattr"))]
+    #[test]
+    fn test_xattrs() {
+        // 拡張属性の記録と復元をテスト（対応していないファイルシステムでは確認しない）
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("data.bin");
+        let target = temp_dir.path().join("restored.bin");
+        fs::write(&source, "data").unwrap();
+        fs::write(&target, "data").unwrap();
+        if xattr::set(&source, "user.filesplit", b"\x00value").is_err() {
+            return;
+        }
+
+        let metadata = FileMetadata::read(&source, true).unwrap();
+        assert_eq!(metadata.xattrs["user.filesplit"], "0076616c7565");
+        metadata.apply(&target, true, false).unwrap();
+        assert_eq!(
+            xattr::get(&target, "user.filesplit").unwrap().unwrap(),
+            b"\x00value"
+        );
+    }
+}
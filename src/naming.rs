@@ -0,0 +1,686 @@
+use crate::error::{FileSplitError, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 標準の分割ファイル名のテンプレート
+pub const DEFAULT_TEMPLATE: &str = "{name}.{index}";
+
+/// 番号の桁数を自動的に決める場合の最小の桁数
+pub const MIN_INDEX_WIDTH: usize = 3;
+
+/// アルファベットの接尾辞の既定の文字数（GNU split と同じ）
+pub const DEFAULT_ALPHA_LENGTH: usize = 2;
+
+/// 分割ファイルの名前の付け方
+///
+/// テンプレートでは以下のプレースホルダーが使用できる
+/// * `{name}` - 元ファイルの名前（例: "data.tar.gz"）
+/// * `{stem}` - 元ファイルの名前から最後の拡張子を除いたもの（例: "data.tar"）
+/// * `{ext}` - 元ファイルの最後の拡張子（"." を含む。例: ".gz"。拡張子がなければ空）
+/// * `{index}` - 分割ファイルの番号（分割数から桁数を決めて0埋めする。最小3桁）
+/// * `{index:4}` - 分割ファイルの番号（`:` の後の桁数に固定して0埋めする）
+/// * `{alpha}` / `{alpha:3}` - アルファベットの接尾辞（aa, ab, ... az, ba, ...）
+///
+/// `{index}` と `{alpha}` のどちらかをちょうど1つ含める必要がある。
+/// 分割と結合で同じ名前の付け方を使用することで、結合時に分割ファイルを探すことができる
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamingScheme {
+    /// 番号より前の部分のテンプレート
+    before: String,
+    /// 番号より後の部分のテンプレート
+    after: String,
+    /// 番号の表し方
+    suffix: Suffix,
+    /// 最初の分割ファイルの番号（10進数の番号の場合のみ）
+    start_index: usize,
+}
+
+/// 分割ファイルの番号の表し方
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Suffix {
+    /// 10進数（0埋めする桁数。fixed が false の場合は分割数に合わせて桁数を増やす）
+    Numeric { width: usize, fixed: bool },
+    /// アルファベット（文字数。widen が true の場合は GNU split と同様に足りなくなると文字数を増やす）
+    Alphabetic { length: usize, widen: bool },
+}
+
+impl Default for NamingScheme {
+    fn default() -> Self {
+        Self {
+            before: "{name}.".to_string(),
+            after: String::new(),
+            suffix: Suffix::Numeric {
+                width: MIN_INDEX_WIDTH,
+                fixed: false,
+            },
+            start_index: 1,
+        }
+    }
+}
+
+impl NamingScheme {
+    /// テンプレートから名前の付け方を作成する
+    ///
+    /// # 引数
+    /// * `template` - 分割ファイル名のテンプレート（例: "{stem}.part{index:04}{ext}"）
+    ///
+    /// # 戻り値
+    /// 名前の付け方。最初の番号は1になる
+    pub fn parse(template: &str) -> Result<Self> {
+        let invalid = |msg: &str| FileSplitError::InvalidNaming(format!("{msg}: {template}"));
+
+        // {index}・{alpha} または桁数付きのものを探す
+        let Some((start, kind)) = ["{index", "{alpha"]
+            .iter()
+            .filter_map(|kind| template.find(kind).map(|start| (start, *kind)))
+            .min()
+        else {
+            return Err(invalid("{index} が含まれていません"));
+        };
+        let Some(len) = template[start..].find('}') else {
+            return Err(invalid("閉じられていないプレースホルダーがあります"));
+        };
+        let spec = &template[start + kind.len()..start + len];
+        let digits = match spec.strip_prefix(':') {
+            None if spec.is_empty() => None,
+            Some(digits) if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) => {
+                Some(
+                    digits
+                        .parse::<usize>()
+                        .map_err(|_| invalid("桁数の指定が不正です"))?,
+                )
+            }
+            _ => return Err(invalid("番号の桁数の指定が不正です")),
+        };
+        let suffix = match (kind, digits) {
+            ("{index", None) => Suffix::Numeric {
+                width: MIN_INDEX_WIDTH,
+                fixed: false,
+            },
+            (_, Some(0)) => return Err(invalid("番号の桁数は1以上にしてください")),
+            ("{index", Some(width)) => Suffix::Numeric { width, fixed: true },
+            (_, None) => Suffix::Alphabetic {
+                length: DEFAULT_ALPHA_LENGTH,
+                widen: true,
+            },
+            (_, Some(length)) => Suffix::Alphabetic {
+                length,
+                widen: false,
+            },
+        };
+        let before = &template[..start];
+        let after = &template[start + len + 1..];
+
+        for part in [before, after] {
+            if part.contains("{index") || part.contains("{alpha") {
+                return Err(invalid("番号は1つだけ指定してください"));
+            }
+            if part.contains(['/', '\\']) {
+                return Err(invalid("ディレクトリの区切り文字は使用できません"));
+            }
+            let rest = Self::expand(part, "", "", "");
+            if rest.contains(['{', '}']) {
+                return Err(invalid("未対応のプレースホルダーがあります"));
+            }
+        }
+
+        Ok(Self {
+            before: before.to_string(),
+            after: after.to_string(),
+            suffix,
+            start_index: 1,
+        })
+    }
+
+    /// 元ファイル名と番号を区切り文字でつなぐ名前の付け方を作成する
+    ///
+    /// # 引数
+    /// * `separator` - 元ファイル名と番号の間に入れる文字列（例: "_" -> "file.txt_001"）
+    ///
+    /// # 戻り値
+    /// デフォルトと同じく、番号の桁数は分割数に合わせて3桁から自動的に増える
+    pub fn with_separator(separator: &str) -> Result<Self> {
+        Self::parse(&format!("{{name}}{separator}{{index}}"))
+    }
+
+    /// GNU split と同じアルファベットの接尾辞を付ける名前の付け方を作成する
+    ///
+    /// # 引数
+    /// * `separator` - 元ファイル名と接尾辞の間に入れる文字列（"" の場合は "xaa" のようになる）
+    /// * `length` - 接尾辞の文字数（Noneの場合は2文字から始めて足りなくなると自動的に増やす）
+    ///
+    /// # 戻り値
+    /// 例: ".", None -> "file.txt.aa", "file.txt.ab", ... "file.txt.yz", "file.txt.zaaa", ...
+    pub fn alphabetic(separator: &str, length: Option<usize>) -> Result<Self> {
+        match length {
+            Some(length) => Self::parse(&format!("{{name}}{separator}{{alpha:{length}}}")),
+            None => Self::parse(&format!("{{name}}{separator}{{alpha}}")),
+        }
+    }
+
+    /// 桁数を自動的に決める番号の最小の桁数を変更する
+    ///
+    /// 他のツールの "file.part1" のような0埋めしない番号を探す場合に1を指定する
+    pub(crate) fn min_width(mut self, min_width: usize) -> Self {
+        if let Suffix::Numeric {
+            width,
+            fixed: false,
+        } = &mut self.suffix
+        {
+            *width = min_width;
+        }
+        self
+    }
+
+    /// 分割ファイルの名前を決めるのに分割数が必要かどうか
+    ///
+    /// 桁数を自動的に決める番号と、桁数・文字数を固定した接尾辞では分割数が必要になる
+    pub fn needs_part_count(&self) -> bool {
+        !matches!(self.suffix, Suffix::Alphabetic { widen: true, .. })
+    }
+
+    /// 分割数に合わせた名前の付け方を返す
+    ///
+    /// # 引数
+    /// * `part_count` - 作成する分割ファイルの数
+    ///
+    /// # 戻り値
+    /// 桁数を自動的に決める番号の場合は、最後の番号が収まる桁数にした名前の付け方
+    /// （例: 1000個 -> "file.txt.0001" ... "file.txt.1000"）。
+    /// 桁数・文字数を固定した接尾辞に収まらない場合はエラー
+    pub fn fit(&self, part_count: usize) -> Result<Self> {
+        let mut fitted = self.clone();
+        if part_count == 0 {
+            return Ok(fitted);
+        }
+        match &mut fitted.suffix {
+            Suffix::Numeric {
+                width,
+                fixed: false,
+            } => {
+                let digits = (part_count - 1 + self.start_index).to_string().len();
+                *width = (*width).max(digits);
+            }
+            _ => {
+                // 最後の分割ファイルの名前を作成できるかを事前に確認する
+                self.format_suffix(part_count - 1)?;
+            }
+        }
+        Ok(fitted)
+    }
+
+    /// 最初の分割ファイルの番号を変更する
+    ///
+    /// アルファベットの接尾辞は常に "aa"（文字数分の "a"）から始まる
+    ///
+    /// # 引数
+    /// * `start_index` - 最初の分割ファイルの番号（例: 0 -> "file.txt.000", "file.txt.001", ...）
+    pub fn start_index(mut self, start_index: usize) -> Self {
+        self.start_index = start_index;
+        self
+    }
+
+    /// 分割ファイルの名前を生成する
+    ///
+    /// # 引数
+    /// * `base_name` - 元ファイルの名前
+    /// * `index` - 分割ファイルの番号（1から始まる。名前には最初の番号からの連番として付く）
+    ///
+    /// # 戻り値
+    /// 例: "file.txt", 1 -> "file.txt.001"。
+    /// 番号が0の場合や、文字数を固定したアルファベットの接尾辞が足りない場合はエラー
+    pub fn file_name(&self, base_name: &str, index: usize) -> Result<String> {
+        let ordinal = index.checked_sub(1).ok_or_else(|| {
+            FileSplitError::InvalidNaming(format!("分割ファイルの番号は1から始まります: {index}"))
+        })?;
+        let (stem, ext) = Self::split_name(base_name);
+        Ok(format!(
+            "{}{}{}",
+            Self::expand(&self.before, base_name, stem, ext),
+            self.format_suffix(ordinal)?,
+            Self::expand(&self.after, base_name, stem, ext),
+        ))
+    }
+
+    /// 分割ファイルのパスを生成する
+    ///
+    /// # 引数
+    /// * `base_path` - 元ファイルのパス（分割ファイルは同じディレクトリに置かれる）
+    /// * `index` - 分割ファイルの番号（1から始まる）
+    ///
+    /// # 戻り値
+    /// 例: "/tmp/file.txt", 1 -> "/tmp/file.txt.001"
+    pub fn path(&self, base_path: &Path, index: usize) -> Result<PathBuf> {
+        let base_name = base_path.file_name().unwrap_or_default().to_string_lossy();
+        let mut path = base_path.to_path_buf();
+        path.set_file_name(self.file_name(&base_name, index)?);
+        Ok(path)
+    }
+
+    /// ファイル名がこの名前の付け方による分割ファイルであれば番号を返す
+    ///
+    /// # 引数
+    /// * `base_name` - 元ファイルの名前
+    /// * `file_name` - 調べるファイルの名前
+    ///
+    /// # 戻り値
+    /// 分割ファイルの番号（1から始まる）。分割ファイルでなければNone
+    pub fn parse_index(&self, base_name: &str, file_name: &str) -> Option<usize> {
+        let (stem, ext) = Self::split_name(base_name);
+        let before = Self::expand(&self.before, base_name, stem, ext);
+        let after = Self::expand(&self.after, base_name, stem, ext);
+        let suffix = file_name.strip_prefix(&before)?.strip_suffix(&after)?;
+        self.parse_suffix(suffix).map(|ordinal| ordinal + 1)
+    }
+
+    /// 元ファイルと同じディレクトリにある分割ファイルをすべて探す
+    ///
+    /// # 引数
+    /// * `base_path` - 元ファイルのパス
+    ///
+    /// # 戻り値
+    /// 番号と分割ファイルパスの組（番号順）。桁数の異なる分割ファイルが混ざっていて
+    /// 同じ番号のものが複数ある場合は、どれを使うか決められないためエラー
+    pub fn scan(&self, base_path: &Path) -> Result<BTreeMap<usize, PathBuf>> {
+        let mut parts = BTreeMap::new();
+        for (index, path) in self.scan_all(base_path)? {
+            if let Some(other) = parts.insert(index, path) {
+                return Err(FileSplitError::AmbiguousParts(format!(
+                    "{} と {}",
+                    other.display(),
+                    parts[&index].display()
+                )));
+            }
+        }
+        Ok(parts)
+    }
+
+    /// 元ファイルと同じディレクトリにある分割ファイルを、番号が重複するものも含めてすべて探す
+    ///
+    /// # 引数
+    /// * `base_path` - 元ファイルのパス
+    ///
+    /// # 戻り値
+    /// 番号と分割ファイルパスの組（番号順）
+    pub fn scan_all(&self, base_path: &Path) -> Result<Vec<(usize, PathBuf)>> {
+        let base_name = base_path.file_name().unwrap_or_default().to_string_lossy();
+        let dir = match base_path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+
+        let mut parts = Vec::new();
+        if !dir.is_dir() {
+            return Ok(parts);
+        }
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            if let Some(index) = self.parse_index(&base_name, &file_name.to_string_lossy()) {
+                parts.push((index, entry.path()));
+            }
+        }
+        parts.sort();
+        Ok(parts)
+    }
+
+    /// 0から始まる通し番号を接尾辞にする
+    fn format_suffix(&self, ordinal: usize) -> Result<String> {
+        match self.suffix {
+            Suffix::Numeric { width, fixed } => {
+                let number = (ordinal + self.start_index).to_string();
+                if fixed && number.len() > width {
+                    return Err(FileSplitError::SuffixExhausted(format!(
+                        "{width}桁の番号では {number} 番目の分割ファイルの名前を作成できません"
+                    )));
+                }
+                Ok(format!("{number:0>width$}"))
+            }
+            Suffix::Alphabetic {
+                length,
+                widen: false,
+            } => {
+                let max = u32::try_from(length)
+                    .ok()
+                    .and_then(|n| 26usize.checked_pow(n));
+                match max {
+                    Some(max) if ordinal >= max => Err(FileSplitError::SuffixExhausted(format!(
+                        "{length}文字のアルファベットでは {max} 個までしか作成できません"
+                    ))),
+                    _ => Ok(Self::alpha(ordinal, length)),
+                }
+            }
+            Suffix::Alphabetic {
+                length,
+                widen: true,
+            } => {
+                // GNU split と同様に、先頭が "z" になる名前を使わずに文字数を増やしていく
+                // （aa ... yz, zaaa ... zyzz, zzaaaa ...）
+                let mut ordinal = ordinal;
+                let mut prefix = String::new();
+                let mut length = length;
+                loop {
+                    let capacity = 26usize
+                        .checked_pow(length as u32 - 1)
+                        .and_then(|n| n.checked_mul(25));
+                    match capacity {
+                        Some(capacity) if ordinal >= capacity => {
+                            ordinal -= capacity;
+                            prefix.push('z');
+                            length += 1;
+                        }
+                        _ => return Ok(prefix + &Self::alpha(ordinal, length)),
+                    }
+                }
+            }
+        }
+    }
+
+    /// 接尾辞を0から始まる通し番号に戻す
+    fn parse_suffix(&self, suffix: &str) -> Option<usize> {
+        match self.suffix {
+            Suffix::Numeric { width, fixed } => {
+                // 桁数を固定した場合はその桁数、自動的に決める場合は最小の桁数以上
+                let width_ok = if fixed {
+                    suffix.len() == width
+                } else {
+                    suffix.len() >= width
+                };
+                if !width_ok || !suffix.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                let number: usize = suffix.parse().ok()?;
+                number.checked_sub(self.start_index)
+            }
+            Suffix::Alphabetic {
+                length,
+                widen: false,
+            } => {
+                if suffix.len() != length {
+                    return None;
+                }
+                Self::parse_alpha(suffix)
+            }
+            Suffix::Alphabetic {
+                length,
+                widen: true,
+            } => {
+                // 先頭の "z" の数だけ文字数が増えている
+                let zs = suffix.bytes().take_while(|&b| b == b'z').count();
+                let body = &suffix[zs..];
+                if body.len() != length + zs || body.starts_with('z') {
+                    return None;
+                }
+                let mut ordinal = 0usize;
+                for level in 0..zs {
+                    let capacity = 26usize.checked_pow((length + level) as u32 - 1)?;
+                    ordinal = ordinal.checked_add(capacity.checked_mul(25)?)?;
+                }
+                ordinal.checked_add(Self::parse_alpha(body)?)
+            }
+        }
+    }
+
+    /// 通し番号を指定された文字数のアルファベットにする
+    ///
+    /// # 戻り値
+    /// 例: 0, 2 -> "aa"、27, 2 -> "bb"
+    fn alpha(mut ordinal: usize, length: usize) -> String {
+        let mut letters = vec![b'a'; length];
+        for letter in letters.iter_mut().rev() {
+            *letter = b'a' + (ordinal % 26) as u8;
+            ordinal /= 26;
+        }
+        String::from_utf8(letters).unwrap_or_default()
+    }
+
+    /// アルファベットを通し番号に戻す
+    fn parse_alpha(letters: &str) -> Option<usize> {
+        if letters.is_empty() || !letters.bytes().all(|b| b.is_ascii_lowercase()) {
+            return None;
+        }
+        letters.bytes().try_fold(0usize, |ordinal, b| {
+            ordinal.checked_mul(26)?.checked_add((b - b'a') as usize)
+        })
+    }
+
+    /// 元ファイルの名前を拡張子の前後に分ける
+    ///
+    /// # 戻り値
+    /// 例: "data.tar.gz" -> ("data.tar", ".gz")、".bashrc" -> (".bashrc", "")
+    fn split_name(base_name: &str) -> (&str, &str) {
+        match base_name.rfind('.') {
+            Some(pos) if pos > 0 => base_name.split_at(pos),
+            _ => (base_name, ""),
+        }
+    }
+
+    /// テンプレート内の元ファイル名のプレースホルダーを置き換える
+    fn expand(template: &str, name: &str, stem: &str, ext: &str) -> String {
+        template
+            .replace("{name}", name)
+            .replace("{stem}", stem)
+            .replace("{ext}", ext)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_default_naming() {
+        // 標準の名前の付け方が従来の "元のファイル名.001" と同じことをテスト
+        let naming = NamingScheme::default();
+        assert_eq!(naming, NamingScheme::parse(DEFAULT_TEMPLATE).unwrap());
+        assert_eq!(naming.file_name("file.txt", 1).unwrap(), "file.txt.001");
+        assert_eq!(naming.file_name("file.txt", 1000).unwrap(), "file.txt.1000");
+        assert_eq!(
+            naming.path(Path::new("/tmp/test.txt"), 10).unwrap(),
+            PathBuf::from("/tmp/test.txt.010")
+        );
+        assert_eq!(naming.parse_index("file.txt", "file.txt.001"), Some(1));
+        assert_eq!(naming.parse_index("file.txt", "file.txt.1000"), Some(1000));
+        assert_eq!(
+            naming.parse_index("file.txt", "file.txt.manifest.json"),
+            None
+        );
+        assert_eq!(naming.parse_index("file.txt", "file.txt."), None);
+        assert_eq!(naming.parse_index("file.txt", "other.txt.001"), None);
+    }
+
+    #[test]
+    fn test_template_naming() {
+        // 拡張子の前に番号を入れるテンプレートをテスト
+        let naming = NamingScheme::parse("{stem}.part{index:04}{ext}").unwrap();
+        assert_eq!(
+            naming.file_name("data.tar.gz", 2).unwrap(),
+            "data.tar.part0002.gz"
+        );
+        assert_eq!(naming.file_name("README", 1).unwrap(), "README.part0001");
+        assert_eq!(naming.file_name(".bashrc", 1).unwrap(), ".bashrc.part0001");
+        assert_eq!(
+            naming.parse_index("data.tar.gz", "data.tar.part0002.gz"),
+            Some(2)
+        );
+        assert_eq!(naming.parse_index("data.tar.gz", "data.tar.gz.001"), None);
+
+        // 桁数を自動的に決める番号は最小3桁で、分割数に合わせて桁数が増える
+        let naming = NamingScheme::parse("{name}-{index}").unwrap();
+        assert_eq!(naming.file_name("a.bin", 12).unwrap(), "a.bin-012");
+        assert_eq!(naming.parse_index("a.bin", "a.bin-012"), Some(12));
+        assert_eq!(naming.parse_index("a.bin", "a.bin-12"), None);
+        let fitted = naming.fit(1000).unwrap();
+        assert_eq!(fitted.file_name("a.bin", 1).unwrap(), "a.bin-0001");
+        assert_eq!(fitted.file_name("a.bin", 1000).unwrap(), "a.bin-1000");
+        assert_eq!(naming.parse_index("a.bin", "a.bin-0001"), Some(1));
+        assert_eq!(naming.fit(999).unwrap(), naming);
+    }
+
+    #[test]
+    fn test_fixed_width_overflow() {
+        // 桁数を固定した番号に収まらない分割数がエラーになることをテスト
+        let naming = NamingScheme::parse("{name}.{index:2}").unwrap();
+        assert_eq!(naming.file_name("f", 99).unwrap(), "f.99");
+        assert_eq!(naming.parse_index("f", "f.05"), Some(5));
+        assert_eq!(naming.parse_index("f", "f.005"), None);
+        assert!(naming.fit(99).is_ok());
+        assert!(matches!(
+            naming.fit(100),
+            Err(FileSplitError::SuffixExhausted(_))
+        ));
+        assert!(matches!(
+            naming.file_name("f", 100),
+            Err(FileSplitError::SuffixExhausted(_))
+        ));
+
+        // 0から始まる番号では99が100個目になる
+        let naming = naming.start_index(0);
+        assert!(naming.fit(100).is_ok());
+        assert!(naming.fit(101).is_err());
+    }
+
+    #[test]
+    fn test_scan_ambiguous_width() {
+        // 桁数の異なる同じ番号の分割ファイルが混ざっている場合をテスト
+        let temp_dir = TempDir::new().unwrap();
+        for name in ["data.bin.001", "data.bin.0001", "data.bin.0002"] {
+            fs::write(temp_dir.path().join(name), "x").unwrap();
+        }
+        let base_path = temp_dir.path().join("data.bin");
+
+        let naming = NamingScheme::default();
+        assert_eq!(naming.scan_all(&base_path).unwrap().len(), 3);
+        assert!(matches!(
+            naming.scan(&base_path),
+            Err(FileSplitError::AmbiguousParts(_))
+        ));
+    }
+
+    #[test]
+    fn test_start_index_and_separator() {
+        // 0から始まる番号と区切り文字の指定をテスト
+        let naming = NamingScheme::default().start_index(0);
+        assert_eq!(naming.file_name("file.txt", 1).unwrap(), "file.txt.000");
+        assert_eq!(naming.file_name("file.txt", 2).unwrap(), "file.txt.001");
+        assert_eq!(naming.parse_index("file.txt", "file.txt.000"), Some(1));
+
+        let naming = NamingScheme::with_separator("_").unwrap();
+        assert_eq!(naming.file_name("file.txt", 1).unwrap(), "file.txt_001");
+        assert_eq!(naming.parse_index("file.txt", "file.txt.001"), None);
+        // 区切り文字を変えても桁数は分割数に合わせて増える
+        let fitted = naming.fit(1001).unwrap();
+        assert_eq!(fitted.file_name("file.txt", 1001).unwrap(), "file.txt_1001");
+        assert_eq!(fitted.file_name("file.txt", 1).unwrap(), "file.txt_0001");
+
+        // 最初の番号より小さい番号は分割ファイルとみなさない
+        let naming = NamingScheme::default().start_index(5);
+        assert_eq!(naming.parse_index("file.txt", "file.txt.004"), None);
+        assert_eq!(naming.parse_index("file.txt", "file.txt.005"), Some(1));
+    }
+
+    #[test]
+    fn test_alphabetic_suffix() {
+        // GNU split と同じアルファベットの接尾辞と自動的な文字数の増加をテスト
+        let naming = NamingScheme::alphabetic("", None).unwrap();
+        let cases = [
+            (1, "xaa"),
+            (2, "xab"),
+            (27, "xba"),
+            (650, "xyz"),
+            (651, "xzaaa"),
+            (652, "xzaab"),
+            (650 + 25 * 26 * 26, "xzyzz"),
+            (651 + 25 * 26 * 26, "xzzaaaa"),
+        ];
+        for (index, name) in cases {
+            assert_eq!(naming.file_name("x", index).unwrap(), name);
+            assert_eq!(naming.parse_index("x", name), Some(index), "{name}");
+        }
+
+        // 自動的な文字数の増加で作られない名前は分割ファイルとみなさない
+        for name in ["xza", "xzaa", "xzzaaa", "xa", "xaaa", "xAA", "xa1"] {
+            assert_eq!(naming.parse_index("x", name), None, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_alphabetic_fixed_length() {
+        // 文字数を固定したアルファベットの接尾辞が足りなくなるとエラーになることをテスト
+        let naming = NamingScheme::parse("{name}.{alpha:3}").unwrap();
+        assert_eq!(naming.file_name("f", 1).unwrap(), "f.aaa");
+        assert_eq!(naming.file_name("f", 26 * 26 * 26).unwrap(), "f.zzz");
+        assert_eq!(naming.parse_index("f", "f.zzz"), Some(26 * 26 * 26));
+        assert_eq!(naming.parse_index("f", "f.zzzz"), None);
+        assert!(matches!(
+            naming.file_name("f", 26 * 26 * 26 + 1),
+            Err(FileSplitError::SuffixExhausted(_))
+        ));
+
+        let naming = NamingScheme::alphabetic(".", Some(1)).unwrap();
+        assert_eq!(naming.file_name("f", 26).unwrap(), "f.z");
+        assert!(naming.file_name("f", 27).is_err());
+    }
+
+    #[test]
+    fn test_invalid_template() {
+        // 不正なテンプレートがエラーになることをテスト
+        for template in [
+            "{name}",
+            "{name}.{index}.{index}",
+            "{name}.{index:x}",
+            "{name}.{index:0}",
+            "{name}.{index:}",
+            "{name}.{index",
+            "parts/{name}.{index}",
+            "{name}.{unknown}.{index}",
+            "{name}.{alpha}.{index}",
+            "{name}.{alpha:0}",
+        ] {
+            assert!(
+                matches!(
+                    NamingScheme::parse(template),
+                    Err(FileSplitError::InvalidNaming(_))
+                ),
+                "{template}"
+            );
+        }
+        assert!(NamingScheme::with_separator("/").is_err());
+    }
+
+    #[test]
+    fn test_file_name_zero_index() {
+        // 番号0はパニックせずにエラーになることをテスト
+        let naming = NamingScheme::default();
+        assert!(matches!(
+            naming.file_name("data.bin", 0),
+            Err(FileSplitError::InvalidNaming(_))
+        ));
+        assert!(matches!(
+            naming.path(Path::new("data.bin"), 0),
+            Err(FileSplitError::InvalidNaming(_))
+        ));
+        assert_eq!(naming.file_name("data.bin", 1).unwrap(), "data.bin.001");
+    }
+
+    #[test]
+    fn test_scan() {
+        // 名前の付け方に一致する分割ファイルだけを探すことをテスト
+        let temp_dir = TempDir::new().unwrap();
+        for name in [
+            "data.part01.bin",
+            "data.part03.bin",
+            "data.bin.001",
+            "data.bin",
+        ] {
+            fs::write(temp_dir.path().join(name), "x").unwrap();
+        }
+
+        let naming = NamingScheme::parse("{stem}.part{index:02}{ext}").unwrap();
+        let parts = naming.scan(&temp_dir.path().join("data.bin")).unwrap();
+        assert_eq!(parts.keys().copied().collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(parts[&3], temp_dir.path().join("data.part03.bin"));
+    }
+}
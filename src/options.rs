@@ -0,0 +1,217 @@
+use crate::checksum::ChecksumFormat;
+use crate::encoding::Encoding;
+use crate::naming::NamingScheme;
+use crate::template::PartTemplate;
+use crate::time_window::{TimeWindow, DEFAULT_TIMESTAMP_FORMAT, DEFAULT_TIMESTAMP_REGEX};
+use std::path::PathBuf;
+
+/// ファイル分割のオプション
+///
+/// `Splitter::builder().options(...)` に渡して使用する。
+/// 将来オプションが追加されても互換性を保つため、
+/// `SplitOptions::default()` から必要なフィールドだけを変更して作成する
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SplitOptions {
+    /// 分割サイズ（バイト単位）
+    pub chunk_size: u64,
+    /// 文字境界を考慮するエンコーディング（Noneの場合はバイト単位で分割）
+    pub encoding: Option<Encoding>,
+    /// 各分割ファイルに挿入するヘッダー・フッター
+    pub template: PartTemplate,
+    /// 分割結果のマニフェストを保存するかどうか
+    pub manifest: bool,
+    /// 以前の分割で作成された古い分割ファイルを削除するかどうか
+    pub remove_stale: bool,
+    /// 各分割ファイルの先頭にコンテナ形式のヘッダーを付けるかどうか
+    pub container: bool,
+    /// 作成するチェックサムファイルの形式
+    pub checksums: Vec<ChecksumFormat>,
+    /// チェックサムファイルに元ファイルも含めるかどうか
+    pub checksum_original: bool,
+    /// ファイルの代わりに各分割ファイルを渡すコマンド（Noneの場合はファイルに書き込む）
+    pub filter: Option<String>,
+    /// 分割ファイルを置くディレクトリ（Noneの場合は元ファイルと同じディレクトリ）
+    pub output_dir: Option<PathBuf>,
+    /// 分割ファイルの名前に元ファイル名の代わりに使用する名前
+    pub prefix: Option<PathBuf>,
+    /// 分割ファイルの名前の付け方
+    pub naming: NamingScheme,
+    /// 元ファイルの名前と属性をマニフェストに記録するかどうか
+    pub metadata: bool,
+    /// 元ファイルの拡張属性も記録するかどうか（xattr 機能が必要）
+    pub xattrs: bool,
+}
+
+impl Default for SplitOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: 0,
+            encoding: None,
+            template: PartTemplate::default(),
+            manifest: true,
+            remove_stale: false,
+            container: false,
+            checksums: Vec::new(),
+            checksum_original: false,
+            filter: None,
+            output_dir: None,
+            prefix: None,
+            naming: NamingScheme::default(),
+            metadata: true,
+            xattrs: false,
+        }
+    }
+}
+
+/// ファイル結合のオプション
+///
+/// `Merger::builder().options(...)` に渡して使用する
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MergeOptions {
+    /// 分割時に挿入されたヘッダー・フッター（結合時に取り除く）
+    pub template: PartTemplate,
+    /// 連番に欠けがあっても存在する分割ファイルだけを結合するかどうか
+    pub allow_partial: bool,
+    /// 分割ファイルにコンテナ形式のヘッダーが付いているかどうか
+    ///
+    /// マニフェストがある場合はマニフェストの記録に従う
+    pub container: bool,
+    /// 出力先のファイルが既に存在する場合に上書きするかどうか
+    pub force: bool,
+    /// 分割ファイルを探すディレクトリ（Noneの場合は元ファイルと同じディレクトリ）
+    pub output_dir: Option<PathBuf>,
+    /// 分割時に元ファイル名の代わりに使用した名前
+    pub prefix: Option<PathBuf>,
+    /// 分割ファイルの名前の付け方
+    pub naming: NamingScheme,
+    /// マニフェストに記録された元ファイルの名前と属性を復元するかどうか
+    pub metadata: bool,
+    /// 記録された拡張属性も復元するかどうか（xattr 機能が必要）
+    pub xattrs: bool,
+    /// 記録された setuid・setgid・スティッキービットも復元するかどうか
+    pub special_bits: bool,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        Self {
+            template: PartTemplate::default(),
+            allow_partial: false,
+            container: false,
+            force: false,
+            output_dir: None,
+            prefix: None,
+            naming: NamingScheme::default(),
+            metadata: true,
+            xattrs: false,
+            special_bits: false,
+        }
+    }
+}
+
+/// 分割ファイル検証のオプション
+///
+/// `Verifier::builder().options(...)` に渡して使用する
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct VerifyOptions {
+    /// 比較対象の元ファイル（Noneの場合はマニフェストと照合する）
+    pub original: Option<PathBuf>,
+    /// 分割時に挿入したヘッダー・フッター
+    pub template: PartTemplate,
+    /// 分割ファイルを探すディレクトリ（Noneの場合は元ファイルと同じディレクトリ）
+    pub output_dir: Option<PathBuf>,
+    /// 分割時に元ファイル名の代わりに使用した名前
+    pub prefix: Option<PathBuf>,
+    /// 分割ファイルの名前の付け方
+    pub naming: NamingScheme,
+}
+
+/// 時間枠分割のオプション
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TimeWindowOptions {
+    /// 時間枠の単位
+    pub window: TimeWindow,
+    /// タイムスタンプを検出する正規表現
+    pub timestamp_regex: String,
+    /// タイムスタンプの書式（strftime形式）
+    pub timestamp_format: String,
+}
+
+impl TimeWindowOptions {
+    /// デフォルトのタイムスタンプ形式で新しいTimeWindowOptionsを作成する
+    ///
+    /// # 引数
+    /// * `window` - 時間枠の単位
+    pub fn new(window: TimeWindow) -> Self {
+        Self {
+            window,
+            timestamp_regex: DEFAULT_TIMESTAMP_REGEX.to_string(),
+            timestamp_format: DEFAULT_TIMESTAMP_FORMAT.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_options_default() {
+        // SplitOptionsのデフォルト値をテスト
+        let options = SplitOptions::default();
+        assert_eq!(options.chunk_size, 0);
+        assert_eq!(options.encoding, None);
+        assert!(options.template.is_empty());
+        assert!(options.manifest);
+        assert!(!options.remove_stale);
+        assert!(!options.container);
+        assert!(options.checksums.is_empty());
+        assert!(!options.checksum_original);
+        assert_eq!(options.filter, None);
+        assert_eq!(options.output_dir, None);
+        assert_eq!(options.prefix, None);
+        assert_eq!(options.naming, NamingScheme::default());
+        assert!(options.metadata);
+        assert!(!options.xattrs);
+    }
+
+    #[test]
+    fn test_merge_options_default() {
+        // MergeOptionsのデフォルト値をテスト
+        let options = MergeOptions::default();
+        assert!(options.template.is_empty());
+        assert!(!options.allow_partial);
+        assert!(!options.container);
+        assert!(!options.force);
+        assert_eq!(options.output_dir, None);
+        assert_eq!(options.prefix, None);
+        assert_eq!(options.naming, NamingScheme::default());
+        assert!(options.metadata);
+        assert!(!options.xattrs);
+        assert!(!options.special_bits);
+    }
+
+    #[test]
+    fn test_verify_options_default() {
+        // VerifyOptionsのデフォルト値をテスト
+        let options = VerifyOptions::default();
+        assert_eq!(options.original, None);
+        assert!(options.template.is_empty());
+        assert_eq!(options.output_dir, None);
+        assert_eq!(options.prefix, None);
+        assert_eq!(options.naming, NamingScheme::default());
+    }
+
+    #[test]
+    fn test_time_window_options_new() {
+        // TimeWindowOptionsの作成をテスト
+        let options = TimeWindowOptions::new(TimeWindow::Day);
+        assert_eq!(options.window, TimeWindow::Day);
+        assert_eq!(options.timestamp_regex, DEFAULT_TIMESTAMP_REGEX);
+        assert_eq!(options.timestamp_format, DEFAULT_TIMESTAMP_FORMAT);
+    }
+}
@@ -1,20 +1,92 @@
-use crate::error::Result;
-use crate::io::{BufferedReader, SplitFileWriter};
-use std::path::Path;
+use crate::checksum::{self, ChecksumFormat, MultiHasher};
+use crate::container::PartHeader;
+use crate::encoding::Encoding;
+use crate::error::{FileSplitError, Result};
+use crate::io::{writer, BufferedReader, SplitFileWriter};
+use crate::manifest::Manifest;
+use crate::metadata::FileMetadata;
+use crate::naming::NamingScheme;
+use crate::options::SplitOptions;
+use crate::strategy::{Boundary, FixedSizeStrategy, SplitStrategy};
+use crate::template::{PartInfo, PartTemplate};
+use std::collections::HashSet;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// 読み込み用バッファのサイズ（バイト単位）
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// 分割処理中に発生するイベント
+enum PartEvent<'a> {
+    /// 現在の分割ファイルに含めるデータ
+    Data(&'a [u8]),
+    /// 現在の分割ファイルの終わり
+    End,
+}
 
 /// ファイル分割処理を行う構造体
 pub struct Splitter {
-    /// 分割サイズ（バイト単位）
-    chunk_size: usize,
+    /// 分割位置を決定する戦略
+    strategy: Box<dyn SplitStrategy>,
+    /// 各分割ファイルに挿入するヘッダー・フッター
+    template: PartTemplate,
+    /// マニフェストを保存するかどうか
+    write_manifest: bool,
+    /// 以前の分割で作成された古い分割ファイルを削除するかどうか
+    remove_stale: bool,
+    /// 直前の分割で見つかり、削除しなかった古い分割ファイル
+    stale_parts: Vec<PathBuf>,
+    /// 直前の分割で削除した古い分割ファイル
+    removed_parts: Vec<PathBuf>,
+    /// 各分割ファイルの先頭にコンテナ形式のヘッダーを付けるかどうか
+    container: bool,
+    /// 作成するチェックサムファイルの形式
+    checksums: Vec<ChecksumFormat>,
+    /// チェックサムファイルに元ファイルも含めるかどうか
+    checksum_original: bool,
+    /// ファイルの代わりに各分割ファイルを渡すコマンド
+    filter: Option<String>,
+    /// 分割ファイルを置くディレクトリ
+    output_dir: Option<PathBuf>,
+    /// 分割ファイルの名前に元ファイル名の代わりに使用する名前
+    prefix: Option<PathBuf>,
+    /// 分割ファイルの名前の付け方
+    naming: NamingScheme,
+    /// 元ファイルの名前と属性をマニフェストに記録するかどうか
+    record_metadata: bool,
+    /// 元ファイルの拡張属性も記録するかどうか
+    xattrs: bool,
 }
 
 impl Splitter {
-    /// 新しいSplitterを作成する
+    /// 固定サイズで分割する新しいSplitterを作成する
     ///
     /// # 引数
     /// * `chunk_size` - 分割サイズ（バイト単位）
-    pub fn new(chunk_size: usize) -> Self {
-        Self { chunk_size }
+    pub fn new(chunk_size: u64) -> Self {
+        Self {
+            strategy: Box::new(FixedSizeStrategy::new(chunk_size)),
+            template: PartTemplate::default(),
+            write_manifest: true,
+            remove_stale: false,
+            stale_parts: Vec::new(),
+            removed_parts: Vec::new(),
+            container: false,
+            checksums: Vec::new(),
+            checksum_original: false,
+            filter: None,
+            output_dir: None,
+            prefix: None,
+            naming: NamingScheme::default(),
+            record_metadata: true,
+            xattrs: false,
+        }
+    }
+
+    /// Splitterを組み立てるビルダーを作成する
+    pub fn builder() -> SplitterBuilder {
+        SplitterBuilder::default()
     }
 
     /// 指定されたファイルを分割する
@@ -23,38 +95,673 @@ impl Splitter {
     /// * `file_path` - 分割するファイルのパス
     ///
     /// # 動作
-    /// ファイルをchunk_sizeバイトごとに分割し、
-    /// 元のファイル名.001, .002, ...の形式で保存する
-    pub fn split_file(&self, file_path: &Path) -> Result<()> {
-        // ファイルリーダーとライターを初期化
+    /// 戦略が決定した位置でファイルを分割し、
+    /// 元のファイル名.001, .002, ...の形式で保存する。
+    /// テンプレートが指定されている場合は、各分割ファイルの前後に
+    /// 展開したヘッダー・フッターを挿入する（分割サイズには含めない）。
+    /// コンテナ形式の場合は、さらにその前にバイナリのヘッダーを付ける。
+    /// 分割後に各分割ファイルのサイズとハッシュを記録したマニフェストを
+    /// 元のファイル名.manifest.json として保存する。
+    /// マニフェストには元ファイルの名前・サイズ・パーミッション・最終更新日時
+    /// （指定された場合は拡張属性も）を記録する。
+    /// チェックサムファイルの形式が指定されている場合は、分割ファイルの
+    /// チェックサムを記録したファイルも保存する。
+    /// 以前の分割で作成された、今回より大きい番号の分割ファイルが残っていれば
+    /// `stale_parts` で確認できる（削除する設定の場合は、以前のマニフェストに
+    /// 記録された分割ファイルだけを削除し、`removed_stale_parts` で確認できる）
+    ///
+    /// # 戻り値
+    /// 分割結果を記録したマニフェスト
+    pub fn split_file(&mut self, file_path: &Path) -> Result<Manifest> {
+        let base_path = self.part_base_path(file_path);
+        self.split_file_to(file_path, &base_path)
+    }
+
+    /// 分割ファイルの名前の基準にするパスを返す
+    ///
+    /// # 引数
+    /// * `file_path` - 分割するファイルのパス
+    ///
+    /// # 戻り値
+    /// 出力先ディレクトリと名前の指定を反映したパス（分割ファイルはこのパス.001, .002, ...）
+    pub fn part_base_path(&self, file_path: &Path) -> PathBuf {
+        writer::part_base_path(
+            file_path,
+            self.output_dir.as_deref(),
+            self.prefix.as_deref(),
+        )
+    }
+
+    /// 指定されたファイルを別の名前で分割する
+    ///
+    /// # 引数
+    /// * `file_path` - 分割するファイルのパス
+    /// * `base_path` - 分割ファイルの名前の基準にするパス（base_path.001, .002, ...）
+    ///
+    /// # 動作
+    /// 分割の内容は `split_file` と同じ。/proc のファイルのように、
+    /// 入力と同じ場所に分割ファイルを作成できない場合に使用する
+    ///
+    /// # 戻り値
+    /// 分割結果を記録したマニフェスト
+    pub fn split_file_to(&mut self, file_path: &Path, base_path: &Path) -> Result<Manifest> {
+        // {total}の展開やコンテナ形式のヘッダーには事前に分割数を数える必要があり、
+        // フィルターコマンドには各分割ファイルのサイズを渡す。
+        // 分割ファイルの名前の桁数は、入力のサイズから分割数が決まらなければ数えて決める
+        let mut part_sizes = Vec::new();
+        let size = fs::metadata(file_path)?.len();
+        let mut part_count = self
+            .strategy
+            .part_count(size)
+            .and_then(|count| usize::try_from(count).ok());
+        if self.needs_total()
+            || self.filter.is_some()
+            || (part_count.is_none() && self.naming.needs_part_count())
+        {
+            let mut size = 0;
+            self.split_with(&mut BufferedReader::new(file_path)?, |event| {
+                match event {
+                    PartEvent::Data(data) => size += data.len() as u64,
+                    PartEvent::End => part_sizes.push(std::mem::take(&mut size)),
+                }
+                Ok(())
+            })?;
+            part_count = Some(part_sizes.len());
+        }
+
+        // 結合時に元の名前と属性を復元できるように記録する
+        let original = if self.write_manifest && self.record_metadata && self.filter.is_none() {
+            Some(FileMetadata::read(file_path, self.xattrs)?)
+        } else {
+            None
+        };
+
+        let original_name = file_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
         let mut reader = BufferedReader::new(file_path)?;
-        let mut writer = SplitFileWriter::new(file_path);
+        self.split_source(
+            &mut reader,
+            base_path,
+            &original_name,
+            part_sizes,
+            part_count,
+            original,
+        )
+    }
+
+    /// 任意の入力を分割する
+    ///
+    /// # 引数
+    /// * `reader` - 分割する入力（パイプや標準入力など）
+    /// * `base_path` - 分割ファイルの名前の基準にするパス
+    ///
+    /// # 動作
+    /// 入力を先頭から1回だけ読み込み、base_path.001, .002, ...の形式で保存する。
+    /// 1回の読み込みで返ってくるデータが少なくても、各分割ファイルは分割サイズまで埋める。
+    /// 入力を読み直せないため、分割数を事前に数える必要がある {total} の展開と
+    /// コンテナ形式は使用できない。分割数がわからないため、番号の桁数は
+    /// 最小の桁数から必要に応じて増える（桁数を固定した場合は収まらなければエラー）
+    ///
+    /// # 戻り値
+    /// 分割結果を記録したマニフェスト
+    pub fn split_reader<R: Read>(&mut self, reader: R, base_path: &Path) -> Result<Manifest> {
+        if self.needs_total() {
+            return Err(FileSplitError::UnsupportedInput(
+                "{total} の展開とコンテナ形式には分割数を事前に数えるためファイルを指定してください"
+                    .to_string(),
+            ));
+        }
+        // 入力に名前がないため、分割ファイルの名前の基準を元ファイル名として扱う
+        let original_name = base_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        self.split_source(
+            &mut BufferedReader::from_reader(reader),
+            base_path,
+            &original_name,
+            Vec::new(),
+            None,
+            None,
+        )
+    }
+
+    /// 分割数を事前に数える必要があるかどうか
+    fn needs_total(&self) -> bool {
+        self.template.needs_total() || self.container
+    }
+
+    /// 入力を分割して分割ファイル・マニフェスト・チェックサムファイルを保存する
+    ///
+    /// # 引数
+    /// * `reader` - 分割する入力
+    /// * `file_path` - 分割ファイルの名前の基準にするパス
+    /// * `original_name` - テンプレートの展開とマニフェストに使用する元ファイル名
+    /// * `part_sizes` - 事前に数えた各分割ファイルのデータのバイト数（数えていない場合は空）
+    /// * `part_count` - 分割数（わからない場合はNone）
+    /// * `original` - マニフェストに記録する元ファイルの名前と属性
+    fn split_source<R: Read>(
+        &mut self,
+        reader: &mut BufferedReader<R>,
+        file_path: &Path,
+        original_name: &str,
+        part_sizes: Vec<u64>,
+        part_count: Option<usize>,
+        original: Option<FileMetadata>,
+    ) -> Result<Manifest> {
+        let total = part_sizes.len();
+        // 分割数に合わせて番号の桁数を決める（収まらない場合は何も書き込まずにエラー）
+        let naming = match part_count {
+            Some(count) => self.naming.fit(count)?,
+            None => self.naming.clone(),
+        };
+        if let Some(dir) = &self.output_dir {
+            fs::create_dir_all(dir)?;
+        }
+        // 古い分割ファイルを判定するため、上書きする前に以前のマニフェストを読み込む
+        // （読み込めないマニフェストはこのツールが作成したものとして扱わない）
+        let manifest_path = Manifest::path_for(file_path);
+        let previous = if manifest_path.exists() {
+            Manifest::load(&manifest_path).ok()
+        } else {
+            None
+        };
+        let template = self.template.clone();
+        let mut manifest = Manifest::new(original_name);
+        manifest.container = self.container;
+        manifest.original = original;
+
+        let mut sidecar_entries = Vec::new();
+        let mut writer = SplitFileWriter::new(file_path)
+            .with_part_sizes(part_sizes)
+            .with_naming(naming);
+        if self.container {
+            writer = writer.with_container(&manifest.set_id, total);
+        }
+        if let Some(command) = &self.filter {
+            writer = writer.with_filter(command);
+        }
+        if !self.checksums.is_empty() {
+            writer = writer.with_checksums();
+        }
+
+        // 元ファイルのチェックサムは読み込みながら計算する
+        let mut original_hasher = self.checksum_original.then(MultiHasher::new);
+
+        // 分割ファイルごとにデータを溜めず、読み込んだ分だけ書き込む
+        let mut writing = false;
+        self.split_with(reader, |event| {
+            let info = PartInfo {
+                index: manifest.part_count + 1,
+                total,
+                offset: manifest.total_size,
+                original_name,
+            };
+            match event {
+                PartEvent::Data(data) => {
+                    if !writing {
+                        writer.begin_part(template.render_header(&info).as_bytes())?;
+                        writing = true;
+                    }
+                    if let Some(hasher) = &mut original_hasher {
+                        hasher.update(data);
+                    }
+                    writer.write_data(data)
+                }
+                PartEvent::End => {
+                    let part = writer.finish_part(template.render_footer(&info).as_bytes())?;
+                    writing = false;
+
+                    // マニフェストに分割ファイルの情報を記録する
+                    let name = part
+                        .path
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string();
+                    if let Some(checksums) = part.checksums {
+                        sidecar_entries.push((name.clone(), checksums));
+                    }
+                    manifest.push_part(name, part.length, part.size, part.sha256);
+                    Ok(())
+                }
+            }
+        })?;
+
+        // フィルターコマンドに渡した場合は分割ファイルが作成されないため、
+        // マニフェストの保存と古い分割ファイルの確認は行わない
+        if self.filter.is_some() {
+            self.stale_parts.clear();
+            self.removed_parts.clear();
+            return Ok(manifest);
+        }
+
+        if self.write_manifest {
+            manifest.sidecars = self
+                .checksums
+                .iter()
+                .map(|format| {
+                    let path = format.path_for(file_path);
+                    path.file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string()
+                })
+                .collect();
+            manifest.save(&Manifest::path_for(file_path))?;
+        }
+        if !self.checksums.is_empty() {
+            if let Some(hasher) = original_hasher {
+                sidecar_entries.push((original_name.to_string(), hasher));
+            }
+            checksum::write_sidecars_with(file_path, &sidecar_entries, &self.checksums)?;
+        }
+        self.handle_stale_parts(file_path, &manifest, previous.as_ref())?;
+        Ok(manifest)
+    }
+
+    /// 直前の分割で見つかり、削除しなかった古い分割ファイルを返す
+    pub fn stale_parts(&self) -> &[PathBuf] {
+        &self.stale_parts
+    }
+
+    /// 直前の分割で削除した古い分割ファイルを返す
+    pub fn removed_stale_parts(&self) -> &[PathBuf] {
+        &self.removed_parts
+    }
+
+    /// 以前の分割で作成された古い分割ファイルを探し、削除する設定なら削除する
+    ///
+    /// # 引数
+    /// * `file_path` - 分割ファイルの名前の基準にしたパス
+    /// * `manifest` - 今回の分割結果
+    /// * `previous` - 上書きする前のマニフェスト
+    ///
+    /// # 動作
+    /// 削除するのは、以前のマニフェストに記録されていてサイズ（コンテナ形式の場合は
+    /// 識別子も）が記録と一致するファイルだけ。名前が一致するだけのファイルは
+    /// このツールが作成したものとは限らないため、削除せずに `stale_parts` に残す。
+    /// チェックサムファイルは以前のマニフェストに記録されたものだけを対象にする
+    fn handle_stale_parts(
+        &mut self,
+        file_path: &Path,
+        manifest: &Manifest,
+        previous: Option<&Manifest>,
+    ) -> Result<()> {
+        // 今回作成していない分割ファイル（より大きい番号や桁数の異なるもの）は別の分割のもの
+        let names: HashSet<&str> = manifest.parts.iter().map(|p| p.name.as_str()).collect();
+        // 古いファイルと、このツールが作成したことを確認できたかどうか
+        let mut stale: Vec<(PathBuf, bool)> = self
+            .naming
+            .scan_all(file_path)?
+            .into_iter()
+            .map(|(_, path)| path)
+            .filter(|path| {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                !names.contains(name.as_ref())
+            })
+            .map(|path| {
+                let recorded = previous.is_some_and(|p| Self::is_recorded_part(&path, p));
+                (path, recorded)
+            })
+            .collect();
+
+        // マニフェストを保存しない場合、残っているマニフェストは以前の分割のもの
+        let manifest_path = Manifest::path_for(file_path);
+        if !self.write_manifest && manifest_path.exists() {
+            stale.push((manifest_path, previous.is_some()));
+        }
+
+        // 今回作成しない形式のチェックサムファイルは、以前のマニフェストに記録されていれば
+        // 以前の分割のもの（記録されていなければ利用者が用意したものかもしれないため触れない）
+        if let Some(previous) = previous {
+            stale.extend(
+                ChecksumFormat::ALL
+                    .iter()
+                    .filter(|format| !self.checksums.contains(format))
+                    .map(|format| format.path_for(file_path))
+                    .filter(|path| {
+                        let name = path.file_name().unwrap_or_default().to_string_lossy();
+                        path.exists() && previous.sidecars.iter().any(|s| *s == name)
+                    })
+                    .map(|path| (path, true)),
+            );
+        }
+
+        self.stale_parts.clear();
+        self.removed_parts.clear();
+        for (path, recorded) in stale {
+            if self.remove_stale && recorded {
+                fs::remove_file(&path)?;
+                self.removed_parts.push(path);
+            } else {
+                self.stale_parts.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    /// 以前のマニフェストに記録された分割ファイルかどうか
+    ///
+    /// 名前に加えてサイズ（コンテナ形式の場合はヘッダーの識別子も）が記録と一致するか確認する
+    fn is_recorded_part(path: &Path, previous: &Manifest) -> bool {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let Some(part) = previous.parts.iter().find(|part| part.name == name) else {
+            return false;
+        };
+        if fs::metadata(path).map(|m| m.len()).ok() != Some(part.size) {
+            return false;
+        }
+        if previous.container {
+            return matches!(
+                PartHeader::read_from(path),
+                Ok(Some(header)) if header.set_id == previous.set_id
+            );
+        }
+        true
+    }
+
+    /// ファイルを分割し、分割ファイルの内容と区切りをコールバックに渡す
+    ///
+    /// # 引数
+    /// * `reader` - 分割する入力
+    /// * `on_event` - データを読み込むごと、および分割ファイルの終わりごとに呼び出される関数
+    ///
+    /// # 動作
+    /// 一定の大きさのバッファで読み込むため、分割サイズに関係なくメモリ使用量は一定。
+    /// 分割ファイルの終わりは、空でない分割ファイルに対してだけ通知する
+    fn split_with<R, F>(&mut self, reader: &mut BufferedReader<R>, mut on_event: F) -> Result<()>
+    where
+        R: Read,
+        F: FnMut(PartEvent) -> Result<()>,
+    {
+        self.strategy.start()?;
 
         // 読み込み用バッファを確保
-        let mut buffer = vec![0u8; self.chunk_size];
+        let mut buffer = vec![0u8; BUFFER_SIZE];
+        // 現在の分割ファイルに含めたバイト数
+        let mut part_len = 0u64;
+        // 判定を持ち越したバイト数（バッファの先頭に移動済み）
+        let mut carried = 0;
 
         // ファイルの終端まで読み込みと書き込みを繰り返す
         loop {
-            // バッファサイズ分のデータを読み込む
-            let bytes_read = reader.read_chunk(&mut buffer)?;
+            // バッファの空き領域分のデータを読み込む
+            let bytes_read = reader.read_chunk(&mut buffer[carried..])?;
+            let filled = carried + bytes_read;
+            let eof = bytes_read == 0;
 
-            // ファイルの終端に達したら終了
-            if bytes_read == 0 {
+            // 読み込んだデータの分割位置を戦略に判定させる
+            let mut pos = 0;
+            while pos < filled {
+                let data = &buffer[pos..filled];
+                match self.strategy.next_boundary(data, part_len, eof) {
+                    Boundary::Cut(len) => {
+                        // 空の分割ファイルで区切ると先に進めない
+                        if len == 0 && part_len == 0 {
+                            return Err(FileSplitError::InvalidSize(
+                                "分割位置が先に進みません".to_string(),
+                            ));
+                        }
+                        if len > 0 {
+                            on_event(PartEvent::Data(&data[..len]))?;
+                        }
+                        on_event(PartEvent::End)?;
+                        part_len = 0;
+                        pos += len;
+                    }
+                    Boundary::Continue(len) => {
+                        if len > 0 {
+                            on_event(PartEvent::Data(&data[..len]))?;
+                            part_len += len as u64;
+                        }
+                        pos += len;
+                        break;
+                    }
+                }
+            }
+
+            // ファイルの終端では残りをすべて最後の分割ファイルに含める
+            if eof {
+                if pos < filled {
+                    on_event(PartEvent::Data(&buffer[pos..filled]))?;
+                    part_len += (filled - pos) as u64;
+                }
+                if part_len > 0 {
+                    on_event(PartEvent::End)?;
+                }
                 break;
             }
 
-            // 読み込んだデータを分割ファイルに書き込む
-            writer.write_next_file(&buffer[..bytes_read])?;
+            // 判定を持ち越したデータをバッファの先頭に移動する
+            buffer.copy_within(pos..filled, 0);
+            carried = filled - pos;
+            if carried == buffer.len() {
+                return Err(FileSplitError::InvalidSize(
+                    "分割位置の判定に必要なデータがバッファに収まりません".to_string(),
+                ));
+            }
         }
 
         Ok(())
     }
 }
 
+/// Splitterのビルダー
+///
+/// # 例
+/// ```no_run
+/// use filesplit_rs::{Encoding, Splitter};
+///
+/// let mut splitter = Splitter::builder()
+///     .chunk_size(1024 * 1024)
+///     .encoding(Encoding::Utf8)
+///     .header("== {original_name} {index}/{total} ==\n")
+///     .build()?;
+/// splitter.split_file("notes.txt".as_ref())?;
+/// # Ok::<(), filesplit_rs::FileSplitError>(())
+/// ```
+#[derive(Default)]
+pub struct SplitterBuilder {
+    /// 分割のオプション
+    options: SplitOptions,
+    /// 固定サイズの代わりに使用する戦略
+    strategy: Option<Box<dyn SplitStrategy>>,
+}
+
+impl SplitterBuilder {
+    /// オプションをまとめて指定する
+    pub fn options(mut self, options: SplitOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// 分割サイズ（バイト単位）を指定する
+    pub fn chunk_size(mut self, chunk_size: u64) -> Self {
+        self.options.chunk_size = chunk_size;
+        self
+    }
+
+    /// 文字の途中で分割しないようにエンコーディングを指定する
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.options.encoding = Some(encoding);
+        self
+    }
+
+    /// 各分割ファイルに挿入するヘッダー・フッターを指定する
+    pub fn template(mut self, template: PartTemplate) -> Self {
+        self.options.template = template;
+        self
+    }
+
+    /// 各分割ファイルの先頭に挿入するテンプレートを指定する
+    pub fn header(mut self, header: impl Into<String>) -> Self {
+        self.options.template.header = Some(header.into());
+        self
+    }
+
+    /// 各分割ファイルの末尾に挿入するテンプレートを指定する
+    pub fn footer(mut self, footer: impl Into<String>) -> Self {
+        self.options.template.footer = Some(footer.into());
+        self
+    }
+
+    /// マニフェストを保存するかどうかを指定する（デフォルトは保存する）
+    pub fn manifest(mut self, manifest: bool) -> Self {
+        self.options.manifest = manifest;
+        self
+    }
+
+    /// 以前の分割で作成された古い分割ファイルを削除するかどうかを指定する
+    /// （デフォルトは削除しない）
+    ///
+    /// 削除するのは以前のマニフェストに記録された分割ファイルだけで、
+    /// 名前が一致するだけのファイルは削除しない
+    pub fn remove_stale(mut self, remove_stale: bool) -> Self {
+        self.options.remove_stale = remove_stale;
+        self
+    }
+
+    /// 各分割ファイルの先頭にコンテナ形式のヘッダーを付けるかどうかを指定する
+    ///
+    /// ヘッダーには分割ファイル一式の識別子・番号・総数・元ファイル内の位置・
+    /// ハッシュが記録され、名前が変更された分割ファイルも結合できるようになる
+    pub fn container(mut self, container: bool) -> Self {
+        self.options.container = container;
+        self
+    }
+
+    /// 作成するチェックサムファイルの形式を追加する
+    ///
+    /// 分割ファイルのチェックサムを `sha256sum -c` などの標準的なツールで
+    /// 確認できる形式で、元ファイルと同じディレクトリに保存する
+    pub fn checksum(mut self, format: ChecksumFormat) -> Self {
+        if !self.options.checksums.contains(&format) {
+            self.options.checksums.push(format);
+        }
+        self
+    }
+
+    /// チェックサムファイルに元ファイルも含めるかどうかを指定する
+    pub fn checksum_original(mut self, checksum_original: bool) -> Self {
+        self.options.checksum_original = checksum_original;
+        self
+    }
+
+    /// 分割ファイルを作成する代わりに、各分割ファイルの内容を標準入力に渡すコマンドを指定する
+    ///
+    /// コマンドはシェルで実行され、環境変数 `FILE`（分割ファイルのパス）、
+    /// `FILESPLIT_INDEX`（番号）、`FILESPLIT_SIZE`（データのバイト数）を参照できる。
+    /// `split_reader` では分割数を事前に数えられないため `FILESPLIT_SIZE` は設定されない。
+    /// 分割ファイルが作成されないため、マニフェストは保存せず（`split_file` などの
+    /// 戻り値では確認できる）、古い分割ファイルも確認しない。
+    /// チェックサムファイルとは同時に使用できない。
+    /// コマンドが失敗した場合は分割を中断する
+    pub fn filter(mut self, command: impl Into<String>) -> Self {
+        self.options.filter = Some(command.into());
+        self
+    }
+
+    /// 分割ファイルを置くディレクトリを指定する（存在しない場合は作成する）
+    pub fn output_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.options.output_dir = Some(dir.into());
+        self
+    }
+
+    /// 分割ファイルの名前に元ファイル名の代わりに使用する名前を指定する
+    ///
+    /// 分割ファイルは「名前.001」「名前.002」... として保存される
+    pub fn prefix(mut self, prefix: impl Into<PathBuf>) -> Self {
+        self.options.prefix = Some(prefix.into());
+        self
+    }
+
+    /// 分割ファイルの名前の付け方を指定する
+    pub fn naming(mut self, naming: NamingScheme) -> Self {
+        self.options.naming = naming;
+        self
+    }
+
+    /// 元ファイルの名前と属性をマニフェストに記録するかどうかを指定する
+    /// （デフォルトは記録する）
+    pub fn metadata(mut self, metadata: bool) -> Self {
+        self.options.metadata = metadata;
+        self
+    }
+
+    /// 元ファイルの拡張属性も記録するかどうかを指定する
+    ///
+    /// xattr 機能を有効にしてビルドした Unix 環境でのみ使用できる
+    pub fn xattrs(mut self, xattrs: bool) -> Self {
+        self.options.xattrs = xattrs;
+        self
+    }
+
+    /// 固定サイズの代わりに独自の戦略で分割する
+    ///
+    /// 戦略を指定した場合、分割サイズとエンコーディングの指定は使用されない
+    pub fn strategy<S: SplitStrategy + 'static>(mut self, strategy: S) -> Self {
+        self.strategy = Some(Box::new(strategy));
+        self
+    }
+
+    /// Splitterを作成する
+    ///
+    /// # 戻り値
+    /// 作成したSplitter、または分割サイズの指定が不正な場合はエラー
+    pub fn build(self) -> Result<Splitter> {
+        // コマンドに渡したデータには後からヘッダーを書き込めない
+        if self.options.container && self.options.filter.is_some() {
+            return Err(FileSplitError::UnsupportedInput(
+                "フィルターコマンドとコンテナ形式は同時に使用できません".to_string(),
+            ));
+        }
+        // 作成されない分割ファイルのチェックサムファイルは確認に使えない
+        if !self.options.checksums.is_empty() && self.options.filter.is_some() {
+            return Err(FileSplitError::UnsupportedInput(
+                "フィルターコマンドとチェックサムファイルは同時に使用できません".to_string(),
+            ));
+        }
+        let strategy = match self.strategy {
+            Some(strategy) => strategy,
+            None => {
+                let mut strategy = FixedSizeStrategy::new(self.options.chunk_size);
+                if let Some(encoding) = self.options.encoding {
+                    strategy = strategy.with_encoding(encoding);
+                }
+                strategy.validate()?;
+                Box::new(strategy)
+            }
+        };
+        Ok(Splitter {
+            strategy,
+            template: self.options.template,
+            write_manifest: self.options.manifest,
+            remove_stale: self.options.remove_stale,
+            stale_parts: Vec::new(),
+            removed_parts: Vec::new(),
+            container: self.options.container,
+            checksums: self.options.checksums,
+            checksum_original: self.options.checksum_original,
+            filter: self.options.filter,
+            output_dir: self.options.output_dir,
+            prefix: self.options.prefix,
+            naming: self.options.naming,
+            record_metadata: self.options.metadata,
+            xattrs: self.options.xattrs,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
+    use crate::container::HEADER_LEN;
+    use crate::manifest::sha256_hex;
     use std::io::Write;
     use tempfile::{NamedTempFile, TempDir};
 
@@ -62,7 +769,7 @@ mod tests {
     fn test_splitter_creation() {
         // Splitterの作成をテスト
         let splitter = Splitter::new(1024);
-        assert_eq!(splitter.chunk_size, 1024);
+        assert!(splitter.template.is_empty());
     }
 
     #[test]
@@ -77,7 +784,7 @@ mod tests {
             .unwrap();
         temp_file.flush().unwrap();
 
-        let splitter = Splitter::new(10);
+        let mut splitter = Splitter::new(10);
         splitter.split_file(temp_file.path()).unwrap();
 
         // 分割ファイルが作成されたことを確認
@@ -112,7 +819,7 @@ mod tests {
         temp_file.write_all(b"0123456789abcdefghij12345").unwrap();
         temp_file.flush().unwrap();
 
-        let splitter = Splitter::new(10);
+        let mut splitter = Splitter::new(10);
         splitter.split_file(temp_file.path()).unwrap();
 
         // 分割ファイルが作成されたことを確認
@@ -143,7 +850,7 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let temp_file = NamedTempFile::new_in(&temp_dir).unwrap();
 
-        let splitter = Splitter::new(10);
+        let mut splitter = Splitter::new(10);
         splitter.split_file(temp_file.path()).unwrap();
 
         // 分割ファイルが作成されていないことを確認
@@ -163,7 +870,7 @@ mod tests {
         temp_file.write_all(b"small").unwrap();
         temp_file.flush().unwrap();
 
-        let splitter = Splitter::new(100);
+        let mut splitter = Splitter::new(100);
         splitter.split_file(temp_file.path()).unwrap();
 
         // 1つの分割ファイルだけが作成されることを確認
@@ -180,10 +887,1032 @@ mod tests {
         assert_eq!(fs::read(&file1).unwrap(), b"small");
     }
 
+    /// 分割ファイルの内容を順に読み込むヘルパー関数
+    fn read_parts(temp_file: &NamedTempFile) -> Vec<Vec<u8>> {
+        let base_name = temp_file.path().file_name().unwrap().to_string_lossy();
+        let dir = temp_file.path().parent().unwrap();
+        (1..)
+            .map(|i| dir.join(format!("{base_name}.{i:03}")))
+            .take_while(|path| path.exists())
+            .map(|path| fs::read(path).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_split_utf8_on_char_boundary() {
+        // UTF-8の文字の途中で分割しないことをテスト（各文字3バイト）
+        let temp_dir = TempDir::new().unwrap();
+        let mut temp_file = NamedTempFile::new_in(&temp_dir).unwrap();
+        temp_file.write_all("あいうえお".as_bytes()).unwrap();
+        temp_file.flush().unwrap();
+
+        let mut splitter = Splitter::builder()
+            .chunk_size(7)
+            .encoding(Encoding::Utf8)
+            .build()
+            .unwrap();
+        splitter.split_file(temp_file.path()).unwrap();
+
+        let parts = read_parts(&temp_file);
+        assert_eq!(
+            parts,
+            vec![
+                "あい".as_bytes().to_vec(),
+                "うえ".as_bytes().to_vec(),
+                "お".as_bytes().to_vec(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_shift_jis_on_char_boundary() {
+        // Shift_JISの文字の途中で分割しないことをテスト（"aあいう"）
+        let temp_dir = TempDir::new().unwrap();
+        let mut temp_file = NamedTempFile::new_in(&temp_dir).unwrap();
+        let data = [0x61, 0x82, 0xA0, 0x82, 0xA2, 0x82, 0xA4];
+        temp_file.write_all(&data).unwrap();
+        temp_file.flush().unwrap();
+
+        let mut splitter = Splitter::builder()
+            .chunk_size(4)
+            .encoding(Encoding::ShiftJis)
+            .build()
+            .unwrap();
+        splitter.split_file(temp_file.path()).unwrap();
+
+        let parts = read_parts(&temp_file);
+        assert_eq!(
+            parts,
+            vec![vec![0x61, 0x82, 0xA0], vec![0x82, 0xA2, 0x82, 0xA4]]
+        );
+    }
+
+    #[test]
+    fn test_split_utf16_with_bom() {
+        // UTF-16LEのBOMとサロゲートペアを分割しないことをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let mut temp_file = NamedTempFile::new_in(&temp_dir).unwrap();
+        // BOM + "あ" + U+1F600
+        let data = [0xFF, 0xFE, 0x42, 0x30, 0x3D, 0xD8, 0x00, 0xDE];
+        temp_file.write_all(&data).unwrap();
+        temp_file.flush().unwrap();
+
+        let mut splitter = Splitter::builder()
+            .chunk_size(5)
+            .encoding(Encoding::Utf16)
+            .build()
+            .unwrap();
+        splitter.split_file(temp_file.path()).unwrap();
+
+        let parts = read_parts(&temp_file);
+        assert_eq!(
+            parts,
+            vec![vec![0xFF, 0xFE, 0x42, 0x30], vec![0x3D, 0xD8, 0x00, 0xDE]]
+        );
+    }
+
+    #[test]
+    fn test_split_encoding_too_small_size() {
+        // 1文字が収まらない分割サイズのエラーテスト
+        let result = Splitter::builder()
+            .chunk_size(3)
+            .encoding(Encoding::Utf8)
+            .build();
+        match result {
+            Err(FileSplitError::InvalidSize(_)) => (),
+            _ => panic!("予期しないエラー型"),
+        }
+    }
+
+    #[test]
+    fn test_builder_requires_chunk_size() {
+        // 分割サイズも戦略も指定しない場合のエラーテスト
+        assert!(Splitter::builder().build().is_err());
+    }
+
+    #[test]
+    fn test_builder_with_options() {
+        // オプション構造体からの作成をテスト
+        let options = SplitOptions {
+            chunk_size: 10,
+            template: PartTemplate {
+                header: Some("[{index}]".to_string()),
+                footer: None,
+            },
+            ..Default::default()
+        };
+
+        let splitter = Splitter::builder().options(options).build().unwrap();
+        assert_eq!(splitter.template.header.as_deref(), Some("[{index}]"));
+    }
+
+    #[test]
+    fn test_split_with_template() {
+        // ヘッダー・フッターの挿入をテスト
+        let temp_dir = TempDir::new().unwrap();
+        let mut temp_file = NamedTempFile::new_in(&temp_dir).unwrap();
+        temp_file.write_all(b"0123456789abcde").unwrap();
+        temp_file.flush().unwrap();
+
+        let template = PartTemplate {
+            header: Some("[{index}/{total} @{offset}]".to_string()),
+            footer: Some("[end]".to_string()),
+        };
+        let mut splitter = Splitter::builder()
+            .chunk_size(10)
+            .template(template)
+            .build()
+            .unwrap();
+        splitter.split_file(temp_file.path()).unwrap();
+
+        let parts = read_parts(&temp_file);
+        assert_eq!(
+            parts,
+            vec![
+                b"[1/2 @0]0123456789[end]".to_vec(),
+                b"[2/2 @10]abcde[end]".to_vec()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_with_template_original_name() {
+        // {original_name}の展開をテスト
+        let temp_dir = TempDir::new().unwrap();
+        let mut temp_file = NamedTempFile::new_in(&temp_dir).unwrap();
+        temp_file.write_all(b"abc").unwrap();
+        temp_file.flush().unwrap();
+
+        let template = PartTemplate {
+            header: Some("{original_name}:".to_string()),
+            footer: None,
+        };
+        let mut splitter = Splitter::builder()
+            .chunk_size(10)
+            .template(template)
+            .build()
+            .unwrap();
+        splitter.split_file(temp_file.path()).unwrap();
+
+        let base_name = temp_file.path().file_name().unwrap().to_string_lossy();
+        let parts = read_parts(&temp_file);
+        assert_eq!(parts, vec![format!("{base_name}:abc").into_bytes()]);
+    }
+
+    #[test]
+    fn test_split_with_template_original_name_and_prefix() {
+        // 別の名前で分割しても{original_name}は入力のファイル名に展開され、結合できることをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("input.bin");
+        fs::write(&file_path, b"0123456789abcde").unwrap();
+
+        for manifest in [false, true] {
+            let mut splitter = Splitter::builder()
+                .chunk_size(10)
+                .header("[{original_name} {index}]")
+                .prefix(temp_dir.path().join("out"))
+                .manifest(manifest)
+                .build()
+                .unwrap();
+            splitter.split_file(&file_path).unwrap();
+            assert_eq!(
+                fs::read(temp_dir.path().join("out.001")).unwrap(),
+                b"[input.bin 1]0123456789"
+            );
+
+            // マニフェストがなければ結合時に指定した元ファイルの名前で展開する
+            let mut merged = Vec::new();
+            crate::Merger::builder()
+                .header("[{original_name} {index}]")
+                .prefix(temp_dir.path().join("out"))
+                .build()
+                .unwrap()
+                .merge_to_writer(&file_path, &mut merged)
+                .unwrap();
+            assert_eq!(merged, b"0123456789abcde");
+        }
+    }
+
+    /// 改行ごとに分割するテスト用の戦略
+    struct LineStrategy;
+
+    impl SplitStrategy for LineStrategy {
+        fn next_boundary(&mut self, data: &[u8], _part_len: u64, _eof: bool) -> Boundary {
+            match data.iter().position(|&b| b == b'\n') {
+                Some(pos) => Boundary::Cut(pos + 1),
+                None => Boundary::Continue(data.len()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_split_with_custom_strategy() {
+        // 独自の戦略で分割するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let mut temp_file = NamedTempFile::new_in(&temp_dir).unwrap();
+        temp_file.write_all(b"first\nsecond line\nlast").unwrap();
+        temp_file.flush().unwrap();
+
+        let mut splitter = Splitter::builder().strategy(LineStrategy).build().unwrap();
+        splitter.split_file(temp_file.path()).unwrap();
+
+        let parts = read_parts(&temp_file);
+        assert_eq!(
+            parts,
+            vec![
+                b"first\n".to_vec(),
+                b"second line\n".to_vec(),
+                b"last".to_vec()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_larger_than_buffer() {
+        // 読み込みバッファより大きな分割サイズのテスト
+        let temp_dir = TempDir::new().unwrap();
+        let mut temp_file = NamedTempFile::new_in(&temp_dir).unwrap();
+        let data: Vec<u8> = (0..BUFFER_SIZE * 3).map(|i| i as u8).collect();
+        temp_file.write_all(&data).unwrap();
+        temp_file.flush().unwrap();
+
+        let mut splitter = Splitter::new(BUFFER_SIZE as u64 * 2 + 1);
+        splitter.split_file(temp_file.path()).unwrap();
+
+        let parts = read_parts(&temp_file);
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0], &data[..BUFFER_SIZE * 2 + 1]);
+        assert_eq!(parts[1], &data[BUFFER_SIZE * 2 + 1..]);
+    }
+
+    #[test]
+    fn test_split_huge_chunk_size() {
+        // 分割サイズの分のメモリを確保しないことをテスト（8GiB）
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("data.bin");
+        let data: Vec<u8> = (0..BUFFER_SIZE * 2 + 3).map(|i| i as u8).collect();
+        fs::write(&file_path, &data).unwrap();
+
+        let mut splitter = Splitter::new(8 * 1024 * 1024 * 1024);
+        let manifest = splitter.split_file(&file_path).unwrap();
+
+        assert_eq!(manifest.part_count, 1);
+        assert_eq!(manifest.parts[0].sha256, sha256_hex(&data));
+        assert_eq!(
+            fs::read(temp_dir.path().join("data.bin.001")).unwrap(),
+            data
+        );
+    }
+
+    /// 1回の読み込みで返すバイト数が毎回変わる入力（パイプを模擬する）
+    struct TrickleReader {
+        data: Vec<u8>,
+        pos: usize,
+        calls: usize,
+    }
+
+    impl TrickleReader {
+        fn new(data: &[u8]) -> Self {
+            Self {
+                data: data.to_vec(),
+                pos: 0,
+                calls: 0,
+            }
+        }
+    }
+
+    impl Read for TrickleReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.calls += 1;
+            let len = buf
+                .len()
+                .min(self.data.len() - self.pos)
+                .min(self.calls % 7 + 1);
+            buf[..len].copy_from_slice(&self.data[self.pos..self.pos + len]);
+            self.pos += len;
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn test_split_reader_short_reads() {
+        // 読み込みが少しずつしか返ってこなくても分割サイズどおりに分割するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("stream.bin");
+        let data: Vec<u8> = (0..1000).map(|i| (i % 251) as u8).collect();
+
+        let mut splitter = Splitter::new(64);
+        let manifest = splitter
+            .split_reader(TrickleReader::new(&data), &base_path)
+            .unwrap();
+
+        assert_eq!(manifest.part_count, 16);
+        assert_eq!(manifest.total_size, 1000);
+        for (i, part) in manifest.parts.iter().enumerate() {
+            let expected = &data[i * 64..((i + 1) * 64).min(data.len())];
+            let content = fs::read(temp_dir.path().join(&part.name)).unwrap();
+            assert_eq!(content, expected);
+        }
+        assert_eq!(manifest.parts[15].length, 1000 - 64 * 15);
+    }
+
+    #[test]
+    fn test_split_reader_short_reads_with_encoding() {
+        // 読み込みが途中で返ってきても文字の途中で分割しないテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("stream.txt");
+        let text = "あいうえおかきくけこ".repeat(20);
+
+        let mut splitter = Splitter::builder()
+            .chunk_size(10)
+            .encoding(Encoding::Utf8)
+            .build()
+            .unwrap();
+        let manifest = splitter
+            .split_reader(TrickleReader::new(text.as_bytes()), &base_path)
+            .unwrap();
+
+        // 3バイト文字が3文字ずつ（9バイト）入り、最後に残りの2文字が入る
+        assert_eq!(manifest.part_count, 67);
+        assert!(manifest.parts[..66].iter().all(|part| part.length == 9));
+        assert_eq!(manifest.parts[66].length, 6);
+        let merged: Vec<u8> = manifest
+            .parts
+            .iter()
+            .flat_map(|part| fs::read(temp_dir.path().join(&part.name)).unwrap())
+            .collect();
+        assert_eq!(merged, text.as_bytes());
+    }
+
+    #[test]
+    fn test_split_file_to_other_name() {
+        // 入力とは別の名前で分割し、元ファイルのチェックサムも記録するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("input.bin");
+        let base_path = temp_dir.path().join("out").join("copy.bin");
+        fs::create_dir(temp_dir.path().join("out")).unwrap();
+        fs::write(&file_path, b"0123456789abcde").unwrap();
+
+        let mut splitter = Splitter::builder()
+            .chunk_size(10)
+            .checksum(ChecksumFormat::Sha256)
+            .checksum_original(true)
+            .build()
+            .unwrap();
+        let manifest = splitter.split_file_to(&file_path, &base_path).unwrap();
+
+        assert_eq!(manifest.original_name, "input.bin");
+        assert_eq!(
+            fs::read(temp_dir.path().join("out/copy.bin.002")).unwrap(),
+            b"abcde"
+        );
+        assert!(!temp_dir.path().join("input.bin.001").exists());
+
+        // 元ファイルは分割ファイルの名前の基準ではなく入力の名前で記録される
+        let sums = fs::read_to_string(temp_dir.path().join("out/copy.bin.SHA256SUMS")).unwrap();
+        assert!(sums.contains(&format!("{}  input.bin", sha256_hex(b"0123456789abcde"))));
+    }
+
+    #[test]
+    fn test_split_records_original_metadata() {
+        // 元ファイルの名前と属性がマニフェストに記録されることをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("report.pdf");
+        fs::write(&file_path, "0123456789").unwrap();
+
+        let mut splitter = Splitter::builder()
+            .chunk_size(4)
+            .prefix(temp_dir.path().join("x"))
+            .build()
+            .unwrap();
+        splitter.split_file(&file_path).unwrap();
+        let manifest = Manifest::load(&temp_dir.path().join("x.manifest.json")).unwrap();
+        let original = manifest.original.unwrap();
+        assert_eq!(original.name, "report.pdf");
+        assert_eq!(original.size, 10);
+        assert!(original.modified.is_some());
+        #[cfg(unix)]
+        assert!(original.mode.is_some());
+
+        // 記録しない指定とファイル以外の入力では記録しない
+        let mut splitter = Splitter::builder()
+            .chunk_size(4)
+            .metadata(false)
+            .build()
+            .unwrap();
+        assert_eq!(splitter.split_file(&file_path).unwrap().original, None);
+        let mut splitter = Splitter::new(4);
+        let manifest = splitter
+            .split_reader(&b"0123456789"[..], &temp_dir.path().join("stdin"))
+            .unwrap();
+        assert_eq!(manifest.original, None);
+    }
+
+    #[test]
+    fn test_split_to_output_dir_with_prefix() {
+        // 別のディレクトリに別の名前で分割し、同じ指定で結合できることをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("input.bin");
+        let output_dir = temp_dir.path().join("parts");
+        fs::write(&file_path, b"0123456789abcde").unwrap();
+
+        let mut splitter = Splitter::builder()
+            .chunk_size(10)
+            .output_dir(&output_dir)
+            .prefix("backup")
+            .build()
+            .unwrap();
+        let manifest = splitter.split_file(&file_path).unwrap();
+
+        // 出力先ディレクトリは自動的に作成される
+        assert_eq!(manifest.original_name, "input.bin");
+        assert_eq!(
+            fs::read(output_dir.join("backup.001")).unwrap(),
+            b"0123456789"
+        );
+        assert_eq!(fs::read(output_dir.join("backup.002")).unwrap(), b"abcde");
+        assert!(output_dir.join("backup.manifest.json").exists());
+        assert!(!temp_dir.path().join("input.bin.001").exists());
+
+        let merged_path = temp_dir.path().join("restored.bin");
+        let merger = crate::Merger::builder()
+            .output_dir(&output_dir)
+            .prefix("backup")
+            .build()
+            .unwrap();
+        merger.merge_files(&merged_path).unwrap();
+        assert_eq!(fs::read(&merged_path).unwrap(), b"0123456789abcde");
+    }
+
+    #[test]
+    fn test_split_with_naming_scheme() {
+        // 独自の名前の付け方で分割し、同じ名前の付け方で結合・古い分割ファイルの検出ができることをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("data.bin");
+        fs::write(&file_path, b"0123456789abcde").unwrap();
+        let naming = NamingScheme::parse("{stem}.part{index:02}{ext}")
+            .unwrap()
+            .start_index(0);
+
+        // 以前の分割で作成された3つ目の分割ファイル
+        fs::write(temp_dir.path().join("data.part02.bin"), "old").unwrap();
+
+        let mut splitter = Splitter::builder()
+            .chunk_size(10)
+            .naming(naming.clone())
+            .manifest(false)
+            .build()
+            .unwrap();
+        splitter.split_file(&file_path).unwrap();
+
+        assert_eq!(
+            fs::read(temp_dir.path().join("data.part00.bin")).unwrap(),
+            b"0123456789"
+        );
+        assert_eq!(
+            fs::read(temp_dir.path().join("data.part01.bin")).unwrap(),
+            b"abcde"
+        );
+        assert_eq!(
+            splitter.stale_parts(),
+            [temp_dir.path().join("data.part02.bin")]
+        );
+        assert!(!temp_dir.path().join("data.bin.001").exists());
+
+        // マニフェストがないため古い分割ファイルは警告されるだけで残る
+        fs::remove_file(temp_dir.path().join("data.part02.bin")).unwrap();
+
+        // マニフェストがなくても同じ名前の付け方で分割ファイルを探して結合できる
+        fs::remove_file(&file_path).unwrap();
+        crate::Merger::builder()
+            .naming(naming)
+            .build()
+            .unwrap()
+            .merge_files(&file_path)
+            .unwrap();
+        assert_eq!(fs::read(&file_path).unwrap(), b"0123456789abcde");
+    }
+
+    #[test]
+    fn test_split_with_alphabetic_suffix() {
+        // GNU split と同じ名前で分割して結合し、接尾辞が足りなければエラーになることをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("data.bin");
+        fs::write(&file_path, "0123456789".repeat(3)).unwrap();
+        let naming = NamingScheme::alphabetic("", None).unwrap();
+
+        let mut splitter = Splitter::builder()
+            .chunk_size(10)
+            .prefix(temp_dir.path().join("x"))
+            .naming(naming.clone())
+            .manifest(false)
+            .build()
+            .unwrap();
+        splitter.split_file(&file_path).unwrap();
+        for name in ["xaa", "xab", "xac"] {
+            assert_eq!(fs::read(temp_dir.path().join(name)).unwrap(), b"0123456789");
+        }
+
+        let merged_path = temp_dir.path().join("merged.bin");
+        crate::Merger::builder()
+            .prefix(temp_dir.path().join("x"))
+            .naming(naming)
+            .build()
+            .unwrap()
+            .merge_files_to(&merged_path, &merged_path)
+            .unwrap();
+        assert_eq!(
+            fs::read(&merged_path).unwrap(),
+            "0123456789".repeat(3).as_bytes()
+        );
+
+        // 1文字の接尾辞では27個目の分割ファイルを作成できないため、何も書き込まずにエラーになる
+        fs::write(&file_path, "x".repeat(27)).unwrap();
+        let mut splitter = Splitter::builder()
+            .chunk_size(1)
+            .naming(NamingScheme::alphabetic(".", Some(1)).unwrap())
+            .build()
+            .unwrap();
+        let result = splitter.split_file(&file_path);
+        assert!(matches!(result, Err(FileSplitError::SuffixExhausted(_))));
+        assert!(!temp_dir.path().join("data.bin.a").exists());
+    }
+
+    #[test]
+    fn test_split_widens_index_for_many_parts() {
+        // 1000個以上に分割する場合に番号の桁数を揃え、名前順に連結すると元に戻ることをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("data.bin");
+        let data: Vec<u8> = (0..1000u32).map(|i| (i % 251) as u8).collect();
+        fs::write(&file_path, &data).unwrap();
+
+        // 以前の分割で作成された3桁の分割ファイル
+        Splitter::new(600).split_file(&file_path).unwrap();
+
+        let mut splitter = Splitter::builder()
+            .chunk_size(1)
+            .remove_stale(true)
+            .build()
+            .unwrap();
+        let manifest = splitter.split_file(&file_path).unwrap();
+        assert_eq!(manifest.part_count, 1000);
+        assert_eq!(manifest.parts[0].name, "data.bin.0001");
+        assert_eq!(manifest.parts[999].name, "data.bin.1000");
+        assert_eq!(
+            splitter.removed_stale_parts(),
+            [
+                temp_dir.path().join("data.bin.001"),
+                temp_dir.path().join("data.bin.002")
+            ]
+        );
+
+        // シェルのグロブと同じ名前順に連結すると元のデータになる
+        let mut names: Vec<_> = fs::read_dir(temp_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .filter(|name| name.starts_with("data.bin.") && !name.ends_with(".json"))
+            .collect();
+        names.sort();
+        let joined: Vec<u8> = names
+            .iter()
+            .flat_map(|name| fs::read(temp_dir.path().join(name)).unwrap())
+            .collect();
+        assert_eq!(joined, data);
+
+        // マニフェストがなくても4桁の分割ファイルを探して結合できる
+        fs::remove_file(Manifest::path_for(&file_path)).unwrap();
+        fs::remove_file(&file_path).unwrap();
+        crate::Merger::new().merge_files(&file_path).unwrap();
+        assert_eq!(fs::read(&file_path).unwrap(), data);
+    }
+
+    #[test]
+    fn test_split_fixed_width_overflow() {
+        // 桁数を固定した番号に収まらない場合は何も書き込まずにエラーになることをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("data.bin");
+        fs::write(&file_path, "x".repeat(100)).unwrap();
+
+        let mut splitter = Splitter::builder()
+            .chunk_size(1)
+            .naming(NamingScheme::parse("{name}.{index:2}").unwrap())
+            .build()
+            .unwrap();
+        let result = splitter.split_file(&file_path);
+        assert!(matches!(result, Err(FileSplitError::SuffixExhausted(_))));
+        assert!(!temp_dir.path().join("data.bin.01").exists());
+
+        // 文字境界を考慮する場合も事前に分割数を数えて確認する
+        let mut splitter = Splitter::builder()
+            .chunk_size(4)
+            .encoding(Encoding::Utf8)
+            .naming(NamingScheme::parse("{name}.{index:1}").unwrap())
+            .build()
+            .unwrap();
+        let result = splitter.split_file(&file_path);
+        assert!(matches!(result, Err(FileSplitError::SuffixExhausted(_))));
+        assert!(!temp_dir.path().join("data.bin.1").exists());
+    }
+
+    #[test]
+    fn test_split_reader_checksum_original() {
+        // 読み直せない入力でも元ファイルのチェックサムを記録できることをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("stream.bin");
+
+        let mut splitter = Splitter::builder()
+            .chunk_size(4)
+            .checksum(ChecksumFormat::Md5)
+            .checksum_original(true)
+            .build()
+            .unwrap();
+        splitter
+            .split_reader(TrickleReader::new(b"0123456789"), &base_path)
+            .unwrap();
+
+        let merged = temp_dir.path().join("merged.bin");
+        crate::Merger::new()
+            .merge_files_to(&base_path, &merged)
+            .unwrap();
+        assert_eq!(fs::read(&merged).unwrap(), b"0123456789");
+        let sums = fs::read_to_string(temp_dir.path().join("stream.bin.md5")).unwrap();
+        assert_eq!(sums.lines().count(), 4);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_split_with_filter() {
+        // 各分割ファイルをコマンドに渡すテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("data.bin");
+        fs::write(&file_path, b"0123456789abcde").unwrap();
+
+        // 以前の分割で作成された分割ファイル
+        fs::write(temp_dir.path().join("data.bin.003"), "old").unwrap();
+
+        let mut splitter = Splitter::builder()
+            .chunk_size(10)
+            .header("[{index}]")
+            .remove_stale(true)
+            .filter(r#"{ cat; echo " $FILESPLIT_INDEX $FILESPLIT_SIZE"; } > "$FILE.out""#)
+            .build()
+            .unwrap();
+        let manifest = splitter.split_file(&file_path).unwrap();
+
+        // 分割ファイルもマニフェストも作成されず、コマンドが受け取った内容を戻り値で確認できる
+        assert!(!temp_dir.path().join("data.bin.001").exists());
+        assert!(!temp_dir.path().join("data.bin.manifest.json").exists());
+        assert!(temp_dir.path().join("data.bin.003").exists());
+        assert!(splitter.stale_parts().is_empty());
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("data.bin.001.out")).unwrap(),
+            "[1]0123456789 1 10\n"
+        );
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("data.bin.002.out")).unwrap(),
+            "[2]abcde 2 5\n"
+        );
+        assert_eq!(manifest.parts[1].size, 8);
+        assert_eq!(manifest.parts[1].sha256, sha256_hex(b"[2]abcde"));
+
+        // 作成されない分割ファイルのチェックサムファイルは作成できない
+        let result = Splitter::builder()
+            .chunk_size(10)
+            .checksum(ChecksumFormat::Sha256)
+            .filter("cat > /dev/null")
+            .build();
+        assert!(matches!(result, Err(FileSplitError::UnsupportedInput(_))));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_split_with_failing_filter() {
+        // コマンドが失敗した場合に分割を中断するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("data.bin");
+        fs::write(&file_path, vec![b'x'; BUFFER_SIZE * 4]).unwrap();
+
+        // 終了コードで失敗
+        let mut splitter = Splitter::builder()
+            .chunk_size(10)
+            .filter(r#"cat > /dev/null; [ "$FILESPLIT_INDEX" != 2 ]"#)
+            .build()
+            .unwrap();
+        match splitter.split_file(&file_path).unwrap_err() {
+            // 26215個に分割するため番号は5桁になる
+            FileSplitError::FilterFailed(msg) => assert!(msg.contains("data.bin.00002")),
+            e => panic!("予期しないエラー型: {e}"),
+        }
+
+        // 入力を読まずに終了
+        let mut splitter = Splitter::builder()
+            .chunk_size(BUFFER_SIZE as u64 * 4)
+            .filter("exit 0")
+            .build()
+            .unwrap();
+        assert!(matches!(
+            splitter.split_file(&file_path),
+            Err(FileSplitError::FilterFailed(_))
+        ));
+
+        // 見つからないコマンド
+        let mut splitter = Splitter::builder()
+            .chunk_size(10)
+            .filter("/nonexistent/command 2> /dev/null")
+            .build()
+            .unwrap();
+        assert!(matches!(
+            splitter.split_file(&file_path),
+            Err(FileSplitError::FilterFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_filter_rejects_container() {
+        // コマンドに渡す場合はコンテナ形式を使用できない
+        let result = Splitter::builder()
+            .chunk_size(10)
+            .container(true)
+            .filter("cat")
+            .build();
+        assert!(matches!(result, Err(FileSplitError::UnsupportedInput(_))));
+    }
+
+    #[test]
+    fn test_split_reader_rejects_total() {
+        // 読み直せない入力では分割数を数える指定をエラーにするテスト
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("stream.bin");
+
+        let mut splitter = Splitter::builder()
+            .chunk_size(10)
+            .header("{index}/{total}")
+            .build()
+            .unwrap();
+        let result = splitter.split_reader(TrickleReader::new(b"data"), &base_path);
+        assert!(matches!(result, Err(FileSplitError::UnsupportedInput(_))));
+
+        let mut splitter = Splitter::builder()
+            .chunk_size(10)
+            .container(true)
+            .build()
+            .unwrap();
+        let result = splitter.split_reader(TrickleReader::new(b"data"), &base_path);
+        assert!(matches!(result, Err(FileSplitError::UnsupportedInput(_))));
+        assert!(!temp_dir.path().join("stream.bin.001").exists());
+    }
+
+    #[test]
+    fn test_split_zero_size() {
+        // 分割サイズ0のエラーテスト
+        let temp_dir = TempDir::new().unwrap();
+        let temp_file = NamedTempFile::new_in(&temp_dir).unwrap();
+
+        let mut splitter = Splitter::new(0);
+        assert!(splitter.split_file(temp_file.path()).is_err());
+    }
+
+    #[test]
+    fn test_split_writes_manifest() {
+        // マニフェストの保存内容をテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("data.bin");
+        fs::write(&file_path, b"0123456789abcde").unwrap();
+
+        let mut splitter = Splitter::new(10);
+        let manifest = splitter.split_file(&file_path).unwrap();
+
+        let loaded = Manifest::load(&temp_dir.path().join("data.bin.manifest.json")).unwrap();
+        assert_eq!(loaded, manifest);
+        assert_eq!(manifest.original_name, "data.bin");
+        assert_eq!(manifest.total_size, 15);
+        assert_eq!(manifest.part_count, 2);
+        assert_eq!(manifest.parts[1].name, "data.bin.002");
+        assert_eq!(manifest.parts[1].offset, 10);
+        assert_eq!(manifest.parts[1].length, 5);
+        assert_eq!(manifest.parts[1].sha256, sha256_hex(b"abcde"));
+    }
+
+    #[test]
+    fn test_split_manifest_with_template() {
+        // ヘッダー・フッター付きの場合はファイル全体のサイズとハッシュを記録する
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("data.txt");
+        fs::write(&file_path, b"abc").unwrap();
+
+        let mut splitter = Splitter::builder()
+            .chunk_size(10)
+            .header("[{index}]")
+            .build()
+            .unwrap();
+        let manifest = splitter.split_file(&file_path).unwrap();
+
+        assert_eq!(manifest.parts[0].length, 3);
+        assert_eq!(manifest.parts[0].size, 6);
+        assert_eq!(manifest.parts[0].sha256, sha256_hex(b"[1]abc"));
+    }
+
+    #[test]
+    fn test_split_without_manifest() {
+        // マニフェストを保存しない設定のテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("data.bin");
+        fs::write(&file_path, b"abc").unwrap();
+
+        let mut splitter = Splitter::builder()
+            .chunk_size(10)
+            .manifest(false)
+            .build()
+            .unwrap();
+        splitter.split_file(&file_path).unwrap();
+
+        assert!(temp_dir.path().join("data.bin.001").exists());
+        assert!(!temp_dir.path().join("data.bin.manifest.json").exists());
+    }
+
+    #[test]
+    fn test_split_removes_stale_parts() {
+        // 以前のマニフェストに記録された古い分割ファイルだけを削除するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let file_path = dir.join("data.bin");
+        fs::write(&file_path, "0123456789".repeat(6)).unwrap();
+        Splitter::new(10).split_file(&file_path).unwrap();
+        let first = Manifest::load(&Manifest::path_for(&file_path)).unwrap();
+
+        // 名前が一致するだけのファイルと、記録とサイズが異なるファイルは削除しない
+        fs::write(dir.join("data.bin.2024"), "unrelated").unwrap();
+        fs::write(dir.join("data.bin.006"), "replaced").unwrap();
+
+        // 小さくしたファイルを再分割すると.004から.006が残るはず
+        fs::write(&file_path, "0123456789".repeat(3)).unwrap();
+        let mut splitter = Splitter::builder()
+            .chunk_size(10)
+            .remove_stale(true)
+            .build()
+            .unwrap();
+        let second = splitter.split_file(&file_path).unwrap();
+
+        assert_ne!(first.set_id, second.set_id);
+        assert!(dir.join("data.bin.003").exists());
+        assert_eq!(
+            splitter.removed_stale_parts(),
+            [dir.join("data.bin.004"), dir.join("data.bin.005")]
+        );
+        assert!(!dir.join("data.bin.004").exists());
+        assert!(!dir.join("data.bin.005").exists());
+        assert_eq!(
+            splitter.stale_parts(),
+            [dir.join("data.bin.006"), dir.join("data.bin.2024")]
+        );
+        assert!(dir.join("data.bin.006").exists());
+        assert!(dir.join("data.bin.2024").exists());
+    }
+
+    #[test]
+    fn test_split_removes_stale_container_parts_of_same_set() {
+        // コンテナ形式では以前の分割と識別子が一致する分割ファイルだけを削除するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let file_path = dir.join("data.bin");
+        fs::write(&file_path, "0123456789".repeat(3)).unwrap();
+        let mut splitter = Splitter::builder()
+            .chunk_size(10)
+            .container(true)
+            .remove_stale(true)
+            .build()
+            .unwrap();
+        splitter.split_file(&file_path).unwrap();
+
+        // 別の分割ファイル一式の同じ大きさの分割ファイルに置き換える
+        let other = dir.join("other").join("data.bin");
+        fs::create_dir(dir.join("other")).unwrap();
+        fs::write(&other, "0123456789".repeat(3)).unwrap();
+        splitter.split_file(&other).unwrap();
+        fs::copy(
+            dir.join("other").join("data.bin.003"),
+            dir.join("data.bin.003"),
+        )
+        .unwrap();
+
+        fs::write(&file_path, "0123456789").unwrap();
+        splitter.split_file(&file_path).unwrap();
+        assert_eq!(splitter.removed_stale_parts(), [dir.join("data.bin.002")]);
+        assert_eq!(splitter.stale_parts(), [dir.join("data.bin.003")]);
+    }
+
+    #[test]
+    fn test_split_keeps_stale_parts() {
+        // 古い分割ファイルを削除しないデフォルトの設定のテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("data.bin");
+        fs::write(&file_path, "0123456789".repeat(2)).unwrap();
+        Splitter::new(10).split_file(&file_path).unwrap();
+
+        // マニフェストなしで再分割すると古いマニフェストも古い分割ファイルになる
+        fs::write(&file_path, "0123456789").unwrap();
+        let mut splitter = Splitter::builder()
+            .chunk_size(10)
+            .manifest(false)
+            .build()
+            .unwrap();
+        splitter.split_file(&file_path).unwrap();
+
+        // デフォルトでは削除せずに警告のために返すだけ
+        assert!(temp_dir.path().join("data.bin.002").exists());
+        assert!(splitter.removed_stale_parts().is_empty());
+        assert_eq!(
+            splitter.stale_parts(),
+            [
+                temp_dir.path().join("data.bin.002"),
+                temp_dir.path().join("data.bin.manifest.json")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_with_container() {
+        // コンテナ形式のヘッダー付きの分割をテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("data.bin");
+        fs::write(&file_path, b"0123456789abcde").unwrap();
+
+        let mut splitter = Splitter::builder()
+            .chunk_size(10)
+            .container(true)
+            .build()
+            .unwrap();
+        let manifest = splitter.split_file(&file_path).unwrap();
+        assert!(manifest.container);
+
+        let content = fs::read(temp_dir.path().join("data.bin.002")).unwrap();
+        let header = crate::PartHeader::parse(&content).unwrap().unwrap();
+        assert_eq!(header.set_id, manifest.set_id);
+        assert_eq!((header.index, header.total), (2, 2));
+        assert_eq!((header.offset, header.length), (10, 5));
+        assert_eq!(&content[HEADER_LEN..], b"abcde");
+        assert_eq!(manifest.parts[1].size, (HEADER_LEN + 5) as u64);
+        assert_eq!(manifest.parts[1].sha256, sha256_hex(&content));
+    }
+
+    #[test]
+    fn test_split_with_checksums() {
+        // チェックサムファイルの作成をテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("data.bin");
+        fs::write(&file_path, b"0123456789abcde").unwrap();
+
+        let mut splitter = Splitter::builder()
+            .chunk_size(10)
+            .checksum(ChecksumFormat::Sfv)
+            .checksum(ChecksumFormat::Sha256)
+            .checksum_original(true)
+            .build()
+            .unwrap();
+        splitter.split_file(&file_path).unwrap();
+
+        let sums = fs::read_to_string(temp_dir.path().join("data.bin.SHA256SUMS")).unwrap();
+        assert_eq!(
+            sums,
+            format!(
+                "{}  data.bin.001\n{}  data.bin.002\n{}  data.bin\n",
+                sha256_hex(b"0123456789"),
+                sha256_hex(b"abcde"),
+                sha256_hex(b"0123456789abcde")
+            )
+        );
+        assert!(temp_dir.path().join("data.bin.sfv").exists());
+        assert!(!temp_dir.path().join("data.bin.md5").exists());
+
+        let manifest = Manifest::load(&Manifest::path_for(&file_path)).unwrap();
+        assert_eq!(manifest.sidecars, ["data.bin.sfv", "data.bin.SHA256SUMS"]);
+
+        // 作成していないチェックサムファイルは古いものとして扱わない
+        fs::write(temp_dir.path().join("data.bin.md5"), "downloaded").unwrap();
+
+        // チェックサムファイルなしで再分割すると記録された古いチェックサムファイルを削除する
+        let mut splitter = Splitter::builder()
+            .chunk_size(10)
+            .remove_stale(true)
+            .build()
+            .unwrap();
+        splitter.split_file(&file_path).unwrap();
+        assert_eq!(
+            splitter.removed_stale_parts(),
+            [
+                temp_dir.path().join("data.bin.sfv"),
+                temp_dir.path().join("data.bin.SHA256SUMS")
+            ]
+        );
+        assert!(splitter.stale_parts().is_empty());
+        assert!(!temp_dir.path().join("data.bin.SHA256SUMS").exists());
+        assert!(temp_dir.path().join("data.bin.md5").exists());
+    }
+
     #[test]
     fn test_split_nonexistent_file() {
         // 存在しないファイルを分割しようとした場合のエラーテスト
-        let splitter = Splitter::new(1024);
+        let mut splitter = Splitter::new(1024);
         let result = splitter.split_file(Path::new("/nonexistent/file.txt"));
         assert!(result.is_err());
     }
@@ -0,0 +1,201 @@
+use crate::encoding::Encoding;
+use crate::error::{FileSplitError, Result};
+
+/// 分割位置の判定結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Boundary {
+    /// 渡されたデータの先頭から指定バイト数を現在の分割ファイルに含め、
+    /// その位置で分割ファイルを終える
+    Cut(usize),
+    /// 渡されたデータの先頭から指定バイト数を現在の分割ファイルに含め、
+    /// 分割ファイルを続ける（残りのデータは次回の判定に持ち越す）
+    Continue(usize),
+}
+
+/// 分割ファイルの境界を決定する戦略
+///
+/// `Splitter` は入力を少しずつ読み込みながら `next_boundary` を呼び出し、
+/// 戻り値に従って分割ファイルを区切る。
+/// `Continue` で受け取らなかったデータは、続きのデータと合わせて次回に再度渡される。
+pub trait SplitStrategy {
+    /// 入力の先頭から分割を始める前に呼ばれる
+    ///
+    /// 状態を持つ戦略はここで初期化する。設定が不正な場合はエラーを返す
+    fn start(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// 現在の分割ファイルをどこで終えるかを判定する
+    ///
+    /// # 引数
+    /// * `data` - まだ分割ファイルに書き込まれていないデータ
+    /// * `part_len` - 現在の分割ファイルに書き込み済みのバイト数
+    /// * `eof` - 入力の終端に達しているかどうか（`data` が最後のデータ）
+    ///
+    /// # 戻り値
+    /// 分割位置の判定結果（バイト数は `data.len()` 以下であること）。
+    /// 終端で `Continue` を返した場合、残りのデータはすべて最後の分割ファイルに含まれる
+    fn next_boundary(&mut self, data: &[u8], part_len: u64, eof: bool) -> Boundary;
+
+    /// 入力のバイト数から分割数が決まる場合はその数を返す
+    ///
+    /// 分割ファイルの名前の桁数を決めるために使用する。
+    /// Noneを返した場合は、分割数を数えるために入力を事前に1回読み込む
+    fn part_count(&self, _size: u64) -> Option<u64> {
+        None
+    }
+}
+
+/// 固定バイト数ごとに分割する戦略
+pub struct FixedSizeStrategy {
+    /// 分割サイズ（バイト単位）
+    size: u64,
+    /// 文字境界を考慮するエンコーディング（Noneの場合はバイト単位で分割）
+    encoding: Option<Encoding>,
+    /// BOMからバイト順を確定したエンコーディング
+    resolved: Option<Encoding>,
+}
+
+impl FixedSizeStrategy {
+    /// 新しいFixedSizeStrategyを作成する
+    ///
+    /// # 引数
+    /// * `size` - 分割サイズ（バイト単位）
+    pub fn new(size: u64) -> Self {
+        Self {
+            size,
+            encoding: None,
+            resolved: None,
+        }
+    }
+
+    /// 文字の途中で分割しないようにエンコーディングを指定する
+    ///
+    /// # 引数
+    /// * `encoding` - 入力ファイルのエンコーディング
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    /// 分割サイズとエンコーディングの組み合わせが有効か検証する
+    pub fn validate(&self) -> Result<()> {
+        if self.size == 0 {
+            return Err(FileSplitError::InvalidSize(
+                "分割サイズには1以上を指定してください".to_string(),
+            ));
+        }
+        // 1文字が分割サイズに収まらないと分割できない
+        if self.encoding.is_some() && self.size < Encoding::MAX_CHAR_LEN as u64 {
+            return Err(FileSplitError::InvalidSize(format!(
+                "エンコーディング指定時は分割サイズを{}バイト以上にしてください",
+                Encoding::MAX_CHAR_LEN
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl SplitStrategy for FixedSizeStrategy {
+    fn start(&mut self) -> Result<()> {
+        self.validate()?;
+        self.resolved = None;
+        Ok(())
+    }
+
+    fn next_boundary(&mut self, data: &[u8], part_len: u64, _eof: bool) -> Boundary {
+        let remaining = self.size.saturating_sub(part_len);
+        let fits = (data.len() as u64) < remaining;
+
+        let encoding = match self.encoding {
+            None if fits => return Boundary::Continue(data.len()),
+            None => return Boundary::Cut(remaining as usize),
+            Some(encoding) => encoding,
+        };
+
+        // UTF-16は入力先頭のBOMでバイト順を確定する
+        let encoding = *self.resolved.get_or_insert_with(|| encoding.resolve(data));
+
+        // 分割位置を直前の文字境界まで戻す
+        if fits {
+            Boundary::Continue(encoding.last_boundary(data))
+        } else {
+            Boundary::Cut(encoding.last_boundary(&data[..remaining as usize]))
+        }
+    }
+
+    fn part_count(&self, size: u64) -> Option<u64> {
+        // 文字境界で分割位置を戻す場合は分割数が変わる
+        match self.encoding {
+            None if self.size > 0 => Some(size.div_ceil(self.size)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_size_part_count() {
+        // 入力のサイズから分割数を求められることをテスト
+        let strategy = FixedSizeStrategy::new(10);
+        assert_eq!(strategy.part_count(0), Some(0));
+        assert_eq!(strategy.part_count(10), Some(1));
+        assert_eq!(strategy.part_count(11), Some(2));
+
+        // 文字境界で分割位置を戻す場合は数えないとわからない
+        let strategy = FixedSizeStrategy::new(10).with_encoding(Encoding::Utf8);
+        assert_eq!(strategy.part_count(11), None);
+    }
+
+    #[test]
+    fn test_fixed_size_boundary() {
+        // 固定サイズの分割位置をテスト
+        let mut strategy = FixedSizeStrategy::new(10);
+        strategy.start().unwrap();
+
+        assert_eq!(
+            strategy.next_boundary(b"0123", 0, false),
+            Boundary::Continue(4)
+        );
+        assert_eq!(
+            strategy.next_boundary(b"456789abc", 4, false),
+            Boundary::Cut(6)
+        );
+        assert_eq!(
+            strategy.next_boundary(b"abc", 0, true),
+            Boundary::Continue(3)
+        );
+    }
+
+    #[test]
+    fn test_fixed_size_with_encoding_boundary() {
+        // エンコーディング指定時は文字境界まで戻すことをテスト（各文字3バイト）
+        let mut strategy = FixedSizeStrategy::new(7).with_encoding(Encoding::Utf8);
+        strategy.start().unwrap();
+
+        let data = "あいう".as_bytes();
+        assert_eq!(strategy.next_boundary(data, 0, false), Boundary::Cut(6));
+        // 途中までしかない文字は次回に持ち越す
+        assert_eq!(
+            strategy.next_boundary(&data[..5], 0, false),
+            Boundary::Continue(3)
+        );
+    }
+
+    #[test]
+    fn test_fixed_size_invalid_settings() {
+        // 不正な分割サイズのエラーテスト
+        assert!(FixedSizeStrategy::new(0).start().is_err());
+        assert!(FixedSizeStrategy::new(3)
+            .with_encoding(Encoding::ShiftJis)
+            .start()
+            .is_err());
+        assert!(FixedSizeStrategy::new(4)
+            .with_encoding(Encoding::ShiftJis)
+            .start()
+            .is_ok());
+    }
+}
@@ -0,0 +1,121 @@
+/// 分割ファイルごとに挿入するヘッダー・フッターのテンプレート
+///
+/// テンプレートでは以下のプレースホルダーが使用できる
+/// * `{index}` - 分割ファイルの番号（1から始まる）
+/// * `{total}` - 分割ファイルの総数
+/// * `{offset}` - 元ファイル内での開始位置（バイト）
+/// * `{original_name}` - 元ファイルの名前
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PartTemplate {
+    /// 各分割ファイルの先頭に挿入するテンプレート
+    pub header: Option<String>,
+    /// 各分割ファイルの末尾に挿入するテンプレート
+    pub footer: Option<String>,
+}
+
+/// テンプレートの展開に使用する分割ファイルの情報
+pub struct PartInfo<'a> {
+    /// 分割ファイルの番号（1から始まる）
+    pub index: usize,
+    /// 分割ファイルの総数
+    pub total: usize,
+    /// 元ファイル内での開始位置（バイト）
+    pub offset: u64,
+    /// 元ファイルの名前
+    pub original_name: &'a str,
+}
+
+impl PartTemplate {
+    /// ヘッダー・フッターのどちらも指定されていないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.header.is_none() && self.footer.is_none()
+    }
+
+    /// 展開に分割ファイルの総数が必要かどうか
+    pub fn needs_total(&self) -> bool {
+        [&self.header, &self.footer]
+            .iter()
+            .any(|t| t.as_deref().is_some_and(|t| t.contains("{total}")))
+    }
+
+    /// ヘッダーを展開する
+    pub fn render_header(&self, info: &PartInfo) -> String {
+        self.header
+            .as_deref()
+            .map(|t| Self::render(t, info))
+            .unwrap_or_default()
+    }
+
+    /// フッターを展開する
+    pub fn render_footer(&self, info: &PartInfo) -> String {
+        self.footer
+            .as_deref()
+            .map(|t| Self::render(t, info))
+            .unwrap_or_default()
+    }
+
+    /// テンプレート内のプレースホルダーを置き換える
+    fn render(template: &str, info: &PartInfo) -> String {
+        template
+            .replace("{index}", &info.index.to_string())
+            .replace("{total}", &info.total.to_string())
+            .replace("{offset}", &info.offset.to_string())
+            .replace("{original_name}", info.original_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_placeholders() {
+        // プレースホルダーの展開をテスト
+        let template = PartTemplate {
+            header: Some("== {original_name} {index}/{total} @{offset} ==\n".to_string()),
+            footer: Some("== end {index} ==\n".to_string()),
+        };
+        let info = PartInfo {
+            index: 2,
+            total: 5,
+            offset: 1024,
+            original_name: "data.txt",
+        };
+
+        assert_eq!(template.render_header(&info), "== data.txt 2/5 @1024 ==\n");
+        assert_eq!(template.render_footer(&info), "== end 2 ==\n");
+    }
+
+    #[test]
+    fn test_empty_template() {
+        // テンプレート未指定の場合は空文字列になることをテスト
+        let template = PartTemplate::default();
+        let info = PartInfo {
+            index: 1,
+            total: 1,
+            offset: 0,
+            original_name: "a",
+        };
+
+        assert!(template.is_empty());
+        assert!(!template.needs_total());
+        assert_eq!(template.render_header(&info), "");
+        assert_eq!(template.render_footer(&info), "");
+    }
+
+    #[test]
+    fn test_needs_total() {
+        // {total}を含むかどうかの判定をテスト
+        let template = PartTemplate {
+            header: Some("{index}".to_string()),
+            footer: Some("of {total}".to_string()),
+        };
+        assert!(template.needs_total());
+
+        let template = PartTemplate {
+            header: Some("{index}".to_string()),
+            footer: None,
+        };
+        assert!(!template.needs_total());
+    }
+}
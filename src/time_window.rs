@@ -0,0 +1,397 @@
+use crate::error::{FileSplitError, Result};
+use crate::options::TimeWindowOptions;
+use chrono::NaiveDateTime;
+use regex::bytes::Regex;
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// タイムスタンプ検出用のデフォルト正規表現
+pub const DEFAULT_TIMESTAMP_REGEX: &str = r"\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}";
+
+/// タイムスタンプ解析用のデフォルト書式（strftime形式）
+pub const DEFAULT_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// 時間枠の単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeWindow {
+    /// 1時間ごと
+    Hour,
+    /// 1日ごと
+    Day,
+}
+
+impl TimeWindow {
+    /// 文字列から時間枠をパースする
+    ///
+    /// # 引数
+    /// * `s` - "hour" または "day"
+    pub fn parse(s: &str) -> Result<Self> {
+        match s {
+            "hour" => Ok(TimeWindow::Hour),
+            "day" => Ok(TimeWindow::Day),
+            _ => Err(FileSplitError::InvalidTimestamp(format!(
+                "'{s}' は有効な時間枠ではありません（hour または day を指定してください）"
+            ))),
+        }
+    }
+
+    /// 日時が属する時間枠の名前を返す
+    ///
+    /// # 戻り値
+    /// 例: Hour -> "2026-10-17T13", Day -> "2026-10-17"
+    pub fn key(&self, datetime: &NaiveDateTime) -> String {
+        match self {
+            TimeWindow::Hour => datetime.format("%Y-%m-%dT%H").to_string(),
+            TimeWindow::Day => datetime.format("%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+/// ログファイルを行内のタイムスタンプに基づいて時間枠ごとに分割する構造体
+pub struct TimeWindowSplitter {
+    /// 時間枠の単位
+    window: TimeWindow,
+    /// タイムスタンプを検出する正規表現
+    pattern: Regex,
+    /// タイムスタンプの書式
+    format: String,
+}
+
+impl TimeWindowSplitter {
+    /// 新しいTimeWindowSplitterを作成する
+    ///
+    /// # 引数
+    /// * `window` - 時間枠の単位
+    /// * `pattern` - タイムスタンプを検出する正規表現（キャプチャグループがあれば最初のグループを使用）
+    /// * `format` - タイムスタンプの書式（strftime形式）
+    pub fn new(window: TimeWindow, pattern: &str, format: &str) -> Result<Self> {
+        let pattern = Regex::new(pattern)
+            .map_err(|e| FileSplitError::InvalidTimestamp(format!("正規表現が不正です: {e}")))?;
+        Ok(Self {
+            window,
+            pattern,
+            format: format.to_string(),
+        })
+    }
+
+    /// オプションから新しいTimeWindowSplitterを作成する
+    ///
+    /// # 引数
+    /// * `options` - 時間枠分割のオプション
+    pub fn from_options(options: &TimeWindowOptions) -> Result<Self> {
+        Self::new(
+            options.window,
+            &options.timestamp_regex,
+            &options.timestamp_format,
+        )
+    }
+
+    /// 指定されたファイルを時間枠ごとに分割する
+    ///
+    /// # 引数
+    /// * `file_path` - 分割するファイルのパス
+    ///
+    /// # 動作
+    /// 各行のタイムスタンプから時間枠を求め、元のファイル名.2026-10-17T13 のような
+    /// 形式で保存する。タイムスタンプを含まない行（継続行）は直前の行と同じ
+    /// 時間枠に書き込む。先頭のタイムスタンプを含まない行は最初の時間枠に含める。
+    /// 途中の行のタイムスタンプを解析できないなどで失敗した場合は、
+    /// それまでに作成した分割ファイルを削除する
+    ///
+    /// # 戻り値
+    /// 作成された分割ファイルのパス（作成順）
+    pub fn split_file(&self, file_path: &Path) -> Result<Vec<PathBuf>> {
+        let mut created = Vec::new();
+        let result = self.write_windows(file_path, &mut created);
+        if result.is_err() {
+            // 不完全な分割ファイルを残さない
+            for path in &created {
+                let _ = fs::remove_file(path);
+            }
+        }
+        result.map(|()| created)
+    }
+
+    /// ファイルを読み込んで時間枠ごとの分割ファイルに書き込む
+    ///
+    /// # 引数
+    /// * `file_path` - 分割するファイルのパス
+    /// * `created` - 作成した分割ファイルのパスを追加する一覧（失敗時の削除に使用する）
+    fn write_windows(&self, file_path: &Path, created: &mut Vec<PathBuf>) -> Result<()> {
+        let mut reader = BufReader::new(File::open(file_path)?);
+
+        // 作成済みの時間枠と現在の書き込み先
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut current: Option<(String, BufWriter<File>)> = None;
+
+        // 最初のタイムスタンプが現れるまでの行
+        let mut pending = Vec::new();
+        let mut line = Vec::new();
+        let mut line_number = 0;
+
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                break;
+            }
+            line_number += 1;
+
+            // タイムスタンプがあれば時間枠を切り替える
+            if let Some(key) = self.window_key(&line, line_number)? {
+                let switch = match &current {
+                    Some((current_key, _)) => *current_key != key,
+                    None => true,
+                };
+                if switch {
+                    if let Some((_, mut writer)) = current.take() {
+                        writer.flush()?;
+                    }
+                    let path = Self::get_window_file_path(file_path, &key);
+
+                    // 同じ時間枠が再度現れた場合は追記する
+                    let file = if seen.insert(key.clone()) {
+                        created.push(path.clone());
+                        File::create(&path)?
+                    } else {
+                        OpenOptions::new().append(true).open(&path)?
+                    };
+                    let mut writer = BufWriter::new(file);
+                    writer.write_all(&pending)?;
+                    pending.clear();
+                    current = Some((key, writer));
+                }
+            }
+
+            match &mut current {
+                Some((_, writer)) => writer.write_all(&line)?,
+                None => pending.extend_from_slice(&line),
+            }
+        }
+
+        match current {
+            Some((_, mut writer)) => writer.flush()?,
+            None if !pending.is_empty() => {
+                // 空でないのにタイムスタンプが1つも見つからなかった
+                return Err(FileSplitError::InvalidTimestamp(format!(
+                    "タイムスタンプを含む行が見つかりません: {}",
+                    file_path.display()
+                )));
+            }
+            None => {}
+        }
+
+        Ok(())
+    }
+
+    /// 行からタイムスタンプを探し、その時間枠の名前を返す
+    ///
+    /// # 戻り値
+    /// タイムスタンプがない行の場合はNone
+    fn window_key(&self, line: &[u8], line_number: usize) -> Result<Option<String>> {
+        let captures = match self.pattern.captures(line) {
+            Some(captures) => captures,
+            None => return Ok(None),
+        };
+        // キャプチャグループがあれば最初のグループ、なければ全体を使用
+        let matched = captures.get(1).or_else(|| captures.get(0)).unwrap();
+        let text = String::from_utf8_lossy(matched.as_bytes());
+        let datetime = NaiveDateTime::parse_from_str(&text, &self.format).map_err(|e| {
+            FileSplitError::InvalidTimestamp(format!(
+                "{line_number}行目のタイムスタンプ '{text}' を解析できません: {e}"
+            ))
+        })?;
+        Ok(Some(self.window.key(&datetime)))
+    }
+
+    /// 時間枠に対応する分割ファイルパスを生成する
+    ///
+    /// # 戻り値
+    /// 例: "app.log", "2026-10-17T13" -> "app.log.2026-10-17T13"
+    fn get_window_file_path(base_path: &Path, key: &str) -> PathBuf {
+        let mut path = base_path.to_path_buf();
+        let file_name = format!(
+            "{}.{}",
+            path.file_name().unwrap_or_default().to_string_lossy(),
+            key
+        );
+        path.set_file_name(file_name);
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn default_splitter(window: TimeWindow) -> TimeWindowSplitter {
+        TimeWindowSplitter::new(window, DEFAULT_TIMESTAMP_REGEX, DEFAULT_TIMESTAMP_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn test_time_window_parse() {
+        // 時間枠のパースをテスト
+        assert_eq!(TimeWindow::parse("hour").unwrap(), TimeWindow::Hour);
+        assert_eq!(TimeWindow::parse("day").unwrap(), TimeWindow::Day);
+        assert!(TimeWindow::parse("week").is_err());
+    }
+
+    #[test]
+    fn test_time_window_key() {
+        // 時間枠の名前をテスト
+        let datetime =
+            NaiveDateTime::parse_from_str("2026-10-17 13:45:00", DEFAULT_TIMESTAMP_FORMAT).unwrap();
+        assert_eq!(TimeWindow::Hour.key(&datetime), "2026-10-17T13");
+        assert_eq!(TimeWindow::Day.key(&datetime), "2026-10-17");
+    }
+
+    #[test]
+    fn test_split_by_hour_with_continuation_lines() {
+        // 時間ごとの分割と継続行の扱いをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("app.log");
+        fs::write(
+            &log_path,
+            "2026-10-17 13:00:01 start\n\
+             2026-10-17 13:59:59 error\n\
+             \tat main.rs:10\n\
+             2026-10-17 14:00:00 next\n",
+        )
+        .unwrap();
+
+        let created = default_splitter(TimeWindow::Hour)
+            .split_file(&log_path)
+            .unwrap();
+
+        let first = temp_dir.path().join("app.log.2026-10-17T13");
+        let second = temp_dir.path().join("app.log.2026-10-17T14");
+        assert_eq!(created, vec![first.clone(), second.clone()]);
+        assert_eq!(
+            fs::read_to_string(&first).unwrap(),
+            "2026-10-17 13:00:01 start\n2026-10-17 13:59:59 error\n\tat main.rs:10\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&second).unwrap(),
+            "2026-10-17 14:00:00 next\n"
+        );
+    }
+
+    #[test]
+    fn test_split_by_day_with_leading_lines() {
+        // 先頭のタイムスタンプなし行は最初の時間枠に含まれることをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("app.log");
+        fs::write(
+            &log_path,
+            "header\n2026-10-17 23:00:00 a\n2026-10-18 00:00:00 b",
+        )
+        .unwrap();
+
+        default_splitter(TimeWindow::Day)
+            .split_file(&log_path)
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("app.log.2026-10-17")).unwrap(),
+            "header\n2026-10-17 23:00:00 a\n"
+        );
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("app.log.2026-10-18")).unwrap(),
+            "2026-10-18 00:00:00 b"
+        );
+    }
+
+    #[test]
+    fn test_split_out_of_order_windows_appends() {
+        // 同じ時間枠が再度現れた場合は追記されることをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("app.log");
+        fs::write(
+            &log_path,
+            "2026-10-17 13:00:00 a\n2026-10-17 14:00:00 b\n2026-10-17 13:30:00 c\n",
+        )
+        .unwrap();
+
+        let created = default_splitter(TimeWindow::Hour)
+            .split_file(&log_path)
+            .unwrap();
+
+        assert_eq!(created.len(), 2);
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("app.log.2026-10-17T13")).unwrap(),
+            "2026-10-17 13:00:00 a\n2026-10-17 13:30:00 c\n"
+        );
+    }
+
+    #[test]
+    fn test_split_custom_pattern_with_capture_group() {
+        // キャプチャグループ付きの正規表現と独自書式をテスト
+        let temp_dir = TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("access.log");
+        fs::write(
+            &log_path,
+            "127.0.0.1 - - [17/Oct/2026:13:00:00 +0900] \"GET /\"\n",
+        )
+        .unwrap();
+
+        let splitter =
+            TimeWindowSplitter::new(TimeWindow::Day, r"\[([^\]]+)\]", "%d/%b/%Y:%H:%M:%S %z")
+                .unwrap();
+        splitter.split_file(&log_path).unwrap();
+
+        assert!(temp_dir.path().join("access.log.2026-10-17").exists());
+    }
+
+    #[test]
+    fn test_split_invalid_timestamp() {
+        // 書式に合わないタイムスタンプのエラーテスト
+        let temp_dir = TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("app.log");
+        fs::write(&log_path, "2026-13-45 99:00:00 broken\n").unwrap();
+
+        let result = default_splitter(TimeWindow::Hour).split_file(&log_path);
+        match result.unwrap_err() {
+            FileSplitError::InvalidTimestamp(msg) => assert!(msg.contains("1行目")),
+            _ => panic!("予期しないエラー型"),
+        }
+    }
+
+    #[test]
+    fn test_split_invalid_timestamp_removes_created_files() {
+        // 途中の行で失敗した場合に作成済みの分割ファイルが削除されることをテスト
+        let temp_dir = TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("app.log");
+        fs::write(
+            &log_path,
+            "2026-10-17 13:00:00 ok\n2026-10-17 14:00:00 ok\n2026-13-45 99:00:00 broken\n",
+        )
+        .unwrap();
+
+        let result = default_splitter(TimeWindow::Hour).split_file(&log_path);
+        assert!(matches!(result, Err(FileSplitError::InvalidTimestamp(_))));
+        assert!(!temp_dir.path().join("app.log.2026-10-17T13").exists());
+        assert!(!temp_dir.path().join("app.log.2026-10-17T14").exists());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_split_without_timestamps() {
+        // タイムスタンプが1つもない場合のエラーテスト
+        let temp_dir = TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("app.log");
+        fs::write(&log_path, "no timestamp here\n").unwrap();
+
+        let result = default_splitter(TimeWindow::Hour).split_file(&log_path);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_invalid_regex() {
+        // 不正な正規表現のエラーテスト
+        let result = TimeWindowSplitter::new(TimeWindow::Hour, "(", DEFAULT_TIMESTAMP_FORMAT);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,665 @@
+use crate::container::{PartHeader, HEADER_LEN};
+use crate::error::{FileSplitError, Result};
+use crate::io::writer;
+use crate::manifest::{sha256_file, Manifest};
+use crate::merger::Merger;
+use crate::naming::NamingScheme;
+use crate::options::VerifyOptions;
+use crate::template::{PartInfo, PartTemplate};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{BufReader, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// 比較用バッファのサイズ（バイト単位）
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// 分割ファイル一式を結合せずに検証する構造体
+#[derive(Default)]
+pub struct Verifier {
+    /// 比較対象の元ファイル（Noneの場合はマニフェストと照合する）
+    original: Option<PathBuf>,
+    /// 分割時に挿入したヘッダー・フッター
+    template: PartTemplate,
+    /// 分割ファイルを探すディレクトリ（Noneの場合は元ファイルと同じディレクトリ）
+    output_dir: Option<PathBuf>,
+    /// 分割時に元ファイル名の代わりに使用した名前
+    prefix: Option<PathBuf>,
+    /// 分割ファイルの名前の付け方
+    naming: NamingScheme,
+}
+
+/// 検証結果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// 問題のなかった分割ファイル
+    pub verified: Vec<PathBuf>,
+    /// 存在しない分割ファイル
+    pub missing: Vec<PathBuf>,
+    /// 期待より短い分割ファイル
+    pub truncated: Vec<PathBuf>,
+    /// 内容が一致しない分割ファイル
+    pub corrupted: Vec<PathBuf>,
+    /// 分割ファイル一式に含まれないはずの分割ファイル
+    pub extra: Vec<PathBuf>,
+}
+
+impl VerifyReport {
+    /// 問題が1つもなかったかどうか
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty()
+            && self.truncated.is_empty()
+            && self.corrupted.is_empty()
+            && self.extra.is_empty()
+    }
+
+    /// 問題の件数をまとめた文字列を返す
+    pub fn summary(&self) -> String {
+        format!(
+            "正常 {}, 欠落 {}, 切り詰め {}, 破損 {}, 余分 {}",
+            self.verified.len(),
+            self.missing.len(),
+            self.truncated.len(),
+            self.corrupted.len(),
+            self.extra.len()
+        )
+    }
+}
+
+/// 分割ファイルと元ファイルの比較結果
+enum Comparison {
+    /// 一致した
+    Match,
+    /// 分割ファイルが元ファイルの終端を越えている、または内容が異なる
+    Mismatch,
+    /// 分割ファイルの開始位置が元ファイルの終端以降にある
+    BeyondEnd,
+}
+
+/// 元ファイルと比較する分割ファイル
+struct ExpectedPart {
+    /// 分割ファイルの番号
+    index: usize,
+    /// 分割ファイルのパス
+    path: PathBuf,
+    /// マニフェストに記録された元ファイル内での開始位置とデータの長さ
+    position: Option<(u64, u64)>,
+}
+
+impl Verifier {
+    /// マニフェストと照合する新しいVerifierを作成する
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Verifierを組み立てるビルダーを作成する
+    pub fn builder() -> VerifierBuilder {
+        VerifierBuilder::default()
+    }
+
+    /// 分割ファイル一式を検証する
+    ///
+    /// # 引数
+    /// * `base_path` - 元ファイルのパス（分割ファイルは base_path.001, ... ）
+    ///
+    /// # 動作
+    /// 元ファイルが指定されていれば各分割ファイルの内容を元ファイルと比較し、
+    /// 指定されていなければ base_path.manifest.json に記録された
+    /// サイズとハッシュと照合する。分割ファイルを探すディレクトリと名前の指定は
+    /// `Merger` と同じように反映する
+    ///
+    /// # 戻り値
+    /// 検証結果。問題が見つかってもエラーにはならない
+    pub fn verify(&self, base_path: &Path) -> Result<VerifyReport> {
+        let part_base = self.part_base_path(base_path);
+        let manifest_path = Manifest::path_for(&part_base);
+        let manifest = if manifest_path.exists() {
+            Some(Manifest::load(&manifest_path)?)
+        } else {
+            None
+        };
+
+        match (&self.original, manifest) {
+            (Some(original), manifest) => {
+                // テンプレートはマニフェストに記録された元ファイル名で展開する
+                let original_name = match &manifest {
+                    Some(manifest) => manifest.original_name.clone(),
+                    None => base_path
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string(),
+                };
+                self.verify_with_original(&part_base, &original_name, original, manifest.as_ref())
+            }
+            (None, Some(manifest)) => self.verify_with_manifest(&part_base, &manifest),
+            (None, None) => Err(FileSplitError::FileNotFound(
+                manifest_path.to_string_lossy().to_string(),
+            )),
+        }
+    }
+
+    /// 分割ファイルの名前の基準になるパスを返す
+    ///
+    /// # 引数
+    /// * `base_path` - 元ファイルのパス
+    ///
+    /// # 戻り値
+    /// 分割ファイルを探すディレクトリと名前の指定を反映したパス
+    pub fn part_base_path(&self, base_path: &Path) -> PathBuf {
+        writer::part_base_path(
+            base_path,
+            self.output_dir.as_deref(),
+            self.prefix.as_deref(),
+        )
+    }
+
+    /// マニフェストの記録と照合する
+    fn verify_with_manifest(&self, base_path: &Path, manifest: &Manifest) -> Result<VerifyReport> {
+        let mut report = VerifyReport::default();
+
+        for part in &manifest.parts {
+            let split_path = Merger::manifest_part_path(base_path, &part.name)?;
+            if !split_path.exists() {
+                report.missing.push(split_path);
+                continue;
+            }
+
+            let size = fs::metadata(&split_path)?.len();
+            if size < part.size {
+                report.truncated.push(split_path);
+            } else if size > part.size || sha256_file(&split_path)? != part.sha256 {
+                report.corrupted.push(split_path);
+            } else {
+                report.verified.push(split_path);
+            }
+        }
+
+        // マニフェストに記録されていない分割ファイルは余分
+        let names: HashSet<&str> = manifest.parts.iter().map(|p| p.name.as_str()).collect();
+        report.extra = self
+            .naming
+            .scan_all(base_path)?
+            .into_iter()
+            .map(|(_, path)| path)
+            .filter(|path| {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                !names.contains(name.as_ref())
+            })
+            .collect();
+
+        Ok(report)
+    }
+
+    /// 元ファイルの内容と比較する
+    ///
+    /// マニフェストがあれば記録された分割ファイルと位置を使用し、
+    /// なければ見つかった分割ファイルを番号順に連結したものとして比較する。
+    /// ヘッダー・フッターは展開結果と一致するかを確認し、その間のデータだけを比較する
+    fn verify_with_original(
+        &self,
+        base_path: &Path,
+        original_name: &str,
+        original: &Path,
+        manifest: Option<&Manifest>,
+    ) -> Result<VerifyReport> {
+        if !original.exists() {
+            return Err(FileSplitError::FileNotFound(
+                original.to_string_lossy().to_string(),
+            ));
+        }
+        let original_len = fs::metadata(original)?.len();
+        let mut report = VerifyReport::default();
+
+        // 比較する分割ファイルと期待する位置・長さを決定する
+        let mut expected = Vec::new();
+        let total = match manifest {
+            Some(manifest) => {
+                for part in &manifest.parts {
+                    let split_path = Merger::manifest_part_path(base_path, &part.name)?;
+                    expected.push(ExpectedPart {
+                        index: part.index,
+                        path: split_path,
+                        position: Some((part.offset, part.length)),
+                    });
+                }
+                let names: HashSet<&str> = manifest.parts.iter().map(|p| p.name.as_str()).collect();
+                for (_, path) in self.naming.scan_all(base_path)? {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    if !names.contains(name.as_ref()) {
+                        report.extra.push(path);
+                    }
+                }
+                manifest.part_count
+            }
+            None => {
+                // 連番の欠けは欠落として扱う（名前は見つかった分割ファイルの桁数に合わせる）
+                let mut scanned = self.naming.scan(base_path)?;
+                let Some(&last) = scanned.keys().next_back() else {
+                    return Err(FileSplitError::NoSplitFiles(
+                        base_path.to_string_lossy().to_string(),
+                    ));
+                };
+                let naming = self.naming.fit(last)?;
+                for index in 1..=last {
+                    let split_path = match scanned.remove(&index) {
+                        Some(path) => path,
+                        None => naming.path(base_path, index)?,
+                    };
+                    expected.push(ExpectedPart {
+                        index,
+                        path: split_path,
+                        position: None,
+                    });
+                }
+                last
+            }
+        };
+
+        let mut offset = 0u64;
+        let last_index = expected.len().saturating_sub(1);
+        for (i, part) in expected.into_iter().enumerate() {
+            let ExpectedPart {
+                index,
+                path: split_path,
+                position,
+            } = part;
+            if let Some((part_offset, _)) = position {
+                offset = part_offset;
+            }
+            if !split_path.exists() {
+                report.missing.push(split_path);
+                if let Some((_, length)) = position {
+                    offset += length;
+                }
+                continue;
+            }
+
+            // コンテナ形式のヘッダーは比較の対象外
+            let skip = match PartHeader::read_from(&split_path)? {
+                Some(_) => HEADER_LEN as u64,
+                None => 0,
+            };
+            let payload_len = fs::metadata(&split_path)?.len() - skip;
+
+            // ヘッダー・フッターが展開結果と一致しなければ破損として扱う
+            let info = PartInfo {
+                index,
+                total,
+                offset,
+                original_name,
+            };
+            let header = self.template.render_header(&info);
+            let footer = self.template.render_footer(&info);
+            let template_len = (header.len() + footer.len()) as u64;
+            let size = payload_len.saturating_sub(template_len);
+            let comparison = if payload_len < template_len
+                || !Self::starts_with_at(&split_path, skip, header.as_bytes())?
+                || !Self::starts_with_at(
+                    &split_path,
+                    skip + header.len() as u64 + size,
+                    footer.as_bytes(),
+                )? {
+                Comparison::Mismatch
+            } else {
+                let data_start = skip + header.len() as u64;
+                Self::compare_with_original(&split_path, data_start, size, original, offset)?
+            };
+            match comparison {
+                Comparison::BeyondEnd => report.extra.push(split_path),
+                Comparison::Mismatch => report.corrupted.push(split_path),
+                Comparison::Match => {
+                    // 期待より短い、または最後の分割ファイルが元ファイルの終端に届かない
+                    let short = match position {
+                        Some((_, length)) => size < length,
+                        None => i == last_index && offset + size < original_len,
+                    };
+                    if short {
+                        report.truncated.push(split_path);
+                    } else {
+                        report.verified.push(split_path);
+                    }
+                }
+            }
+            offset += match position {
+                Some((_, length)) => length,
+                None => size,
+            };
+        }
+
+        Ok(report)
+    }
+
+    /// 分割ファイルの指定位置が指定したバイト列で始まるかどうか
+    fn starts_with_at(split_path: &Path, position: u64, expected: &[u8]) -> Result<bool> {
+        if expected.is_empty() {
+            return Ok(true);
+        }
+        let mut file = File::open(split_path)?;
+        file.seek(SeekFrom::Start(position))?;
+        let mut buffer = vec![0u8; expected.len()];
+        match file.read_exact(&mut buffer) {
+            Ok(()) => Ok(buffer == expected),
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 分割ファイルのデータ部分を元ファイルの指定位置からの内容と比較する
+    ///
+    /// 分割ファイルの `skip` バイト目から `part_len` バイトを比較する
+    /// （コンテナ形式のヘッダーとテンプレートのヘッダー・フッターは比較しない）
+    fn compare_with_original(
+        split_path: &Path,
+        skip: u64,
+        part_len: u64,
+        original: &Path,
+        offset: u64,
+    ) -> Result<Comparison> {
+        let mut original_file = File::open(original)?;
+        let original_len = original_file.metadata()?.len();
+        if offset >= original_len && part_len > 0 {
+            return Ok(Comparison::BeyondEnd);
+        }
+        if offset + part_len > original_len {
+            return Ok(Comparison::Mismatch);
+        }
+
+        original_file.seek(SeekFrom::Start(offset))?;
+        let mut original_reader = BufReader::new(original_file);
+        let mut part_file = File::open(split_path)?;
+        part_file.seek(SeekFrom::Start(skip))?;
+        let mut part_reader = BufReader::new(part_file).take(part_len);
+
+        let mut part_buffer = vec![0u8; BUFFER_SIZE];
+        let mut original_buffer = vec![0u8; BUFFER_SIZE];
+        loop {
+            let bytes_read = part_reader.read(&mut part_buffer)?;
+            if bytes_read == 0 {
+                return Ok(Comparison::Match);
+            }
+            original_reader.read_exact(&mut original_buffer[..bytes_read])?;
+            if part_buffer[..bytes_read] != original_buffer[..bytes_read] {
+                return Ok(Comparison::Mismatch);
+            }
+        }
+    }
+}
+
+/// Verifierのビルダー
+#[derive(Default)]
+pub struct VerifierBuilder {
+    /// 検証のオプション
+    options: VerifyOptions,
+}
+
+impl VerifierBuilder {
+    /// オプションをまとめて指定する
+    pub fn options(mut self, options: VerifyOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// マニフェストの代わりに元ファイルと比較する
+    pub fn original(mut self, original: impl Into<PathBuf>) -> Self {
+        self.options.original = Some(original.into());
+        self
+    }
+
+    /// 分割時に挿入したヘッダー・フッターを指定する
+    pub fn template(mut self, template: PartTemplate) -> Self {
+        self.options.template = template;
+        self
+    }
+
+    /// 分割時に挿入したヘッダーのテンプレートを指定する
+    pub fn header(mut self, header: impl Into<String>) -> Self {
+        self.options.template.header = Some(header.into());
+        self
+    }
+
+    /// 分割時に挿入したフッターのテンプレートを指定する
+    pub fn footer(mut self, footer: impl Into<String>) -> Self {
+        self.options.template.footer = Some(footer.into());
+        self
+    }
+
+    /// 分割ファイルを探すディレクトリを指定する
+    pub fn output_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.options.output_dir = Some(dir.into());
+        self
+    }
+
+    /// 分割時に元ファイル名の代わりに使用した名前を指定する
+    pub fn prefix(mut self, prefix: impl Into<PathBuf>) -> Self {
+        self.options.prefix = Some(prefix.into());
+        self
+    }
+
+    /// 分割時に使用した分割ファイルの名前の付け方を指定する
+    pub fn naming(mut self, naming: NamingScheme) -> Self {
+        self.options.naming = naming;
+        self
+    }
+
+    /// Verifierを作成する
+    pub fn build(self) -> Result<Verifier> {
+        Ok(Verifier {
+            original: self.options.original,
+            template: self.options.template,
+            output_dir: self.options.output_dir,
+            prefix: self.options.prefix,
+            naming: self.options.naming,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::splitter::Splitter;
+    use tempfile::TempDir;
+
+    /// テスト用に分割ファイル一式を作成する
+    fn split_fixture(temp_dir: &TempDir) -> PathBuf {
+        let file_path = temp_dir.path().join("data.bin");
+        fs::write(&file_path, "0123456789".repeat(3)).unwrap();
+        Splitter::new(10).split_file(&file_path).unwrap();
+        file_path
+    }
+
+    #[test]
+    fn test_verify_with_manifest_ok() {
+        // 問題のない分割ファイル一式の検証をテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = split_fixture(&temp_dir);
+
+        let report = Verifier::new().verify(&file_path).unwrap();
+        assert!(report.is_ok());
+        assert_eq!(report.verified.len(), 3);
+    }
+
+    #[test]
+    fn test_verify_with_manifest_problems() {
+        // 欠落・切り詰め・破損・余分の検出をテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = split_fixture(&temp_dir);
+        let dir = temp_dir.path();
+
+        fs::remove_file(dir.join("data.bin.001")).unwrap();
+        fs::write(dir.join("data.bin.002"), "01234").unwrap();
+        fs::write(dir.join("data.bin.003"), "x123456789").unwrap();
+        fs::write(dir.join("data.bin.004"), "extra").unwrap();
+
+        let report = Verifier::new().verify(&file_path).unwrap();
+        assert!(!report.is_ok());
+        assert_eq!(report.missing, vec![dir.join("data.bin.001")]);
+        assert_eq!(report.truncated, vec![dir.join("data.bin.002")]);
+        assert_eq!(report.corrupted, vec![dir.join("data.bin.003")]);
+        assert_eq!(report.extra, vec![dir.join("data.bin.004")]);
+        assert_eq!(
+            report.summary(),
+            "正常 0, 欠落 1, 切り詰め 1, 破損 1, 余分 1"
+        );
+    }
+
+    #[test]
+    fn test_verify_without_manifest() {
+        // マニフェストも元ファイルもない場合のエラーテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("data.bin");
+        fs::write(temp_dir.path().join("data.bin.001"), "abc").unwrap();
+
+        let result = Verifier::new().verify(&file_path);
+        assert!(matches!(result, Err(FileSplitError::FileNotFound(_))));
+    }
+
+    #[test]
+    fn test_verify_with_original() {
+        // マニフェストなしで元ファイルと比較するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let original = dir.join("original.bin");
+        fs::write(&original, "0123456789abcdefghij").unwrap();
+
+        let file_path = dir.join("data.bin");
+        fs::write(dir.join("data.bin.001"), "0123456789").unwrap();
+        fs::write(dir.join("data.bin.002"), "abcde").unwrap();
+
+        let verifier = Verifier::builder().original(&original).build().unwrap();
+        let report = verifier.verify(&file_path).unwrap();
+        assert_eq!(report.verified, vec![dir.join("data.bin.001")]);
+        assert_eq!(report.truncated, vec![dir.join("data.bin.002")]);
+
+        // 内容の誤りと終端以降の分割ファイル
+        fs::write(dir.join("data.bin.002"), "abcdefghiX").unwrap();
+        fs::write(dir.join("data.bin.004"), "more").unwrap();
+        let report = verifier.verify(&file_path).unwrap();
+        assert_eq!(report.corrupted, vec![dir.join("data.bin.002")]);
+        assert_eq!(report.missing, vec![dir.join("data.bin.003")]);
+        assert_eq!(report.extra, vec![dir.join("data.bin.004")]);
+    }
+
+    #[test]
+    fn test_verify_with_original_without_parts() {
+        // 分割ファイルもマニフェストもない場合は元ファイルがあってもエラーになるテスト
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let original = dir.join("original.bin");
+        fs::write(&original, "0123456789").unwrap();
+
+        let verifier = Verifier::builder().original(&original).build().unwrap();
+        let result = verifier.verify(&dir.join("data.bin"));
+        assert!(matches!(result, Err(FileSplitError::NoSplitFiles(_))));
+    }
+
+    #[test]
+    fn test_verify_with_original_and_manifest() {
+        // マニフェストの位置情報を使って元ファイルと比較するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = split_fixture(&temp_dir);
+        let original = temp_dir.path().join("copy.bin");
+        fs::copy(&file_path, &original).unwrap();
+
+        let verifier = Verifier::builder().original(&original).build().unwrap();
+        let report = verifier.verify(&file_path).unwrap();
+        assert!(report.is_ok());
+
+        fs::write(temp_dir.path().join("data.bin.002"), "01234").unwrap();
+        let report = verifier.verify(&file_path).unwrap();
+        assert_eq!(report.truncated, vec![temp_dir.path().join("data.bin.002")]);
+    }
+
+    #[test]
+    fn test_verify_with_original_and_template() {
+        // ヘッダー・フッターを除いて元ファイルと比較するテスト
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let file_path = dir.join("data.bin");
+        fs::write(&file_path, "0123456789".repeat(3)).unwrap();
+        Splitter::builder()
+            .chunk_size(10)
+            .header("[{original_name} {index}/{total}]\n")
+            .footer("\n@{offset}")
+            .build()
+            .unwrap()
+            .split_file(&file_path)
+            .unwrap();
+        let original = dir.join("copy.bin");
+        fs::copy(&file_path, &original).unwrap();
+        fs::remove_file(Manifest::path_for(&file_path)).unwrap();
+
+        // テンプレートを指定しなければ一致しない
+        let verifier = Verifier::builder().original(&original).build().unwrap();
+        let report = verifier.verify(&file_path).unwrap();
+        assert!(report.verified.is_empty());
+        assert!(!report.corrupted.is_empty());
+
+        let verifier = Verifier::builder()
+            .original(&original)
+            .header("[{original_name} {index}/{total}]\n")
+            .footer("\n@{offset}")
+            .build()
+            .unwrap();
+        let report = verifier.verify(&file_path).unwrap();
+        assert!(report.is_ok(), "{report:?}");
+        assert_eq!(report.verified.len(), 3);
+
+        // フッターが展開結果と一致しなければ破損
+        fs::write(dir.join("data.bin.002"), "[data.bin 2/3]\n0123456789\n@99").unwrap();
+        let report = verifier.verify(&file_path).unwrap();
+        assert_eq!(report.corrupted, vec![dir.join("data.bin.002")]);
+        assert_eq!(report.verified.len(), 2);
+    }
+
+    #[test]
+    fn test_verify_with_output_dir_and_prefix() {
+        // 別のディレクトリ・別の名前で作成された分割ファイルの検証をテスト
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("data.bin");
+        let parts_dir = temp_dir.path().join("parts");
+        fs::write(&file_path, "0123456789".repeat(3)).unwrap();
+        Splitter::builder()
+            .chunk_size(10)
+            .header("{original_name}:")
+            .output_dir(&parts_dir)
+            .prefix("chunk")
+            .build()
+            .unwrap()
+            .split_file(&file_path)
+            .unwrap();
+
+        // 指定しなければ分割ファイルを見つけられない
+        assert!(matches!(
+            Verifier::new().verify(&file_path),
+            Err(FileSplitError::FileNotFound(_))
+        ));
+
+        let report = Verifier::builder()
+            .output_dir(&parts_dir)
+            .prefix("chunk")
+            .build()
+            .unwrap()
+            .verify(&file_path)
+            .unwrap();
+        assert!(report.is_ok(), "{report:?}");
+        assert_eq!(
+            report.verified,
+            (1..=3)
+                .map(|i| parts_dir.join(format!("chunk.{i:03}")))
+                .collect::<Vec<_>>()
+        );
+
+        // ヘッダーは分割ファイルの名前ではなく元ファイルの名前で展開されている
+        let report = Verifier::builder()
+            .original(&file_path)
+            .header("{original_name}:")
+            .output_dir(&parts_dir)
+            .prefix("chunk")
+            .build()
+            .unwrap()
+            .verify(&file_path)
+            .unwrap();
+        assert!(report.is_ok(), "{report:?}");
+    }
+}
@@ -1,17 +1,31 @@
 use std::fs;
 use std::io::Write;
-use std::process::Command;
+use std::process::{Command, Stdio};
 use tempfile::{NamedTempFile, TempDir};
 
 /// 統合テスト用のヘルパー関数：コマンドを実行
 fn run_command(args: &[&str]) -> std::process::Output {
     Command::new("cargo")
-        .args(&["run", "--"])
+        .args(["run", "--"])
         .args(args)
         .output()
         .expect("コマンドの実行に失敗しました")
 }
 
+/// 統合テスト用のヘルパー関数：標準入力にデータを渡してコマンドを実行
+fn run_command_with_stdin(args: &[&str], input: &[u8]) -> std::process::Output {
+    let mut child = Command::new("cargo")
+        .args(["run", "--"])
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("コマンドの実行に失敗しました");
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    child.wait_with_output().unwrap()
+}
+
 #[test]
 fn test_split_and_merge_integration() {
     // 一時ディレクトリとファイルを作成
@@ -141,6 +155,53 @@ fn test_help_option() {
     assert!(stdout.contains("-m"));
 }
 
+#[test]
+fn test_subcommands() {
+    // サブコマンド形式で分割・情報表示・一覧表示・結合するテスト
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("data.bin");
+    let path_str = file_path.to_str().unwrap();
+    let data = "0123456789".repeat(3);
+    fs::write(&file_path, &data).unwrap();
+
+    let output = run_command(&["split", "-s", "10", path_str]);
+    assert!(
+        output.status.success(),
+        "分割コマンドが失敗しました: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = run_command(&["info", path_str]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("元ファイル: data.bin"));
+    assert!(stdout.contains("サイズ: 30 バイト"));
+    assert!(stdout.contains("分割ファイル数: 3"));
+
+    fs::remove_file(temp_dir.path().join("data.bin.002")).unwrap();
+    let output = run_command(&["list", path_str]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("data.bin.001"));
+    assert!(stdout.contains("欠落"));
+    assert!(stdout.contains("3 ファイル（欠落 1）"));
+
+    fs::remove_file(&file_path).unwrap();
+    let output = run_command(&["merge", "--allow-partial", path_str]);
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(&file_path).unwrap(),
+        data[..10].repeat(2)
+    );
+
+    // サブコマンドごとの引数だけを受け付ける
+    let output = run_command(&["merge", "-s", "10", path_str]);
+    assert!(!output.status.success());
+    let output = run_command(&["split", "--help"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("--size"));
+}
+
 #[test]
 fn test_split_binary_file() {
     // バイナリファイルの分割・結合テスト
@@ -169,3 +230,662 @@ fn test_split_binary_file() {
     let merged_content = fs::read(temp_file.path()).unwrap();
     assert_eq!(merged_content, binary_data);
 }
+
+#[test]
+fn test_split_by_time_window() {
+    // ログを時間枠ごとに分割するテスト
+    let temp_dir = TempDir::new().unwrap();
+    let log_path = temp_dir.path().join("app.log");
+    fs::write(
+        &log_path,
+        "2026-10-17 13:00:00 a\n  continued\n2026-10-17 14:00:00 b\n",
+    )
+    .unwrap();
+
+    let output = run_command(&["-t", "hour", log_path.to_str().unwrap()]);
+    assert!(
+        output.status.success(),
+        "時間枠分割コマンドが失敗しました: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("app.log.2026-10-17T13")).unwrap(),
+        "2026-10-17 13:00:00 a\n  continued\n"
+    );
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("app.log.2026-10-17T14")).unwrap(),
+        "2026-10-17 14:00:00 b\n"
+    );
+}
+
+#[test]
+fn test_split_and_merge_with_header_footer() {
+    // ヘッダー・フッター付きで分割し、結合時に取り除くテスト
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("review.txt");
+    let test_data = "line1\nline2\nline3\n";
+    fs::write(&file_path, test_data).unwrap();
+    let path_str = file_path.to_str().unwrap();
+
+    let header = "--- {original_name} part {index}/{total} (offset {offset}) ---\n";
+    let footer = "--- end of part {index} ---\n";
+    let output = run_command(&["-s", "12", "--header", header, "--footer", footer, path_str]);
+    assert!(
+        output.status.success(),
+        "分割コマンドが失敗しました: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let part2 = fs::read_to_string(temp_dir.path().join("review.txt.002")).unwrap();
+    assert_eq!(
+        part2,
+        "--- review.txt part 2/2 (offset 12) ---\nline3\n--- end of part 2 ---\n"
+    );
+
+    fs::remove_file(&file_path).unwrap();
+    let output = run_command(&["-m", "--header", header, "--footer", footer, path_str]);
+    assert!(
+        output.status.success(),
+        "結合コマンドが失敗しました: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), test_data);
+}
+
+#[test]
+fn test_split_writes_manifest() {
+    // 分割時にマニフェストが保存され、結合時に使用されるテスト
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("data.bin");
+    fs::write(&file_path, "0123456789".repeat(5)).unwrap();
+    let path_str = file_path.to_str().unwrap();
+
+    let output = run_command(&["-s", "20", path_str]);
+    assert!(output.status.success());
+
+    let manifest_path = temp_dir.path().join("data.bin.manifest.json");
+    let manifest = fs::read_to_string(&manifest_path).unwrap();
+    assert!(manifest.contains("\"part_count\": 3"));
+    assert!(manifest.contains("\"total_size\": 50"));
+
+    // 分割ファイルが壊れていれば結合に失敗する
+    fs::write(temp_dir.path().join("data.bin.002"), "x".repeat(20)).unwrap();
+    fs::remove_file(&file_path).unwrap();
+    let output = run_command(&["-m", path_str]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("data.bin.002"));
+}
+
+#[test]
+fn test_split_stale_parts() {
+    // 古い分割ファイルはデフォルトでは警告だけで、--remove-staleで記録されたものだけ削除するテスト
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    let file_path = dir.join("data.bin");
+    fs::write(&file_path, "0123456789".repeat(5)).unwrap();
+    let path_str = file_path.to_str().unwrap();
+    assert!(run_command(&["-s", "10", path_str]).status.success());
+
+    fs::write(dir.join("data.bin.2024"), "unrelated").unwrap();
+    fs::write(&file_path, "0123456789".repeat(3)).unwrap();
+    let output = run_command(&["-s", "20", path_str]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("data.bin.003"));
+    assert!(stderr.contains("data.bin.2024"));
+    assert!(dir.join("data.bin.003").exists());
+
+    // 直前の分割のマニフェストに記録された .002 と .003 だけを削除する
+    assert!(run_command(&["-s", "10", path_str]).status.success());
+    fs::write(&file_path, "0123456789").unwrap();
+    let output = run_command(&["split", "-s", "10", "--remove-stale", path_str]);
+    assert!(output.status.success());
+    assert!(!dir.join("data.bin.002").exists());
+    assert!(!dir.join("data.bin.003").exists());
+    assert!(dir.join("data.bin.004").exists());
+    assert!(dir.join("data.bin.2024").exists());
+}
+
+#[test]
+fn test_split_without_manifest_option() {
+    // --no-manifestオプションのテスト
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("data.bin");
+    fs::write(&file_path, "abc").unwrap();
+
+    let output = run_command(&["-s", "2", "--no-manifest", file_path.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert!(temp_dir.path().join("data.bin.002").exists());
+    assert!(!temp_dir.path().join("data.bin.manifest.json").exists());
+}
+
+#[test]
+fn test_verify_command() {
+    // --verifyオプションで分割ファイルを検証するテスト
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("data.bin");
+    fs::write(&file_path, "0123456789".repeat(5)).unwrap();
+    let path_str = file_path.to_str().unwrap();
+
+    let output = run_command(&["-s", "20", path_str]);
+    assert!(output.status.success());
+
+    let output = run_command(&["--verify", path_str]);
+    assert!(
+        output.status.success(),
+        "検証コマンドが失敗しました: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // 切り詰められた分割ファイルと余分な分割ファイルを報告して失敗する
+    fs::write(temp_dir.path().join("data.bin.002"), "0123").unwrap();
+    fs::write(temp_dir.path().join("data.bin.004"), "stale").unwrap();
+    let output = run_command(&["--verify", path_str]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("切り詰め"));
+    assert!(stdout.contains("data.bin.002"));
+    assert!(stdout.contains("余分"));
+    assert!(stdout.contains("data.bin.004"));
+
+    // 元ファイルとの比較
+    let output = run_command(&["--verify", "--original", path_str, path_str]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_merge_with_missing_part() {
+    // 連番に欠けがある場合は結合に失敗し、--allow-partialで結合できるテスト
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("data.txt");
+    fs::write(temp_dir.path().join("data.txt.001"), "one ").unwrap();
+    fs::write(temp_dir.path().join("data.txt.003"), "three").unwrap();
+    let path_str = file_path.to_str().unwrap();
+
+    let output = run_command(&["-m", path_str]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("番号: 2"));
+
+    let output = run_command(&["-m", "--allow-partial", path_str]);
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), "one three");
+}
+
+#[test]
+fn test_container_merge_renamed_parts() {
+    // --containerで分割し、名前を変更した分割ファイルを--partsで結合するテスト
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("data.bin");
+    let test_data = "0123456789".repeat(5);
+    fs::write(&file_path, &test_data).unwrap();
+
+    let output = run_command(&["-s", "20", "--container", file_path.to_str().unwrap()]);
+    assert!(output.status.success());
+
+    // ダウンロードで名前が変わった想定
+    let downloads = temp_dir.path().join("downloads");
+    fs::create_dir(&downloads).unwrap();
+    for (from, to) in [
+        ("data.bin.001", "data.bin(1).001"),
+        ("data.bin.002", "part-b"),
+        ("data.bin.003", "part-a"),
+    ] {
+        fs::rename(temp_dir.path().join(from), downloads.join(to)).unwrap();
+    }
+
+    let restored = temp_dir.path().join("restored.bin");
+    let output = run_command(&[
+        "-m",
+        "--parts",
+        downloads.to_str().unwrap(),
+        restored.to_str().unwrap(),
+    ]);
+    assert!(
+        output.status.success(),
+        "結合コマンドが失敗しました: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(fs::read_to_string(&restored).unwrap(), test_data);
+}
+
+#[test]
+fn test_split_with_checksum_files() {
+    // --checksumオプションで作成したファイルを標準的なツールで確認できるテスト
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("data.bin");
+    fs::write(&file_path, "0123456789".repeat(5)).unwrap();
+
+    let output = run_command(&[
+        "-s",
+        "20",
+        "--checksum",
+        "sfv,md5",
+        "--checksum",
+        "sha256",
+        "--checksum-original",
+        file_path.to_str().unwrap(),
+    ]);
+    assert!(
+        output.status.success(),
+        "分割コマンドが失敗しました: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let sfv = fs::read_to_string(temp_dir.path().join("data.bin.sfv")).unwrap();
+    assert!(sfv.contains("data.bin.003 "));
+    let sums = fs::read_to_string(temp_dir.path().join("data.bin.SHA256SUMS")).unwrap();
+    assert_eq!(sums.lines().count(), 4);
+
+    // sha256sumがあれば記録を確認できることを確かめる
+    if let Ok(output) = Command::new("sha256sum")
+        .args(["-c", "data.bin.SHA256SUMS"])
+        .current_dir(temp_dir.path())
+        .output()
+    {
+        assert!(
+            output.status.success(),
+            "sha256sum -c が失敗しました: {:?}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+    }
+
+    // 未対応の形式はエラー
+    let output = run_command(&[
+        "-s",
+        "20",
+        "--checksum",
+        "sha1",
+        file_path.to_str().unwrap(),
+    ]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_merge_refuses_to_overwrite() {
+    // 既存のファイルは--forceを指定した場合だけ上書きするテスト
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("data.txt");
+    fs::write(&file_path, "original").unwrap();
+    fs::write(temp_dir.path().join("data.txt.001"), "merged").unwrap();
+    let path_str = file_path.to_str().unwrap();
+
+    let output = run_command(&["-m", path_str]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--force"));
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), "original");
+
+    let output = run_command(&["-m", "--force", path_str]);
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), "merged");
+}
+
+#[test]
+fn test_merge_to_output_and_stdout() {
+    // -oで出力先を指定し、-で標準出力に結合するテスト
+    let temp_dir = TempDir::new().unwrap();
+    let out_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("data.txt");
+    fs::write(temp_dir.path().join("data.txt.001"), "Hello ").unwrap();
+    fs::write(temp_dir.path().join("data.txt.002"), "World").unwrap();
+    let path_str = file_path.to_str().unwrap();
+
+    let output = run_command(&["-m", path_str, "-o", out_dir.path().to_str().unwrap()]);
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(out_dir.path().join("data.txt")).unwrap(),
+        "Hello World"
+    );
+    assert!(!file_path.exists());
+
+    // 標準出力には結合結果だけが書き込まれ、進捗は標準エラー出力に出る
+    let output = run_command(&["-m", path_str, "-o", "-"]);
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"Hello World");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("結合が完了しました"));
+    assert!(!file_path.exists());
+}
+
+#[test]
+fn test_split_from_stdin() {
+    // 標準入力を--prefixで指定した名前で分割するテスト
+    let temp_dir = TempDir::new().unwrap();
+    let prefix = temp_dir.path().join("dump.sql");
+    let prefix_str = prefix.to_str().unwrap();
+    let data = "0123456789".repeat(10);
+
+    let output =
+        run_command_with_stdin(&["-s", "30", "--prefix", prefix_str, "-"], data.as_bytes());
+    assert!(
+        output.status.success(),
+        "分割コマンドが失敗しました: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    for (suffix, len) in [("001", 30), ("002", 30), ("003", 30), ("004", 10)] {
+        let part = fs::read(temp_dir.path().join(format!("dump.sql.{suffix}"))).unwrap();
+        assert_eq!(part.len(), len);
+    }
+
+    // 結合すると元のデータに戻る
+    let output = run_command(&["-m", prefix_str, "-o", "-"]);
+    assert!(output.status.success());
+    assert_eq!(output.stdout, data.as_bytes());
+
+    // --prefixがなければ名前を決められない
+    let output = run_command_with_stdin(&["-s", "30", "-"], data.as_bytes());
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--prefix"));
+
+    // 分割数を事前に数える指定は使用できない
+    let output = run_command_with_stdin(
+        &["-s", "30", "--prefix", prefix_str, "--container", "-"],
+        data.as_bytes(),
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("使用できない"));
+}
+
+#[test]
+fn test_split_and_merge_with_output_dir() {
+    // --output-dirと--prefixで別の場所に分割し、同じ指定で結合するテスト
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("data.bin");
+    let output_dir = temp_dir.path().join("parts");
+    let file_str = file_path.to_str().unwrap();
+    let dir_str = output_dir.to_str().unwrap();
+    let data = "0123456789".repeat(5);
+    fs::write(&file_path, &data).unwrap();
+
+    let output = run_command(&[
+        "-s",
+        "20",
+        "--output-dir",
+        dir_str,
+        "--prefix",
+        "archive",
+        file_str,
+    ]);
+    assert!(
+        output.status.success(),
+        "分割コマンドが失敗しました: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    for (suffix, len) in [("001", 20), ("002", 20), ("003", 10)] {
+        let part = fs::read(output_dir.join(format!("archive.{suffix}"))).unwrap();
+        assert_eq!(part.len(), len);
+    }
+    assert!(!temp_dir.path().join("data.bin.001").exists());
+
+    // 同じ指定で分割ファイルを探して元のパスに結合する
+    fs::remove_file(&file_path).unwrap();
+    let output = run_command(&[
+        "-m",
+        "--output-dir",
+        dir_str,
+        "--prefix",
+        "archive",
+        file_str,
+    ]);
+    assert!(
+        output.status.success(),
+        "結合コマンドが失敗しました: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), data);
+}
+
+#[test]
+fn test_split_and_merge_with_name_template() {
+    // --name-templateと--start-indexで名前を付けて分割し、同じ指定で結合するテスト
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("photo.jpg");
+    let file_str = file_path.to_str().unwrap();
+    let data = "0123456789".repeat(5);
+    fs::write(&file_path, &data).unwrap();
+    let naming = [
+        "--name-template",
+        "{stem}.part{index:04}{ext}",
+        "--start-index",
+        "0",
+    ];
+
+    let mut args = vec!["-s", "20"];
+    args.extend(naming);
+    args.push(file_str);
+    let output = run_command(&args);
+    assert!(
+        output.status.success(),
+        "分割コマンドが失敗しました: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    for name in [
+        "photo.part0000.jpg",
+        "photo.part0001.jpg",
+        "photo.part0002.jpg",
+    ] {
+        assert!(temp_dir.path().join(name).exists(), "{name}");
+    }
+
+    fs::remove_file(&file_path).unwrap();
+    let mut args = vec!["-m"];
+    args.extend(naming);
+    args.push(file_str);
+    let output = run_command(&args);
+    assert!(
+        output.status.success(),
+        "結合コマンドが失敗しました: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), data);
+
+    // {index}を含まないテンプレートはエラー
+    let output = run_command(&["-s", "20", "--name-template", "{name}.part", file_str]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("テンプレートが不正"));
+}
+
+#[test]
+fn test_split_and_merge_with_alphabetic_suffix() {
+    // GNU split と同じ xaa, xab, ... という名前で分割し、同じ指定で結合するテスト
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("data.bin");
+    let prefix = temp_dir.path().join("x");
+    let file_str = file_path.to_str().unwrap();
+    let prefix_str = prefix.to_str().unwrap();
+    let data = "0123456789".repeat(3);
+    fs::write(&file_path, &data).unwrap();
+    let naming = ["--alphabetic", "--separator", "", "--prefix", prefix_str];
+
+    let mut args = vec!["-s", "10"];
+    args.extend(naming);
+    args.push(file_str);
+    let output = run_command(&args);
+    assert!(
+        output.status.success(),
+        "分割コマンドが失敗しました: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    for name in ["xaa", "xab", "xac"] {
+        assert!(temp_dir.path().join(name).exists(), "{name}");
+    }
+
+    fs::remove_file(&file_path).unwrap();
+    let mut args = vec!["-m"];
+    args.extend(naming);
+    args.push(file_str);
+    let output = run_command(&args);
+    assert!(
+        output.status.success(),
+        "結合コマンドが失敗しました: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), data);
+
+    // 文字数を固定すると接尾辞が足りなくなった時点でエラー
+    let output = run_command(&["-s", "1", "--alphabetic", "--suffix-length", "1", file_str]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("接尾辞が足りません"));
+}
+
+#[test]
+fn test_merge_detected_parts() {
+    // 7-Zip 形式の分割ファイルを命名規則の判定で結合するテスト
+    let temp_dir = TempDir::new().unwrap();
+    let data = ["alpha-", "beta-", "gamma"];
+    for (index, chunk) in data.iter().enumerate() {
+        fs::write(
+            temp_dir.path().join(format!("archive.7z.{:03}", index + 1)),
+            chunk,
+        )
+        .unwrap();
+    }
+    let first = temp_dir.path().join("archive.7z.001");
+
+    let output = run_command(&["-m", "--detect", first.to_str().unwrap()]);
+    assert!(
+        output.status.success(),
+        "結合コマンドが失敗しました: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("3 ファイル"));
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("archive.7z")).unwrap(),
+        data.concat()
+    );
+
+    // ディレクトリを指定して標準出力へ結合
+    let output = run_command(&[
+        "-m",
+        "--detect",
+        "-o",
+        "-",
+        temp_dir.path().to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), data.concat());
+
+    // 分割ファイルが見つからない場合はエラー
+    let missing = temp_dir.path().join("missing.bin");
+    let output = run_command(&["-m", "--detect", missing.to_str().unwrap()]);
+    assert!(!output.status.success());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_merge_restores_original_name_and_mode() {
+    // 元のファイル名とパーミッションを知らなくても復元できることをテスト
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("run.sh");
+    let prefix = temp_dir.path().join("upload");
+    fs::write(&file_path, "#!/bin/sh\necho hello\n").unwrap();
+    fs::set_permissions(&file_path, fs::Permissions::from_mode(0o750)).unwrap();
+
+    let output = run_command(&[
+        "-s",
+        "8",
+        "--prefix",
+        prefix.to_str().unwrap(),
+        file_path.to_str().unwrap(),
+    ]);
+    assert!(
+        output.status.success(),
+        "分割コマンドが失敗しました: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    fs::remove_file(&file_path).unwrap();
+
+    // 分割ファイルの名前だけで結合する
+    let output = run_command(&["-m", prefix.to_str().unwrap()]);
+    assert!(
+        output.status.success(),
+        "結合コマンドが失敗しました: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("run.sh"));
+    assert_eq!(
+        fs::read_to_string(&file_path).unwrap(),
+        "#!/bin/sh\necho hello\n"
+    );
+    let mode = fs::metadata(&file_path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o7777, 0o750);
+    assert!(!prefix.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_split_from_fifo() {
+    // 名前付きパイプを分割するテスト
+    let temp_dir = TempDir::new().unwrap();
+    let fifo = temp_dir.path().join("stream");
+    let Ok(status) = Command::new("mkfifo").arg(&fifo).status() else {
+        return;
+    };
+    assert!(status.success());
+
+    let data: Vec<u8> = (0..100_000).map(|i| (i % 251) as u8).collect();
+    let writer = {
+        let fifo = fifo.clone();
+        let data = data.clone();
+        std::thread::spawn(move || fs::write(fifo, data).unwrap())
+    };
+
+    let prefix = temp_dir.path().join("out.bin");
+    let output = run_command(&[
+        "-s",
+        "40000",
+        "--prefix",
+        prefix.to_str().unwrap(),
+        fifo.to_str().unwrap(),
+    ]);
+    writer.join().unwrap();
+    assert!(
+        output.status.success(),
+        "分割コマンドが失敗しました: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let parts: Vec<Vec<u8>> = ["001", "002", "003"]
+        .iter()
+        .map(|suffix| fs::read(temp_dir.path().join(format!("out.bin.{suffix}"))).unwrap())
+        .collect();
+    assert_eq!(parts[0].len(), 40000);
+    assert_eq!(parts[1].len(), 40000);
+    assert_eq!(parts.concat(), data);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_split_with_filter() {
+    // --filterで各分割ファイルをコマンドに渡すテスト
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("data.txt");
+    fs::write(&file_path, "0123456789".repeat(5)).unwrap();
+    let path_str = file_path.to_str().unwrap();
+
+    let output = run_command(&[
+        "-s",
+        "20",
+        "--filter",
+        r#"tr 0-9 a-j > "$FILE.$FILESPLIT_INDEX.$FILESPLIT_SIZE""#,
+        path_str,
+    ]);
+    assert!(
+        output.status.success(),
+        "分割コマンドが失敗しました: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!temp_dir.path().join("data.txt.001").exists());
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("data.txt.003.3.10")).unwrap(),
+        "abcdefghij"
+    );
+
+    // コマンドが失敗すると分割を中断する
+    let output = run_command(&["-s", "20", "--filter", "cat > /dev/null; exit 3", path_str]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("フィルターコマンドが失敗しました"));
+}
@@ -0,0 +1,68 @@
+use filesplit_rs::{Boundary, FileSplitError, Merger, SplitOptions, SplitStrategy, Splitter};
+use std::fs;
+use tempfile::TempDir;
+
+/// 区切り文字の直後で分割する外部クレート側の戦略
+struct DelimiterStrategy(u8);
+
+impl SplitStrategy for DelimiterStrategy {
+    fn next_boundary(&mut self, data: &[u8], _part_len: u64, _eof: bool) -> Boundary {
+        match data.iter().position(|&b| b == self.0) {
+            Some(pos) => Boundary::Cut(pos + 1),
+            None => Boundary::Continue(data.len()),
+        }
+    }
+}
+
+#[test]
+fn test_library_split_and_merge() {
+    // ビルダーAPIで分割・結合するテスト
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("data.bin");
+    let data: Vec<u8> = (0..=255).collect();
+    fs::write(&file_path, &data).unwrap();
+
+    let mut options = SplitOptions::default();
+    options.chunk_size = 100;
+    let mut splitter = Splitter::builder().options(options).build().unwrap();
+    splitter.split_file(&file_path).unwrap();
+    assert!(temp_dir.path().join("data.bin.003").exists());
+
+    fs::remove_file(&file_path).unwrap();
+    let merger = Merger::builder().build().unwrap();
+    merger.merge_files(&file_path).unwrap();
+    assert_eq!(fs::read(&file_path).unwrap(), data);
+}
+
+#[test]
+fn test_library_custom_strategy() {
+    // 外部で実装した戦略で分割するテスト
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("records.csv");
+    fs::write(&file_path, "a,1;b,2;c,3").unwrap();
+
+    let mut splitter = Splitter::builder()
+        .strategy(DelimiterStrategy(b';'))
+        .build()
+        .unwrap();
+    splitter.split_file(&file_path).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("records.csv.001")).unwrap(),
+        "a,1;"
+    );
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("records.csv.003")).unwrap(),
+        "c,3"
+    );
+}
+
+#[test]
+fn test_library_typed_error() {
+    // 型付きエラーが返されることをテスト
+    let temp_dir = TempDir::new().unwrap();
+    let merger = Merger::new();
+    let result = merger.merge_files(&temp_dir.path().join("missing.bin"));
+
+    assert!(matches!(result, Err(FileSplitError::NoSplitFiles(_))));
+}